@@ -0,0 +1,595 @@
+// ARIA admin CLI
+// Builds ARIA program transactions, signs them with local keypair files and submits them to
+// an RPC node, or prints them as base64 for air-gapped signing with `--sign-only`.
+
+mod transaction;
+
+use std::process::exit;
+
+use aria_token_client::{amount::ui_amount_to_amount, instruction as aria_instruction};
+use aria_token_interface::{
+    account::AccountType, fees::TransferFeeParams, metadata::MetadataField, roles::AllowanceChange,
+    roles::Role, TOKEN_DECIMALS,
+};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, hash::Hash, instruction::Instruction, program_pack::Pack,
+    pubkey::Pubkey, rent::Rent, signer::Signer, system_instruction, transaction::Transaction,
+};
+use spl_token_2022::{extension::ExtensionType, state::Mint};
+
+use transaction::{
+    build_message, decode_transaction, finish, load_signer, partial_sign, BlockhashSource,
+    CliResult,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "aria-cli",
+    version,
+    about = "Admin tool for the ARIA token program"
+)]
+struct Cli {
+    /// RPC URL of the cluster
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "https://api.devnet.solana.com"
+    )]
+    url: String,
+
+    /// Address of the deployed ARIA program
+    #[arg(long, global = true)]
+    program_id: Option<Pubkey>,
+
+    /// Signing authority: keypair file, or public key when signing offline
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<String>,
+
+    /// Fee payer: keypair file or public key [default: the --keypair signer]
+    #[arg(long, global = true)]
+    fee_payer: Option<String>,
+
+    /// Print the partially signed transaction as base64 instead of submitting it
+    #[arg(long, global = true, requires = "blockhash")]
+    sign_only: bool,
+
+    /// Blockhash to sign with; the nonce value when used with --nonce offline
+    #[arg(long, global = true)]
+    blockhash: Option<Hash>,
+
+    /// Durable nonce account used instead of a recent blockhash
+    #[arg(long, global = true)]
+    nonce: Option<Pubkey>,
+
+    /// Nonce authority: keypair file or public key [default: the --keypair signer]
+    #[arg(long, global = true, requires = "nonce")]
+    nonce_authority: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RoleArg {
+    Minter,
+    Pauser,
+    Rewarder,
+}
+
+impl From<RoleArg> for Role {
+    fn from(role: RoleArg) -> Self {
+        match role {
+            RoleArg::Minter => Role::Minter,
+            RoleArg::Pauser => Role::Pauser,
+            RoleArg::Rewarder => Role::Rewarder,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AccountTypeArg {
+    ProgramConfig,
+    RoleRegistry,
+    MinterAllowance,
+    ReferralConfig,
+    ReferralRecord,
+    ReferrerStats,
+    TreasuryConfig,
+    SpendRecord,
+    GovernanceConfig,
+    VoterRecord,
+    Proposal,
+    VoteRecord,
+    DelegateRecord,
+    Blocklist,
+    CheckpointRecord,
+}
+
+impl From<AccountTypeArg> for AccountType {
+    fn from(account_type: AccountTypeArg) -> Self {
+        match account_type {
+            AccountTypeArg::ProgramConfig => AccountType::ProgramConfig,
+            AccountTypeArg::RoleRegistry => AccountType::RoleRegistry,
+            AccountTypeArg::MinterAllowance => AccountType::MinterAllowance,
+            AccountTypeArg::ReferralConfig => AccountType::ReferralConfig,
+            AccountTypeArg::ReferralRecord => AccountType::ReferralRecord,
+            AccountTypeArg::ReferrerStats => AccountType::ReferrerStats,
+            AccountTypeArg::TreasuryConfig => AccountType::TreasuryConfig,
+            AccountTypeArg::SpendRecord => AccountType::SpendRecord,
+            AccountTypeArg::GovernanceConfig => AccountType::GovernanceConfig,
+            AccountTypeArg::VoterRecord => AccountType::VoterRecord,
+            AccountTypeArg::Proposal => AccountType::Proposal,
+            AccountTypeArg::VoteRecord => AccountType::VoteRecord,
+            AccountTypeArg::DelegateRecord => AccountType::DelegateRecord,
+            AccountTypeArg::Blocklist => AccountType::Blocklist,
+            AccountTypeArg::CheckpointRecord => AccountType::CheckpointRecord,
+        }
+    }
+}
+
+#[derive(Args)]
+struct MintArg {
+    /// The ARIA mint
+    #[arg(long)]
+    mint: Pubkey,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Initialize the ARIA mint and the program config, signed by the program's upgrade authority
+    InitializeMint {
+        /// Mint account: keypair file, or public key of an existing uninitialized mint account
+        #[arg(long)]
+        mint: String,
+        /// Create the mint account first, sized for the extensions used; needs the mint keypair
+        #[arg(long)]
+        create_mint: bool,
+        /// Token program owning the mint
+        #[arg(long, default_value_t = spl_token_2022::id())]
+        token_program: Pubkey,
+        /// Transfer fee in basis points (Token-2022 only)
+        #[arg(long, requires = "maximum_fee")]
+        transfer_fee_bps: Option<u16>,
+        /// Maximum fee per transfer, in ARI (Token-2022 only)
+        #[arg(long, requires = "transfer_fee_bps")]
+        maximum_fee: Option<String>,
+        /// Transfer hook program (Token-2022 only)
+        #[arg(long)]
+        transfer_hook_program: Option<Pubkey>,
+        /// Metadata URI
+        #[arg(long, default_value = "")]
+        metadata_uri: String,
+    },
+    /// Mint ARI, spending from the signer's minter allowance
+    Mint {
+        #[command(flatten)]
+        mint: MintArg,
+        /// Destination token account
+        #[arg(long)]
+        to: Pubkey,
+        /// Amount in ARI
+        amount: String,
+        #[arg(long, default_value_t = spl_token_2022::id())]
+        token_program: Pubkey,
+    },
+    /// Propose a new program authority
+    ProposeAuthority { new_authority: Pubkey },
+    /// Accept the authority transfer, signed by the proposed authority
+    AcceptAuthority,
+    /// Cancel the pending authority transfer
+    CancelAuthority,
+    /// Pause all state-changing instructions
+    Pause,
+    /// Resume normal operation
+    Unpause,
+    /// Grant a role
+    GrantRole {
+        #[arg(value_enum)]
+        role: RoleArg,
+        member: Pubkey,
+    },
+    /// Revoke a role
+    RevokeRole {
+        #[arg(value_enum)]
+        role: RoleArg,
+        member: Pubkey,
+    },
+    /// Set, increase or decrease a minter's allowance, in ARI
+    #[command(group(ArgGroup::new("change").required(true).args(["set", "increase", "decrease"])))]
+    MinterAllowance {
+        minter: Pubkey,
+        #[arg(long)]
+        set: Option<String>,
+        #[arg(long)]
+        increase: Option<String>,
+        #[arg(long)]
+        decrease: Option<String>,
+    },
+    /// Set the referral share in basis points
+    SetReferralShare {
+        #[command(flatten)]
+        mint: MintArg,
+        share_bps: u16,
+    },
+    /// Set the treasury approvers and threshold
+    ConfigureTreasury {
+        #[command(flatten)]
+        mint: MintArg,
+        #[arg(long = "approver", required = true)]
+        approvers: Vec<Pubkey>,
+        #[arg(long)]
+        threshold: u8,
+    },
+    /// Set the governance parameters
+    ConfigureGovernance {
+        #[command(flatten)]
+        mint: MintArg,
+        /// Voting period in seconds
+        #[arg(long)]
+        voting_period: i64,
+        #[arg(long)]
+        quorum_bps: u16,
+        #[arg(long)]
+        approval_threshold_bps: u16,
+        /// Voting power needed to create a proposal, in ARI
+        #[arg(long)]
+        min_proposal_power: String,
+    },
+    /// Update the Token-2022 transfer fee
+    UpdateTransferFee {
+        #[command(flatten)]
+        mint: MintArg,
+        #[arg(long)]
+        transfer_fee_bps: u16,
+        /// Maximum fee per transfer, in ARI
+        #[arg(long)]
+        maximum_fee: String,
+    },
+    /// Update a token metadata field: name, symbol, uri or, on Token-2022 mints, any other key
+    UpdateMetadata {
+        #[command(flatten)]
+        mint: MintArg,
+        field: String,
+        value: String,
+        /// Token program owning the mint; legacy SPL Token mints update their Metaplex metadata
+        #[arg(long, default_value_t = spl_token_2022::id())]
+        token_program: Pubkey,
+    },
+    /// Withdraw withheld transfer fees to the treasury vault's token account
+    WithdrawWithheldFees {
+        #[command(flatten)]
+        mint: MintArg,
+        #[arg(long)]
+        treasury_token_account: Pubkey,
+    },
+    /// Hand the blocklist to a new compliance authority
+    SetComplianceAuthority {
+        #[command(flatten)]
+        mint: MintArg,
+        new_authority: Pubkey,
+    },
+    /// Block transfers from and to an owner
+    BlockAddress {
+        #[command(flatten)]
+        mint: MintArg,
+        address: Pubkey,
+    },
+    /// Unblock an owner
+    UnblockAddress {
+        #[command(flatten)]
+        mint: MintArg,
+        address: Pubkey,
+    },
+    /// Upgrade a program account with an older layout, paid by --keypair
+    MigrateAccount {
+        account: Pubkey,
+        #[arg(long, value_enum)]
+        account_type: AccountTypeArg,
+    },
+    /// Add the signatures of --keypair to a base64 transaction
+    SignTransaction { transaction: String },
+    /// Submit a fully signed base64 transaction
+    SendTransaction { transaction: String },
+}
+
+fn default_keypair_path() -> String {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    format!("{}/.config/solana/id.json", home)
+}
+
+fn ui_amount(amount: &str) -> CliResult<u64> {
+    ui_amount_to_amount(amount, TOKEN_DECIMALS)
+        .map_err(|err| format!("Invalid amount {}: {}", amount, err).into())
+}
+
+fn metadata_field(field: &str) -> MetadataField {
+    match field {
+        "name" => MetadataField::Name,
+        "symbol" => MetadataField::Symbol,
+        "uri" => MetadataField::Uri,
+        key => MetadataField::Key(key.to_string()),
+    }
+}
+
+// System instruction creating a mint account sized for the extensions InitializeMint enables
+fn create_mint_account(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    transfer_fee: bool,
+    transfer_hook: bool,
+) -> CliResult<Instruction> {
+    let space = if token_program == &spl_token_2022::id() {
+        let mut extensions = vec![ExtensionType::MetadataPointer];
+        if transfer_fee {
+            extensions.push(ExtensionType::TransferFeeConfig);
+        }
+        if transfer_hook {
+            extensions.push(ExtensionType::TransferHook);
+        }
+        ExtensionType::try_calculate_account_len::<Mint>(&extensions)?
+    } else {
+        spl_token::state::Mint::LEN
+    };
+    // Mainnet, devnet and testnet all use the default rent parameters
+    let lamports = Rent::default().minimum_balance(space);
+    Ok(system_instruction::create_account(
+        payer,
+        mint,
+        lamports,
+        space as u64,
+        token_program,
+    ))
+}
+
+fn run(cli: Cli) -> CliResult<()> {
+    let rpc_client = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+    let keypair = load_signer(&cli.keypair.clone().unwrap_or_else(default_keypair_path))?;
+
+    // Transactions built elsewhere only need the keypair's signature or a submission
+    match &cli.command {
+        Command::SignTransaction { transaction } => {
+            let mut transaction = decode_transaction(transaction)?;
+            partial_sign(&mut transaction, &[keypair.as_ref()])?;
+            return finish(&rpc_client, &transaction, true);
+        }
+        Command::SendTransaction { transaction } => {
+            return finish(&rpc_client, &decode_transaction(transaction)?, false);
+        }
+        _ => {}
+    }
+
+    let program_id = &cli.program_id.ok_or("--program-id is required")?;
+    let fee_payer = match &cli.fee_payer {
+        Some(source) => Some(load_signer(source)?),
+        None => None,
+    };
+    let nonce_authority = match &cli.nonce_authority {
+        Some(source) => Some(load_signer(source)?),
+        None => None,
+    };
+    let authority = keypair.pubkey();
+    let authority = &authority;
+    let mut extra_signers: Vec<Box<dyn Signer>> = Vec::new();
+
+    let instructions = match cli.command {
+        Command::InitializeMint {
+            mint,
+            create_mint,
+            token_program,
+            transfer_fee_bps,
+            maximum_fee,
+            transfer_hook_program,
+            metadata_uri,
+        } => {
+            let mint_signer = load_signer(&mint)?;
+            let mint = mint_signer.pubkey();
+            let transfer_fee = match (transfer_fee_bps, maximum_fee) {
+                (Some(transfer_fee_basis_points), Some(maximum_fee)) => Some(TransferFeeParams {
+                    transfer_fee_basis_points,
+                    maximum_fee: ui_amount(&maximum_fee)?,
+                }),
+                _ => None,
+            };
+            let mut instructions = Vec::new();
+            if create_mint {
+                instructions.push(create_mint_account(
+                    authority,
+                    &mint,
+                    &token_program,
+                    transfer_fee.is_some(),
+                    transfer_hook_program.is_some(),
+                )?);
+                extra_signers.push(mint_signer);
+            }
+            instructions.push(aria_instruction::initialize_mint(
+                program_id,
+                authority,
+                &mint,
+                &token_program,
+                transfer_fee,
+                transfer_hook_program,
+                metadata_uri,
+            ));
+            instructions
+        }
+        Command::Mint {
+            mint,
+            to,
+            amount,
+            token_program,
+        } => vec![aria_instruction::mint_tokens(
+            program_id,
+            authority,
+            &mint.mint,
+            &to,
+            &token_program,
+            ui_amount(&amount)?,
+        )],
+        Command::ProposeAuthority { new_authority } => {
+            vec![aria_instruction::propose_authority_transfer(
+                program_id,
+                authority,
+                &new_authority,
+            )]
+        }
+        Command::AcceptAuthority => {
+            vec![aria_instruction::accept_authority_transfer(
+                program_id, authority,
+            )]
+        }
+        Command::CancelAuthority => {
+            vec![aria_instruction::cancel_authority_transfer(
+                program_id, authority,
+            )]
+        }
+        Command::Pause => vec![aria_instruction::pause(program_id, authority)],
+        Command::Unpause => vec![aria_instruction::unpause(program_id, authority)],
+        Command::GrantRole { role, member } => vec![aria_instruction::grant_role(
+            program_id,
+            authority,
+            role.into(),
+            &member,
+        )],
+        Command::RevokeRole { role, member } => vec![aria_instruction::revoke_role(
+            program_id,
+            authority,
+            role.into(),
+            &member,
+        )],
+        Command::MinterAllowance {
+            minter,
+            set,
+            increase,
+            decrease,
+        } => {
+            let change = match (set, increase, decrease) {
+                (Some(amount), _, _) => AllowanceChange::Set(ui_amount(&amount)?),
+                (_, Some(amount), _) => AllowanceChange::Increase(ui_amount(&amount)?),
+                (_, _, Some(amount)) => AllowanceChange::Decrease(ui_amount(&amount)?),
+                _ => unreachable!("clap requires one allowance change"),
+            };
+            vec![aria_instruction::update_minter_allowance(
+                program_id, authority, &minter, change,
+            )]
+        }
+        Command::SetReferralShare { mint, share_bps } => {
+            vec![aria_instruction::set_referral_share(
+                program_id, authority, &mint.mint, share_bps,
+            )]
+        }
+        Command::ConfigureTreasury {
+            mint,
+            approvers,
+            threshold,
+        } => vec![aria_instruction::configure_treasury(
+            program_id, authority, &mint.mint, approvers, threshold,
+        )],
+        Command::ConfigureGovernance {
+            mint,
+            voting_period,
+            quorum_bps,
+            approval_threshold_bps,
+            min_proposal_power,
+        } => vec![aria_instruction::configure_governance(
+            program_id,
+            authority,
+            &mint.mint,
+            voting_period,
+            quorum_bps,
+            approval_threshold_bps,
+            ui_amount(&min_proposal_power)?,
+        )],
+        Command::UpdateTransferFee {
+            mint,
+            transfer_fee_bps,
+            maximum_fee,
+        } => vec![aria_instruction::update_transfer_fee(
+            program_id,
+            authority,
+            &mint.mint,
+            TransferFeeParams {
+                transfer_fee_basis_points: transfer_fee_bps,
+                maximum_fee: ui_amount(&maximum_fee)?,
+            },
+        )],
+        Command::UpdateMetadata {
+            mint,
+            field,
+            value,
+            token_program,
+        } => {
+            vec![aria_instruction::update_metadata(
+                program_id,
+                authority,
+                &mint.mint,
+                &token_program,
+                metadata_field(&field),
+                value,
+            )]
+        }
+        Command::WithdrawWithheldFees {
+            mint,
+            treasury_token_account,
+        } => vec![aria_instruction::withdraw_withheld_fees(
+            program_id,
+            &mint.mint,
+            &treasury_token_account,
+        )],
+        Command::SetComplianceAuthority {
+            mint,
+            new_authority,
+        } => vec![aria_instruction::set_compliance_authority(
+            program_id,
+            authority,
+            &mint.mint,
+            &new_authority,
+        )],
+        Command::BlockAddress { mint, address } => vec![aria_instruction::block_address(
+            program_id, authority, &mint.mint, &address,
+        )],
+        Command::UnblockAddress { mint, address } => vec![aria_instruction::unblock_address(
+            program_id, authority, &mint.mint, &address,
+        )],
+        Command::MigrateAccount {
+            account,
+            account_type,
+        } => vec![aria_instruction::migrate_account(
+            program_id,
+            authority,
+            &account,
+            account_type.into(),
+        )],
+        Command::SignTransaction { .. } | Command::SendTransaction { .. } => unreachable!(),
+    };
+
+    let fee_payer = fee_payer.as_deref().unwrap_or(keypair.as_ref());
+    let nonce_authority = nonce_authority.as_deref().unwrap_or(keypair.as_ref());
+    let blockhash_source = BlockhashSource {
+        blockhash: cli.blockhash,
+        nonce: cli.nonce.map(|nonce| (nonce, nonce_authority.pubkey())),
+    };
+    let blockhash = blockhash_source.resolve(&rpc_client)?;
+    let message = build_message(
+        &instructions,
+        &fee_payer.pubkey(),
+        &blockhash_source,
+        blockhash,
+    );
+
+    let mut transaction = Transaction::new_unsigned(message);
+    let mut signers: Vec<&dyn Signer> = vec![fee_payer, keypair.as_ref(), nonce_authority];
+    signers.extend(extra_signers.iter().map(|signer| signer.as_ref()));
+    partial_sign(&mut transaction, &signers)?;
+    finish(&rpc_client, &transaction, cli.sign_only)
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("Error: {}", err);
+        exit(1);
+    }
+}
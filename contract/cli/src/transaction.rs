@@ -0,0 +1,195 @@
+// Transaction building, signing and submission
+// Signers are keypair files or bare public keys. A bare public key signs nothing, so the
+// transaction can be built and partially signed on one machine and completed elsewhere.
+// Transactions go to the RPC node, or with `--sign-only` are printed as base64 for the next
+// signer. A durable nonce replaces the recent blockhash, so a signing ceremony is not limited
+// to the lifetime of a blockhash.
+
+use std::error::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_nonce_utils::{data_from_account, get_account_with_commitment};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Signature},
+    signer::{null_signer::NullSigner, Signer},
+    transaction::Transaction,
+};
+
+pub type CliResult<T> = Result<T, Box<dyn Error>>;
+
+/// Load a signer from a keypair file, or a public key whose signature is added later
+pub fn load_signer(source: &str) -> CliResult<Box<dyn Signer>> {
+    if !Path::new(source).exists() {
+        if let Ok(pubkey) = Pubkey::from_str(source) {
+            return Ok(Box::new(NullSigner::new(&pubkey)));
+        }
+    }
+    let keypair = read_keypair_file(source)
+        .map_err(|err| format!("Cannot read keypair file {}: {}", source, err))?;
+    Ok(Box::new(keypair))
+}
+
+/// Where the transaction's blockhash comes from
+pub struct BlockhashSource {
+    /// Blockhash given on the command line; the nonce value when a nonce is used offline
+    pub blockhash: Option<Hash>,
+    /// Durable nonce account and its authority
+    pub nonce: Option<(Pubkey, Pubkey)>,
+}
+
+impl BlockhashSource {
+    pub fn resolve(&self, rpc_client: &RpcClient) -> CliResult<Hash> {
+        if let Some(blockhash) = self.blockhash {
+            return Ok(blockhash);
+        }
+        match &self.nonce {
+            Some((nonce, _)) => {
+                let account =
+                    get_account_with_commitment(rpc_client, nonce, CommitmentConfig::confirmed())?;
+                Ok(data_from_account(&account)?.blockhash())
+            }
+            None => Ok(rpc_client.get_latest_blockhash()?),
+        }
+    }
+}
+
+/// Build the message, advancing the durable nonce first when one is used
+pub fn build_message(
+    instructions: &[Instruction],
+    fee_payer: &Pubkey,
+    blockhash_source: &BlockhashSource,
+    blockhash: Hash,
+) -> Message {
+    let mut message = match &blockhash_source.nonce {
+        Some((nonce, nonce_authority)) => Message::new_with_nonce(
+            instructions.to_vec(),
+            Some(fee_payer),
+            nonce,
+            nonce_authority,
+        ),
+        None => Message::new(instructions, Some(fee_payer)),
+    };
+    message.recent_blockhash = blockhash;
+    message
+}
+
+/// Sign with every signer that holds a key; signers without a key leave their slot empty
+pub fn partial_sign(transaction: &mut Transaction, signers: &[&dyn Signer]) -> CliResult<()> {
+    let required = &transaction.message.account_keys
+        [..transaction.message.header.num_required_signatures as usize];
+    let signers: Vec<&dyn Signer> = signers
+        .iter()
+        .filter(|signer| required.contains(&signer.pubkey()))
+        .copied()
+        .collect();
+    let positions = transaction.get_signing_keypair_positions(
+        &signers
+            .iter()
+            .map(|signer| signer.pubkey())
+            .collect::<Vec<_>>(),
+    )?;
+    for (signer, position) in signers.iter().zip(positions) {
+        let position = position.ok_or("Signer is not part of the transaction")?;
+        let signature = signer.try_sign_message(&transaction.message_data())?;
+        // NullSigner returns the default signature, which keeps the slot empty
+        if signature != Signature::default() {
+            transaction.signatures[position] = signature;
+        }
+    }
+    Ok(())
+}
+
+/// Public keys whose signatures are still missing
+pub fn missing_signers(transaction: &Transaction) -> Vec<Pubkey> {
+    transaction
+        .signatures
+        .iter()
+        .zip(transaction.message.account_keys.iter())
+        .filter(|(signature, _)| **signature == Signature::default())
+        .map(|(_, key)| *key)
+        .collect()
+}
+
+pub fn encode_transaction(transaction: &Transaction) -> CliResult<String> {
+    Ok(STANDARD.encode(bincode::serialize(transaction)?))
+}
+
+pub fn decode_transaction(encoded: &str) -> CliResult<Transaction> {
+    let bytes = STANDARD.decode(encoded.trim())?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+/// Print the transaction as base64 for the next signer, or submit it once fully signed
+pub fn finish(rpc_client: &RpcClient, transaction: &Transaction, sign_only: bool) -> CliResult<()> {
+    let missing = missing_signers(transaction);
+    if sign_only {
+        println!("{}", encode_transaction(transaction)?);
+        for pubkey in missing {
+            eprintln!("Missing signature: {}", pubkey);
+        }
+        return Ok(());
+    }
+    if !missing.is_empty() {
+        let missing: Vec<String> = missing.iter().map(|pubkey| pubkey.to_string()).collect();
+        return Err(format!(
+            "Missing signatures from {}; use --sign-only to collect them offline",
+            missing.join(", ")
+        )
+        .into());
+    }
+    let signature = rpc_client.send_and_confirm_transaction(transaction)?;
+    println!("Signature: {}", signature);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{signature::Keypair, system_instruction};
+
+    #[test]
+    fn test_offline_signing_with_nonce() {
+        let fee_payer = Keypair::new();
+        let authority = Keypair::new();
+        let nonce = Pubkey::new_unique();
+        let nonce_value = Hash::new_unique();
+        let source = BlockhashSource {
+            blockhash: Some(nonce_value),
+            nonce: Some((nonce, fee_payer.pubkey())),
+        };
+        let instruction =
+            system_instruction::transfer(&authority.pubkey(), &Pubkey::new_unique(), 1);
+
+        let message = build_message(&[instruction], &fee_payer.pubkey(), &source, nonce_value);
+        assert_eq!(message.instructions.len(), 2);
+        assert_eq!(message.recent_blockhash, nonce_value);
+
+        // The fee payer signs first, the authority completes the transaction later
+        let mut transaction = Transaction::new_unsigned(message);
+        let offline_authority = NullSigner::new(&authority.pubkey());
+        partial_sign(&mut transaction, &[&fee_payer, &offline_authority]).unwrap();
+        assert_eq!(missing_signers(&transaction), vec![authority.pubkey()]);
+
+        let mut transaction =
+            decode_transaction(&encode_transaction(&transaction).unwrap()).unwrap();
+        partial_sign(&mut transaction, &[&authority]).unwrap();
+        assert!(missing_signers(&transaction).is_empty());
+        transaction.verify().unwrap();
+    }
+
+    #[test]
+    fn test_load_signer_pubkey() {
+        let pubkey = Pubkey::new_unique();
+        let signer = load_signer(&pubkey.to_string()).unwrap();
+        assert_eq!(signer.pubkey(), pubkey);
+        assert!(load_signer("/nonexistent/keypair.json").is_err());
+    }
+}
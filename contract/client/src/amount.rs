@@ -0,0 +1,143 @@
+// UI amounts
+// Token amounts in instructions are base units. These helpers convert decimal strings such as
+// "1.5" to base units and back using the mint decimals, without going through floating point.
+
+use solana_program::program_error::ProgramError;
+use spl_token_2022::{extension::StateWithExtensions, state::Mint};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountError {
+    #[error("Invalid amount")]
+    InvalidAmount,
+
+    #[error("Amount has more decimal places than the mint")]
+    TooManyDecimals,
+
+    #[error("Amount overflows u64")]
+    Overflow,
+}
+
+/// Decimals of an SPL Token or Token-2022 mint account
+pub fn mint_decimals(mint_data: &[u8]) -> Result<u8, ProgramError> {
+    Ok(StateWithExtensions::<Mint>::unpack(mint_data)?
+        .base
+        .decimals)
+}
+
+/// Convert a UI amount such as "1.5" to base units
+pub fn ui_amount_to_amount(ui_amount: &str, decimals: u8) -> Result<u64, AmountError> {
+    let (whole, fraction) = match ui_amount.trim().split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (ui_amount.trim(), ""),
+    };
+    let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(AmountError::InvalidAmount);
+    }
+    // Trailing zeros never change the amount
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        return Err(AmountError::TooManyDecimals);
+    }
+
+    let scale = 10u64
+        .checked_pow(decimals as u32)
+        .ok_or(AmountError::Overflow)?;
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        whole.parse::<u64>().map_err(|_| AmountError::Overflow)?
+    };
+    let fraction = if fraction.is_empty() {
+        0
+    } else {
+        // `fraction` has at most `decimals` digits, so it is below `scale`
+        fraction.parse::<u64>().map_err(|_| AmountError::Overflow)?
+            * 10u64.pow((decimals as usize - fraction.len()) as u32)
+    };
+    whole
+        .checked_mul(scale)
+        .and_then(|amount| amount.checked_add(fraction))
+        .ok_or(AmountError::Overflow)
+}
+
+/// Convert base units to a UI amount, without trailing zeros
+pub fn amount_to_ui_amount(amount: u64, decimals: u8) -> String {
+    let digits = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aria_token_interface::TOKEN_DECIMALS;
+    use solana_program::program_pack::Pack;
+
+    #[test]
+    fn test_ui_amount_round_trip() {
+        let decimals = TOKEN_DECIMALS;
+        assert_eq!(ui_amount_to_amount("1", decimals), Ok(1_000_000_000));
+        assert_eq!(ui_amount_to_amount("1.5", decimals), Ok(1_500_000_000));
+        assert_eq!(ui_amount_to_amount(".25", decimals), Ok(250_000_000));
+        assert_eq!(ui_amount_to_amount("0.000000001", decimals), Ok(1));
+        assert_eq!(
+            ui_amount_to_amount("2.1000000000", decimals),
+            Ok(2_100_000_000)
+        );
+        assert_eq!(
+            ui_amount_to_amount("100000000", decimals),
+            Ok(aria_token_interface::TOTAL_SUPPLY)
+        );
+
+        assert_eq!(amount_to_ui_amount(1_500_000_000, decimals), "1.5");
+        assert_eq!(amount_to_ui_amount(1, decimals), "0.000000001");
+        assert_eq!(amount_to_ui_amount(0, decimals), "0");
+        assert_eq!(amount_to_ui_amount(7, 0), "7");
+        for amount in [0, 1, 999_999_999, 1_000_000_000, u64::MAX] {
+            let ui_amount = amount_to_ui_amount(amount, decimals);
+            assert_eq!(ui_amount_to_amount(&ui_amount, decimals), Ok(amount));
+        }
+    }
+
+    #[test]
+    fn test_ui_amount_errors() {
+        let decimals = TOKEN_DECIMALS;
+        for invalid in ["", ".", "-1", "1e9", "1.2.3", "abc", "1,5"] {
+            assert_eq!(
+                ui_amount_to_amount(invalid, decimals),
+                Err(AmountError::InvalidAmount),
+                "{:?}",
+                invalid
+            );
+        }
+        assert_eq!(
+            ui_amount_to_amount("0.0000000001", decimals),
+            Err(AmountError::TooManyDecimals)
+        );
+        assert_eq!(
+            ui_amount_to_amount("18446744074", decimals),
+            Err(AmountError::Overflow)
+        );
+        assert_eq!(ui_amount_to_amount("1", 20), Err(AmountError::Overflow));
+    }
+
+    #[test]
+    fn test_mint_decimals() {
+        let mint = Mint {
+            decimals: TOKEN_DECIMALS,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(mint, &mut data).unwrap();
+        assert_eq!(mint_decimals(&data), Ok(TOKEN_DECIMALS));
+        assert!(mint_decimals(&data[..10]).is_err());
+    }
+}
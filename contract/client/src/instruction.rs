@@ -0,0 +1,1108 @@
+// Instruction builders
+// One function per `ARIAInstruction` variant. PDAs are derived from `program_id` and the mint,
+// so callers only pass wallets, token accounts and arguments. For mints with a transfer hook,
+// append the hook's extra accounts to `Instruction::accounts` of the instructions that move
+// tokens (PayWithReferral, ExecuteSpend, DepositGoverningTokens, WithdrawGoverningTokens).
+
+use aria_token_interface::account::AccountType;
+use aria_token_interface::checkpoint::find_checkpoint_address;
+use aria_token_interface::fees::TransferFeeParams;
+use aria_token_interface::governance::{
+    find_delegate_record_address, find_governance_address, find_governance_authority_address,
+    find_governance_vault_address, find_proposal_address, find_vote_record_address,
+    find_voter_record_address, ProposalInstruction, Vote,
+};
+use aria_token_interface::instruction::{
+    ARIAInstruction, ApproveSpendArgs, BlockAddressArgs, CastVoteArgs, ConfigureGovernanceArgs,
+    ConfigureTreasuryArgs, CreateProposalArgs, DepositGoverningTokensArgs, ExecuteSpendArgs,
+    GetNextMintTimeV2Args, GetRemainingMintableArgs, GrantRoleArgs, InitializeMintArgs,
+    InitializeTransferHookArgs, MigrateAccountArgs, MintTokensArgs, PayWithReferralArgs,
+    ProposeAuthorityTransferV2Args, ProposeSpendArgs, RegisterReferrerArgs, RevokeRoleArgs,
+    SetComplianceAuthorityArgs, SetDelegateArgs, SetReferralShareArgs, UnblockAddressArgs,
+    UpdateMetadataArgs, UpdateMinterAllowanceArgs, UpdateTransferFeeArgs,
+    WithdrawGoverningTokensArgs,
+};
+use aria_token_interface::metadata::{
+    find_metaplex_metadata_address, MetadataField, METAPLEX_PROGRAM_ID,
+};
+use aria_token_interface::referral::{
+    find_referral_config_address, find_referral_record_address, find_referrer_stats_address,
+};
+use aria_token_interface::roles::{
+    find_minter_allowance_address, find_role_registry_address, AllowanceChange, Role,
+};
+use aria_token_interface::transfer_hook::{
+    extra_account_metas, find_blocklist_address, find_extra_account_metas_address,
+};
+use aria_token_interface::treasury::{
+    find_spend_address, find_treasury_address, find_treasury_vault_address, TreasuryAsset,
+};
+use aria_token_interface::{
+    find_config_address, find_mint_authority_address, find_program_data_address,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use spl_tlv_account_resolution::account::ExtraAccountMeta;
+use spl_transfer_hook_interface::instruction as transfer_hook_instruction;
+
+fn build(
+    program_id: &Pubkey,
+    instruction: ARIAInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction.pack(),
+    }
+}
+
+fn config(program_id: &Pubkey, is_writable: bool) -> AccountMeta {
+    let (address, _) = find_config_address(program_id);
+    if is_writable {
+        AccountMeta::new(address, false)
+    } else {
+        AccountMeta::new_readonly(address, false)
+    }
+}
+
+fn mint_authority(program_id: &Pubkey, mint: &Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(find_mint_authority_address(program_id, mint).0, false)
+}
+
+fn role_registry(program_id: &Pubkey, is_writable: bool) -> AccountMeta {
+    let (address, _) = find_role_registry_address(program_id);
+    if is_writable {
+        AccountMeta::new(address, false)
+    } else {
+        AccountMeta::new_readonly(address, false)
+    }
+}
+
+fn readonly(address: Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(address, false)
+}
+
+// Metaplex metadata account and program, only used for legacy SPL Token mints
+fn metaplex_accounts(mint: &Pubkey, token_program_id: &Pubkey) -> Vec<AccountMeta> {
+    if *token_program_id == spl_token_2022::id() {
+        return vec![];
+    }
+    vec![
+        AccountMeta::new(find_metaplex_metadata_address(mint).0, false),
+        readonly(METAPLEX_PROGRAM_ID),
+    ]
+}
+
+/// Create `InitializeMint`. `token_program_id` is SPL Token or Token-2022, matching the owner
+/// of `mint`. `authority` must be the program's upgrade authority.
+pub fn initialize_mint(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+    transfer_fee: Option<TransferFeeParams>,
+    transfer_hook_program: Option<Pubkey>,
+    metadata_uri: String,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::InitializeMint(InitializeMintArgs {
+            transfer_fee,
+            transfer_hook_program,
+            metadata_uri,
+        }),
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*mint, false),
+            readonly(sysvar::rent::id()),
+            readonly(*token_program_id),
+            config(program_id, true),
+            mint_authority(program_id, mint),
+            readonly(system_program::id()),
+            role_registry(program_id, true),
+            readonly(find_program_data_address(program_id).0),
+        ]
+        .into_iter()
+        .chain(metaplex_accounts(mint, token_program_id))
+        .collect(),
+    )
+}
+
+/// Create `MintTokens`, spending from `minter`'s allowance
+pub fn mint_tokens(
+    program_id: &Pubkey,
+    minter: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    token_program_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::MintTokens(MintTokensArgs { amount }),
+        vec![
+            AccountMeta::new_readonly(*minter, true),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*destination, false),
+            readonly(*token_program_id),
+            readonly(sysvar::clock::id()),
+            config(program_id, true),
+            mint_authority(program_id, mint),
+            role_registry(program_id, false),
+            AccountMeta::new(find_minter_allowance_address(program_id, minter).0, false),
+        ],
+    )
+}
+
+/// Create `ProposeAuthorityTransferV2`
+pub fn propose_authority_transfer(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::ProposeAuthorityTransferV2(ProposeAuthorityTransferV2Args {
+            new_authority: *new_authority,
+        }),
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            config(program_id, true),
+            readonly(*new_authority),
+            readonly(sysvar::clock::id()),
+        ],
+    )
+}
+
+/// Create `AcceptAuthorityTransferV2`, signed by the proposed authority
+pub fn accept_authority_transfer(program_id: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::AcceptAuthorityTransferV2,
+        vec![
+            AccountMeta::new_readonly(*new_authority, true),
+            config(program_id, true),
+            readonly(sysvar::clock::id()),
+        ],
+    )
+}
+
+/// Create `CancelAuthorityTransferV2`
+pub fn cancel_authority_transfer(program_id: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::CancelAuthorityTransferV2,
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            config(program_id, true),
+        ],
+    )
+}
+
+/// Create `SetReferralShare`, signed by a rewarder
+pub fn set_referral_share(
+    program_id: &Pubkey,
+    rewarder: &Pubkey,
+    mint: &Pubkey,
+    share_bps: u16,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::SetReferralShare(SetReferralShareArgs { share_bps }),
+        vec![
+            AccountMeta::new(*rewarder, true),
+            config(program_id, false),
+            AccountMeta::new(find_referral_config_address(program_id, mint).0, false),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+            role_registry(program_id, false),
+        ],
+    )
+}
+
+/// Create `RegisterReferrer`
+pub fn register_referrer(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    mint: &Pubkey,
+    referrer: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::RegisterReferrer(RegisterReferrerArgs {
+            referrer: *referrer,
+        }),
+        vec![
+            AccountMeta::new(*user, true),
+            readonly(*mint),
+            AccountMeta::new(
+                find_referral_record_address(program_id, mint, user).0,
+                false,
+            ),
+            AccountMeta::new(
+                find_referrer_stats_address(program_id, mint, referrer).0,
+                false,
+            ),
+            readonly(sysvar::clock::id()),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `PayWithReferral`. `referrer` is the payer's registered referrer.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_with_referral(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    payer_token_account: &Pubkey,
+    recipient_token_account: &Pubkey,
+    referrer: &Pubkey,
+    referrer_token_account: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::PayWithReferral(PayWithReferralArgs { amount }),
+        vec![
+            AccountMeta::new_readonly(*payer, true),
+            AccountMeta::new(*payer_token_account, false),
+            AccountMeta::new(*recipient_token_account, false),
+            AccountMeta::new(*referrer_token_account, false),
+            readonly(*mint),
+            readonly(find_referral_config_address(program_id, mint).0),
+            readonly(find_referral_record_address(program_id, mint, payer).0),
+            AccountMeta::new(
+                find_referrer_stats_address(program_id, mint, referrer).0,
+                false,
+            ),
+            readonly(*token_program_id),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `ConfigureTreasury`
+pub fn configure_treasury(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    approvers: Vec<Pubkey>,
+    threshold: u8,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::ConfigureTreasury(ConfigureTreasuryArgs {
+            approvers,
+            threshold,
+        }),
+        vec![
+            AccountMeta::new(*authority, true),
+            config(program_id, false),
+            AccountMeta::new(find_treasury_address(program_id, mint).0, false),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+        ],
+    )
+}
+
+/// Create `ProposeSpend`. `index` is the treasury's current `spend_count`.
+pub fn propose_spend(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    mint: &Pubkey,
+    index: u64,
+    asset: TreasuryAsset,
+    recipient: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::ProposeSpend(ProposeSpendArgs {
+            asset,
+            recipient: *recipient,
+            amount,
+        }),
+        vec![
+            AccountMeta::new(*proposer, true),
+            readonly(*mint),
+            AccountMeta::new(find_treasury_address(program_id, mint).0, false),
+            AccountMeta::new(find_spend_address(program_id, mint, index).0, false),
+            readonly(sysvar::clock::id()),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `ApproveSpend`
+pub fn approve_spend(
+    program_id: &Pubkey,
+    approver: &Pubkey,
+    mint: &Pubkey,
+    index: u64,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::ApproveSpend(ApproveSpendArgs { index }),
+        vec![
+            AccountMeta::new_readonly(*approver, true),
+            readonly(*mint),
+            readonly(find_treasury_address(program_id, mint).0),
+            AccountMeta::new(find_spend_address(program_id, mint, index).0, false),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `ExecuteSpend`. `recipient` is a wallet for SOL spends and a token account for token
+/// spends; `vault_token_account` is only read for token spends and may be `None` for SOL.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_spend(
+    program_id: &Pubkey,
+    executor: &Pubkey,
+    mint: &Pubkey,
+    index: u64,
+    vault_token_account: Option<&Pubkey>,
+    recipient: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    let (vault, _) = find_treasury_vault_address(program_id, mint);
+    build(
+        program_id,
+        ARIAInstruction::ExecuteSpend(ExecuteSpendArgs { index }),
+        vec![
+            AccountMeta::new_readonly(*executor, true),
+            readonly(*mint),
+            readonly(find_treasury_address(program_id, mint).0),
+            AccountMeta::new(find_spend_address(program_id, mint, index).0, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(*vault_token_account.unwrap_or(&vault), false),
+            AccountMeta::new(*recipient, false),
+            readonly(sysvar::clock::id()),
+            readonly(sysvar::rent::id()),
+            readonly(*token_program_id),
+            readonly(system_program::id()),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `ConfigureGovernance`
+pub fn configure_governance(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    voting_period: i64,
+    quorum_bps: u16,
+    approval_threshold_bps: u16,
+    min_proposal_power: u64,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::ConfigureGovernance(ConfigureGovernanceArgs {
+            voting_period,
+            quorum_bps,
+            approval_threshold_bps,
+            min_proposal_power,
+        }),
+        vec![
+            AccountMeta::new(*authority, true),
+            config(program_id, false),
+            AccountMeta::new(find_governance_address(program_id, mint).0, false),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+        ],
+    )
+}
+
+/// Create `DepositGoverningTokens`. `vault_token_account` is the token account owned by the
+/// governance vault PDA.
+pub fn deposit_governing_tokens(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    source_token_account: &Pubkey,
+    vault_token_account: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::DepositGoverningTokens(DepositGoverningTokensArgs { amount }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*source_token_account, false),
+            AccountMeta::new(*vault_token_account, false),
+            AccountMeta::new(find_voter_record_address(program_id, mint, owner).0, false),
+            readonly(*mint),
+            readonly(sysvar::rent::id()),
+            readonly(*token_program_id),
+            readonly(system_program::id()),
+            AccountMeta::new(find_checkpoint_address(program_id, mint, owner).0, false),
+            readonly(sysvar::clock::id()),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `WithdrawGoverningTokens`
+pub fn withdraw_governing_tokens(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    destination_token_account: &Pubkey,
+    vault_token_account: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::WithdrawGoverningTokens(WithdrawGoverningTokensArgs { amount }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*destination_token_account, false),
+            AccountMeta::new(*vault_token_account, false),
+            AccountMeta::new(find_voter_record_address(program_id, mint, owner).0, false),
+            readonly(find_governance_vault_address(program_id, mint).0),
+            readonly(*mint),
+            readonly(sysvar::clock::id()),
+            readonly(*token_program_id),
+            AccountMeta::new(find_checkpoint_address(program_id, mint, owner).0, false),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `CreateProposal`. `index` is the governance config's current `proposal_count`.
+pub fn create_proposal(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    mint: &Pubkey,
+    index: u64,
+    description: String,
+    instructions: Vec<ProposalInstruction>,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::CreateProposal(CreateProposalArgs {
+            description,
+            instructions,
+        }),
+        vec![
+            AccountMeta::new(*proposer, true),
+            AccountMeta::new(find_governance_address(program_id, mint).0, false),
+            readonly(find_voter_record_address(program_id, mint, proposer).0),
+            AccountMeta::new(find_proposal_address(program_id, mint, index).0, false),
+            readonly(*mint),
+            readonly(sysvar::clock::id()),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `CastVote` for `voter` and every delegator in `delegators` that delegated to them
+pub fn cast_vote(
+    program_id: &Pubkey,
+    voter: &Pubkey,
+    mint: &Pubkey,
+    proposal_index: u64,
+    vote: Vote,
+    delegators: &[Pubkey],
+) -> Instruction {
+    let (proposal, _) = find_proposal_address(program_id, mint, proposal_index);
+    let mut accounts = vec![
+        AccountMeta::new(*voter, true),
+        AccountMeta::new(proposal, false),
+        readonly(find_checkpoint_address(program_id, mint, voter).0),
+        AccountMeta::new(
+            find_vote_record_address(program_id, &proposal, voter).0,
+            false,
+        ),
+        readonly(sysvar::clock::id()),
+        readonly(sysvar::rent::id()),
+        readonly(system_program::id()),
+        config(program_id, false),
+    ];
+    for delegator in delegators {
+        accounts.push(readonly(
+            find_delegate_record_address(program_id, mint, delegator).0,
+        ));
+        accounts.push(readonly(
+            find_checkpoint_address(program_id, mint, delegator).0,
+        ));
+        accounts.push(AccountMeta::new(
+            find_vote_record_address(program_id, &proposal, delegator).0,
+            false,
+        ));
+    }
+    build(
+        program_id,
+        ARIAInstruction::CastVote(CastVoteArgs { vote }),
+        accounts,
+    )
+}
+
+/// Create `ExecuteProposal`. `instructions` are the proposal's instructions; their programs and
+/// accounts are passed along, with the governance authority PDA signing by CPI.
+pub fn execute_proposal(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    proposal_index: u64,
+    instructions: &[ProposalInstruction],
+) -> Instruction {
+    let (governance_authority, _) = find_governance_authority_address(program_id, mint);
+    let mut accounts = vec![
+        AccountMeta::new(
+            find_proposal_address(program_id, mint, proposal_index).0,
+            false,
+        ),
+        readonly(governance_authority),
+        readonly(sysvar::clock::id()),
+        config(program_id, false),
+    ];
+    for instruction in instructions {
+        accounts.push(readonly(instruction.program_id));
+        for meta in instruction.accounts.iter() {
+            if meta.pubkey != governance_authority {
+                accounts.push(AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: false,
+                    is_writable: meta.is_writable,
+                });
+            }
+        }
+    }
+    build(program_id, ARIAInstruction::ExecuteProposal, accounts)
+}
+
+/// Create `SetDelegate`
+pub fn set_delegate(
+    program_id: &Pubkey,
+    delegator: &Pubkey,
+    mint: &Pubkey,
+    delegate: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::SetDelegate(SetDelegateArgs {
+            delegate: *delegate,
+        }),
+        vec![
+            AccountMeta::new(*delegator, true),
+            readonly(*mint),
+            AccountMeta::new(
+                find_delegate_record_address(program_id, mint, delegator).0,
+                false,
+            ),
+            readonly(sysvar::clock::id()),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `RevokeDelegate`
+pub fn revoke_delegate(program_id: &Pubkey, delegator: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::RevokeDelegate,
+        vec![
+            AccountMeta::new(*delegator, true),
+            readonly(*mint),
+            AccountMeta::new(
+                find_delegate_record_address(program_id, mint, delegator).0,
+                false,
+            ),
+            config(program_id, false),
+        ],
+    )
+}
+
+/// Create `HarvestWithheldFees` for the given Token-2022 token accounts
+pub fn harvest_withheld_fees(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    sources: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        readonly(spl_token_2022::id()),
+        config(program_id, false),
+    ];
+    accounts.extend(
+        sources
+            .iter()
+            .map(|source| AccountMeta::new(*source, false)),
+    );
+    build(program_id, ARIAInstruction::HarvestWithheldFees, accounts)
+}
+
+/// Create `WithdrawWithheldFees`. `treasury_token_account` is the treasury vault's token account.
+pub fn withdraw_withheld_fees(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    treasury_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::WithdrawWithheldFees,
+        vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*treasury_token_account, false),
+            config(program_id, false),
+            mint_authority(program_id, mint),
+            readonly(spl_token_2022::id()),
+        ],
+    )
+}
+
+/// Create `UpdateTransferFee`
+pub fn update_transfer_fee(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    transfer_fee: TransferFeeParams,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::UpdateTransferFee(UpdateTransferFeeArgs { transfer_fee }),
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            config(program_id, false),
+            AccountMeta::new(*mint, false),
+            mint_authority(program_id, mint),
+            readonly(spl_token_2022::id()),
+        ],
+    )
+}
+
+/// Create `UpdateMetadata`. Legacy SPL Token mints update their Metaplex metadata account.
+pub fn update_metadata(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+    field: MetadataField,
+    value: String,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::UpdateMetadata(UpdateMetadataArgs { field, value }),
+        vec![
+            AccountMeta::new(*authority, true),
+            config(program_id, false),
+            AccountMeta::new(*mint, false),
+            mint_authority(program_id, mint),
+            readonly(sysvar::rent::id()),
+            readonly(*token_program_id),
+            readonly(system_program::id()),
+        ]
+        .into_iter()
+        .chain(metaplex_accounts(mint, token_program_id))
+        .collect(),
+    )
+}
+
+/// Create `InitializeTransferHook`, signed by the mint's freeze authority
+pub fn initialize_transfer_hook(
+    program_id: &Pubkey,
+    freeze_authority: &Pubkey,
+    mint: &Pubkey,
+    compliance_authority: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::InitializeTransferHook(InitializeTransferHookArgs {
+            compliance_authority: *compliance_authority,
+        }),
+        vec![
+            AccountMeta::new(*freeze_authority, true),
+            readonly(*mint),
+            AccountMeta::new(find_extra_account_metas_address(program_id, mint).0, false),
+            AccountMeta::new(find_blocklist_address(program_id, mint).0, false),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+        ],
+    )
+}
+
+/// Create the transfer hook interface's `InitializeExtraAccountMetaList` listing the blocklist,
+/// signed by the mint authority or freeze authority. The extra-account-metas PDA must hold the
+/// rent for `ExtraAccountMetaList::size_of(1)` beforehand.
+pub fn initialize_extra_account_meta_list(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    transfer_hook_instruction::initialize_extra_account_meta_list(
+        program_id,
+        &find_extra_account_metas_address(program_id, mint).0,
+        mint,
+        authority,
+        // The blocklist meta only has valid literal and account-key seeds
+        &extra_account_metas().unwrap(),
+    )
+}
+
+/// Create the transfer hook interface's `UpdateExtraAccountMetaList`. The list must start with
+/// the blocklist, and a longer list needs the PDA funded for its new size beforehand.
+pub fn update_extra_account_meta_list(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    extra_account_metas: &[ExtraAccountMeta],
+) -> Instruction {
+    transfer_hook_instruction::update_extra_account_meta_list(
+        program_id,
+        &find_extra_account_metas_address(program_id, mint).0,
+        mint,
+        authority,
+        extra_account_metas,
+    )
+}
+
+fn blocklist_instruction(
+    program_id: &Pubkey,
+    compliance_authority: &Pubkey,
+    mint: &Pubkey,
+    instruction: ARIAInstruction,
+) -> Instruction {
+    build(
+        program_id,
+        instruction,
+        vec![
+            AccountMeta::new_readonly(*compliance_authority, true),
+            readonly(*mint),
+            AccountMeta::new(find_blocklist_address(program_id, mint).0, false),
+        ],
+    )
+}
+
+/// Create `SetComplianceAuthority`
+pub fn set_compliance_authority(
+    program_id: &Pubkey,
+    compliance_authority: &Pubkey,
+    mint: &Pubkey,
+    new_compliance_authority: &Pubkey,
+) -> Instruction {
+    blocklist_instruction(
+        program_id,
+        compliance_authority,
+        mint,
+        ARIAInstruction::SetComplianceAuthority(SetComplianceAuthorityArgs {
+            compliance_authority: *new_compliance_authority,
+        }),
+    )
+}
+
+/// Create `BlockAddress`
+pub fn block_address(
+    program_id: &Pubkey,
+    compliance_authority: &Pubkey,
+    mint: &Pubkey,
+    address: &Pubkey,
+) -> Instruction {
+    blocklist_instruction(
+        program_id,
+        compliance_authority,
+        mint,
+        ARIAInstruction::BlockAddress(BlockAddressArgs { address: *address }),
+    )
+}
+
+/// Create `UnblockAddress`
+pub fn unblock_address(
+    program_id: &Pubkey,
+    compliance_authority: &Pubkey,
+    mint: &Pubkey,
+    address: &Pubkey,
+) -> Instruction {
+    blocklist_instruction(
+        program_id,
+        compliance_authority,
+        mint,
+        ARIAInstruction::UnblockAddress(UnblockAddressArgs { address: *address }),
+    )
+}
+
+/// Create `Pause`, signed by a pauser
+pub fn pause(program_id: &Pubkey, pauser: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::Pause,
+        vec![
+            AccountMeta::new_readonly(*pauser, true),
+            config(program_id, true),
+            role_registry(program_id, false),
+        ],
+    )
+}
+
+/// Create `Unpause`, signed by a pauser
+pub fn unpause(program_id: &Pubkey, pauser: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::Unpause,
+        vec![
+            AccountMeta::new_readonly(*pauser, true),
+            config(program_id, true),
+            role_registry(program_id, false),
+        ],
+    )
+}
+
+/// Create `GrantRole`, signed by the admin
+pub fn grant_role(program_id: &Pubkey, admin: &Pubkey, role: Role, member: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::GrantRole(GrantRoleArgs {
+            role,
+            member: *member,
+        }),
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            config(program_id, false),
+            role_registry(program_id, true),
+        ],
+    )
+}
+
+/// Create `RevokeRole`, signed by the admin
+pub fn revoke_role(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    role: Role,
+    member: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::RevokeRole(RevokeRoleArgs {
+            role,
+            member: *member,
+        }),
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            config(program_id, false),
+            role_registry(program_id, true),
+        ],
+    )
+}
+
+/// Create `UpdateMinterAllowance`, signed by the admin
+pub fn update_minter_allowance(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    minter: &Pubkey,
+    change: AllowanceChange,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::UpdateMinterAllowance(UpdateMinterAllowanceArgs {
+            minter: *minter,
+            change,
+        }),
+        vec![
+            AccountMeta::new(*admin, true),
+            config(program_id, false),
+            AccountMeta::new(find_minter_allowance_address(program_id, minter).0, false),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+        ],
+    )
+}
+
+/// Create `MigrateAccount` for the program account `account` of type `account_type`
+pub fn migrate_account(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    account: &Pubkey,
+    account_type: AccountType,
+) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::MigrateAccount(MigrateAccountArgs { account_type }),
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*account, false),
+            readonly(sysvar::rent::id()),
+            readonly(system_program::id()),
+        ],
+    )
+}
+
+/// Create `GetConfig`. Simulate it and decode the return data as a `view::ConfigView`.
+pub fn get_config(program_id: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::GetConfig,
+        vec![config(program_id, false)],
+    )
+}
+
+/// Create `GetRemainingMintable`, returning a `u64`. With `minter`, the result is capped by its
+/// remaining allowance.
+pub fn get_remaining_mintable(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    minter: Option<&Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![config(program_id, false), readonly(*mint)];
+    if let Some(minter) = minter {
+        accounts.push(readonly(
+            find_minter_allowance_address(program_id, minter).0,
+        ));
+    }
+    build(
+        program_id,
+        ARIAInstruction::GetRemainingMintable(GetRemainingMintableArgs {
+            minter: minter.copied(),
+        }),
+        accounts,
+    )
+}
+
+/// Create `GetNextMintTimeV2` for `minter`, returning an `i64` unix timestamp
+pub fn get_next_mint_time(program_id: &Pubkey, minter: &Pubkey) -> Instruction {
+    let (allowance, _) = find_minter_allowance_address(program_id, minter);
+    build(
+        program_id,
+        ARIAInstruction::GetNextMintTimeV2(GetNextMintTimeV2Args { minter: *minter }),
+        vec![config(program_id, false), readonly(allowance)],
+    )
+}
+
+/// Create `GetPendingAuthorityTransfer`, returning an `Option<view::PendingAuthorityTransfer>`
+pub fn get_pending_authority_transfer(program_id: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        ARIAInstruction::GetPendingAuthorityTransfer,
+        vec![config(program_id, false), readonly(sysvar::clock::id())],
+    )
+}
+
+/// Create `GetProgramVersion`, returning a `view::ProgramVersion`
+pub fn get_program_version(program_id: &Pubkey) -> Instruction {
+    build(program_id, ARIAInstruction::GetProgramVersion, vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshDeserialize;
+
+    #[test]
+    fn test_mint_tokens_accounts() {
+        let program_id = Pubkey::new_unique();
+        let minter = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+
+        let instruction = mint_tokens(
+            &program_id,
+            &minter,
+            &mint,
+            &destination,
+            &spl_token_2022::id(),
+            42,
+        );
+        assert_eq!(instruction.program_id, program_id);
+        assert!(matches!(
+            ARIAInstruction::try_from_slice(&instruction.data).unwrap(),
+            ARIAInstruction::MintTokens(MintTokensArgs { amount: 42 })
+        ));
+
+        let expected = [
+            (minter, true, false),
+            (mint, false, true),
+            (destination, false, true),
+            (spl_token_2022::id(), false, false),
+            (sysvar::clock::id(), false, false),
+            (find_config_address(&program_id).0, false, true),
+            (
+                find_mint_authority_address(&program_id, &mint).0,
+                false,
+                false,
+            ),
+            (find_role_registry_address(&program_id).0, false, false),
+            (
+                find_minter_allowance_address(&program_id, &minter).0,
+                false,
+                true,
+            ),
+        ];
+        let accounts: Vec<_> = instruction
+            .accounts
+            .iter()
+            .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+            .collect();
+        assert_eq!(accounts, expected);
+    }
+
+    #[test]
+    fn test_cast_vote_delegators() {
+        let program_id = Pubkey::new_unique();
+        let voter = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let delegators = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let instruction = cast_vote(&program_id, &voter, &mint, 3, Vote::For, &delegators);
+        assert_eq!(instruction.accounts.len(), 8 + 3 * delegators.len());
+
+        let (proposal, _) = find_proposal_address(&program_id, &mint, 3);
+        assert_eq!(instruction.accounts[1].pubkey, proposal);
+        let group = &instruction.accounts[11..14];
+        assert_eq!(
+            group[0].pubkey,
+            find_delegate_record_address(&program_id, &mint, &delegators[1]).0
+        );
+        assert_eq!(
+            group[1].pubkey,
+            find_checkpoint_address(&program_id, &mint, &delegators[1]).0
+        );
+        assert_eq!(
+            group[2].pubkey,
+            find_vote_record_address(&program_id, &proposal, &delegators[1]).0
+        );
+        assert!(group[2].is_writable);
+    }
+
+    #[test]
+    fn test_execute_proposal_accounts() {
+        let program_id = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let (governance_authority, _) = find_governance_authority_address(&program_id, &mint);
+        let target = Pubkey::new_unique();
+        let proposal_instruction = ProposalInstruction::from(&propose_authority_transfer(
+            &program_id,
+            &governance_authority,
+            &target,
+        ));
+
+        let instruction = execute_proposal(&program_id, &mint, 0, &[proposal_instruction]);
+        // Proposal, authority, clock, config, then the inner program and its accounts minus the
+        // signer
+        assert_eq!(instruction.accounts.len(), 4 + 1 + 3);
+        assert_eq!(instruction.accounts[4].pubkey, program_id);
+        assert!(instruction.accounts.iter().all(|meta| !meta.is_signer));
+        assert_eq!(
+            instruction
+                .accounts
+                .iter()
+                .filter(|meta| meta.pubkey == governance_authority)
+                .count(),
+            1
+        );
+    }
+}
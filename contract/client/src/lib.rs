@@ -0,0 +1,8 @@
+// ARIA Token client
+// Typed builders for every `ARIAInstruction`, with the account order, signer and writable
+// flags and PDA derivation of the program, and helpers that convert UI amounts to base units.
+
+pub mod amount;
+pub mod instruction;
+
+pub use aria_token_interface as interface;
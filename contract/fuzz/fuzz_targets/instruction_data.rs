@@ -0,0 +1,22 @@
+// Arbitrary instruction data through the decoder and process_instruction
+// Every pool account is passed in order with all keypairs signing, so whatever the bytes decode
+// to runs as far as its account checks allow. Decoding must never panic, and anything that
+// decodes must encode back to the same bytes.
+
+#![no_main]
+
+use aria_token_fuzz::{AccountRef, Runtime, Setup, KEYPAIRS, POOL_SIZE};
+use aria_token_interface::instruction::ARIAInstruction;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(instruction) = ARIAInstruction::unpack(data) {
+        assert_eq!(instruction.pack(), data, "decoding is not canonical");
+    }
+
+    let accounts: Vec<AccountRef> = (0..POOL_SIZE)
+        .map(|index| AccountRef::new(index, KEYPAIRS.contains(&index), true))
+        .collect();
+    let mut runtime = Runtime::new(&Setup::default());
+    let _ = runtime.process(&accounts, data);
+});
@@ -0,0 +1,342 @@
+// Fuzzed instructions
+// A step decodes its arguments leniently into an `ARIAInstruction` and builds it with the client
+// crate, so it starts from the account list the program expects: the actor is one of the
+// keypairs, key arguments point into the account pool and PDAs the builder derives join the pool.
+// Account edits then replace, swap or drop accounts and flip their signer and writable flags.
+
+use arbitrary::Arbitrary;
+use aria_token_client::instruction as client;
+use aria_token_interface::find_config_address;
+use aria_token_interface::governance::find_governance_vault_address;
+use aria_token_interface::instruction::{
+    ARIAInstruction, ApproveSpendArgs, CastVoteArgs, ConfigureGovernanceArgs,
+    ConfigureTreasuryArgs, CreateProposalArgs, DepositGoverningTokensArgs, ExecuteSpendArgs,
+    GetRemainingMintableArgs, GrantRoleArgs, InitializeMintArgs, InstructionDiscriminator,
+    MigrateAccountArgs, MintTokensArgs, PayWithReferralArgs, ProposeSpendArgs, RevokeRoleArgs,
+    SetReferralShareArgs, UpdateMetadataArgs, UpdateMinterAllowanceArgs, UpdateTransferFeeArgs,
+    WithdrawGoverningTokensArgs,
+};
+use borsh::BorshDeserialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::{AccountRef, Runtime, DESTINATION, KEYPAIRS, MINT, PROGRAM_ID};
+
+// Spend and proposal indices a step may refer to
+const MAX_INDEX: u64 = 4;
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum AccountEdit {
+    Replace { position: u8, index: u8 },
+    Swap { first: u8, second: u8 },
+    ToggleSigner { position: u8 },
+    ToggleWritable { position: u8 },
+    Truncate { len: u8 },
+}
+
+#[derive(Arbitrary, Debug, Clone)]
+pub struct Step {
+    pub clock_advance: u16,
+    pub instruction: u8,
+    pub args: Vec<u8>,
+    // Keypair that signs the instruction
+    pub actor: u8,
+    // Pool account that key arguments refer to
+    pub other: u8,
+    pub edits: Vec<AccountEdit>,
+}
+
+impl Step {
+    // Instruction data and account list of the step
+    pub fn build(&self, runtime: &mut Runtime) -> (Vec<u8>, Vec<AccountRef>) {
+        let instruction = self.decode();
+        let actor = runtime.key(KEYPAIRS[self.actor as usize % KEYPAIRS.len()]);
+        let other = runtime.key(self.other as usize % runtime.accounts.len());
+        let built = build_instruction(instruction, &actor, &other, runtime.key(MINT));
+        let mut accounts: Vec<AccountRef> = built
+            .accounts
+            .iter()
+            .map(|meta| {
+                AccountRef::new(
+                    runtime.index_of(&meta.pubkey),
+                    meta.is_signer,
+                    meta.is_writable,
+                )
+            })
+            .collect();
+        for edit in self.edits.iter() {
+            apply_edit(&mut accounts, *edit, runtime.accounts.len());
+        }
+        (built.data, accounts)
+    }
+
+    // Missing argument bytes decode as zeros, trailing ones are ignored
+    fn decode(&self) -> ARIAInstruction {
+        let all = InstructionDiscriminator::ALL;
+        let mut data = vec![all[self.instruction as usize % all.len()] as u8];
+        data.extend_from_slice(&self.args);
+        if let Ok(instruction) = ARIAInstruction::deserialize(&mut data.as_slice()) {
+            return instruction;
+        }
+        data.truncate(1);
+        data.resize(1024, 0);
+        ARIAInstruction::deserialize(&mut data.as_slice()).unwrap()
+    }
+}
+
+fn apply_edit(accounts: &mut Vec<AccountRef>, edit: AccountEdit, pool_len: usize) {
+    let position = |position: u8, len: usize| (len > 0).then(|| position as usize % len);
+    match edit {
+        AccountEdit::Replace {
+            position: at,
+            index,
+        } => {
+            if let Some(at) = position(at, accounts.len()) {
+                accounts[at].index = (index as usize % pool_len) as u8;
+            }
+        }
+        AccountEdit::Swap { first, second } => {
+            if let (Some(first), Some(second)) = (
+                position(first, accounts.len()),
+                position(second, accounts.len()),
+            ) {
+                accounts.swap(first, second);
+            }
+        }
+        AccountEdit::ToggleSigner { position: at } => {
+            if let Some(at) = position(at, accounts.len()) {
+                accounts[at].is_signer = !accounts[at].is_signer;
+            }
+        }
+        AccountEdit::ToggleWritable { position: at } => {
+            if let Some(at) = position(at, accounts.len()) {
+                accounts[at].is_writable = !accounts[at].is_writable;
+            }
+        }
+        AccountEdit::Truncate { len } => accounts.truncate(len as usize),
+    }
+}
+
+// Instruction of a retired variant, built by hand as the client no longer offers it
+fn retired_instruction(instruction: &ARIAInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: instruction.pack(),
+    }
+}
+
+fn build_instruction(
+    instruction: ARIAInstruction,
+    actor: &Pubkey,
+    other: &Pubkey,
+    mint: Pubkey,
+) -> Instruction {
+    let program_id = &PROGRAM_ID;
+    let mint = &mint;
+    let token_program = &spl_token::id();
+    let destination = &crate::keys().keys[DESTINATION];
+    match instruction {
+        ARIAInstruction::InitializeMint(InitializeMintArgs {
+            transfer_fee,
+            transfer_hook_program,
+            metadata_uri,
+        }) => client::initialize_mint(
+            program_id,
+            actor,
+            mint,
+            token_program,
+            transfer_fee,
+            transfer_hook_program,
+            metadata_uri,
+        ),
+        ARIAInstruction::MintTokens(MintTokensArgs { amount }) => {
+            client::mint_tokens(program_id, actor, mint, destination, token_program, amount)
+        }
+        // Retired instructions with their original account lists
+        ARIAInstruction::ProposeAuthorityTransfer(..) => retired_instruction(
+            &instruction,
+            vec![
+                AccountMeta::new_readonly(*actor, true),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new_readonly(*other, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+            ],
+        ),
+        ARIAInstruction::AcceptAuthorityTransfer => retired_instruction(
+            &instruction,
+            vec![
+                AccountMeta::new_readonly(*actor, true),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new_readonly(*other, false),
+                AccountMeta::new_readonly(*token_program, false),
+                AccountMeta::new_readonly(sysvar::clock::id(), false),
+            ],
+        ),
+        ARIAInstruction::CancelAuthorityTransfer => retired_instruction(
+            &instruction,
+            vec![
+                AccountMeta::new_readonly(*actor, true),
+                AccountMeta::new(*mint, false),
+            ],
+        ),
+        ARIAInstruction::ProposeAuthorityTransferV2(..) => {
+            client::propose_authority_transfer(program_id, actor, other)
+        }
+        ARIAInstruction::AcceptAuthorityTransferV2 => {
+            client::accept_authority_transfer(program_id, actor)
+        }
+        ARIAInstruction::CancelAuthorityTransferV2 => {
+            client::cancel_authority_transfer(program_id, actor)
+        }
+        ARIAInstruction::SetReferralShare(SetReferralShareArgs { share_bps }) => {
+            client::set_referral_share(program_id, actor, mint, share_bps)
+        }
+        ARIAInstruction::RegisterReferrer(..) => {
+            client::register_referrer(program_id, actor, mint, other)
+        }
+        ARIAInstruction::PayWithReferral(PayWithReferralArgs { amount }) => {
+            client::pay_with_referral(
+                program_id,
+                actor,
+                destination,
+                other,
+                other,
+                destination,
+                mint,
+                token_program,
+                amount,
+            )
+        }
+        ARIAInstruction::ConfigureTreasury(ConfigureTreasuryArgs { threshold, .. }) => {
+            client::configure_treasury(program_id, actor, mint, vec![*actor, *other], threshold)
+        }
+        ARIAInstruction::ProposeSpend(ProposeSpendArgs { asset, amount, .. }) => {
+            let index = amount % MAX_INDEX;
+            client::propose_spend(program_id, actor, mint, index, asset, other, amount)
+        }
+        ARIAInstruction::ApproveSpend(ApproveSpendArgs { index }) => {
+            client::approve_spend(program_id, actor, mint, index % MAX_INDEX)
+        }
+        ARIAInstruction::ExecuteSpend(ExecuteSpendArgs { index }) => client::execute_spend(
+            program_id,
+            actor,
+            mint,
+            index % MAX_INDEX,
+            Some(destination),
+            other,
+            token_program,
+        ),
+        ARIAInstruction::ConfigureGovernance(ConfigureGovernanceArgs {
+            voting_period,
+            quorum_bps,
+            approval_threshold_bps,
+            min_proposal_power,
+        }) => client::configure_governance(
+            program_id,
+            actor,
+            mint,
+            voting_period,
+            quorum_bps,
+            approval_threshold_bps,
+            min_proposal_power,
+        ),
+        ARIAInstruction::DepositGoverningTokens(DepositGoverningTokensArgs { amount }) => {
+            client::deposit_governing_tokens(
+                program_id,
+                actor,
+                destination,
+                other,
+                mint,
+                token_program,
+                amount,
+            )
+        }
+        ARIAInstruction::WithdrawGoverningTokens(WithdrawGoverningTokensArgs { amount }) => {
+            client::withdraw_governing_tokens(
+                program_id,
+                actor,
+                destination,
+                &find_governance_vault_address(program_id, mint).0,
+                mint,
+                token_program,
+                amount,
+            )
+        }
+        ARIAInstruction::CreateProposal(CreateProposalArgs {
+            description,
+            instructions,
+        }) => {
+            let index = description.len() as u64 % MAX_INDEX;
+            client::create_proposal(program_id, actor, mint, index, description, instructions)
+        }
+        ARIAInstruction::CastVote(CastVoteArgs { vote }) => client::cast_vote(
+            program_id,
+            actor,
+            mint,
+            other.as_ref()[0] as u64 % MAX_INDEX,
+            vote,
+            &[],
+        ),
+        ARIAInstruction::ExecuteProposal => {
+            client::execute_proposal(program_id, mint, other.as_ref()[0] as u64 % MAX_INDEX, &[])
+        }
+        ARIAInstruction::SetDelegate(..) => client::set_delegate(program_id, actor, mint, other),
+        ARIAInstruction::RevokeDelegate => client::revoke_delegate(program_id, actor, mint),
+        ARIAInstruction::HarvestWithheldFees => {
+            client::harvest_withheld_fees(program_id, mint, &[*destination])
+        }
+        ARIAInstruction::WithdrawWithheldFees => {
+            client::withdraw_withheld_fees(program_id, mint, other)
+        }
+        ARIAInstruction::UpdateTransferFee(UpdateTransferFeeArgs { transfer_fee }) => {
+            client::update_transfer_fee(program_id, actor, mint, transfer_fee)
+        }
+        ARIAInstruction::UpdateMetadata(UpdateMetadataArgs { field, value }) => {
+            client::update_metadata(program_id, actor, mint, token_program, field, value)
+        }
+        ARIAInstruction::InitializeTransferHook(..) => {
+            client::initialize_transfer_hook(program_id, actor, mint, other)
+        }
+        ARIAInstruction::SetComplianceAuthority(..) => {
+            client::set_compliance_authority(program_id, actor, mint, other)
+        }
+        ARIAInstruction::BlockAddress(..) => client::block_address(program_id, actor, mint, other),
+        ARIAInstruction::UnblockAddress(..) => {
+            client::unblock_address(program_id, actor, mint, other)
+        }
+        ARIAInstruction::Pause => client::pause(program_id, actor),
+        ARIAInstruction::Unpause => client::unpause(program_id, actor),
+        ARIAInstruction::GrantRole(GrantRoleArgs { role, .. }) => {
+            client::grant_role(program_id, actor, role, other)
+        }
+        ARIAInstruction::RevokeRole(RevokeRoleArgs { role, .. }) => {
+            client::revoke_role(program_id, actor, role, other)
+        }
+        ARIAInstruction::UpdateMinterAllowance(UpdateMinterAllowanceArgs { change, .. }) => {
+            client::update_minter_allowance(program_id, actor, other, change)
+        }
+        ARIAInstruction::MigrateAccount(MigrateAccountArgs { account_type }) => {
+            client::migrate_account(program_id, actor, other, account_type)
+        }
+        ARIAInstruction::GetConfig => client::get_config(program_id),
+        ARIAInstruction::GetRemainingMintable(GetRemainingMintableArgs { minter }) => {
+            client::get_remaining_mintable(program_id, mint, minter.map(|_| actor))
+        }
+        ARIAInstruction::GetNextMintTime => retired_instruction(
+            &instruction,
+            vec![AccountMeta::new_readonly(
+                find_config_address(program_id).0,
+                false,
+            )],
+        ),
+        ARIAInstruction::GetPendingAuthorityTransfer => {
+            client::get_pending_authority_transfer(program_id)
+        }
+        ARIAInstruction::GetProgramVersion => client::get_program_version(program_id),
+        ARIAInstruction::GetNextMintTimeV2(..) => client::get_next_mint_time(program_id, actor),
+    }
+}
@@ -0,0 +1,972 @@
+// Simulated runtime for the fuzz targets
+// Accounts are serialized into the loader's input layout and decoded with the SDK's entrypoint
+// deserializer, so handlers see the same account infos they get on-chain, realloc included.
+// CPIs into the system program, SPL Token, Token-2022 and the program itself run in-process
+// behind the runtime's signer and writable privilege checks, and a failed CPI fails the whole
+// instruction. Every successful instruction is checked against the program's invariants.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::sync::{Once, OnceLock};
+
+use arbitrary::Arbitrary;
+use aria_token_interface::account::VersionedAccount;
+use aria_token_interface::governance::find_governance_address;
+use aria_token_interface::metadata::METAPLEX_PROGRAM_ID;
+use aria_token_interface::referral::find_referral_config_address;
+use aria_token_interface::roles::{
+    find_minter_allowance_address, find_role_registry_address, MinterAllowance, Role,
+    RoleAssignment, RoleRegistry,
+};
+use aria_token_interface::transfer_hook::find_blocklist_address;
+use aria_token_interface::treasury::find_treasury_address;
+use aria_token_interface::{
+    find_config_address, find_mint_authority_address, find_program_data_address,
+    AuthorityTransferState, ProgramConfig, MINT_COOLDOWN, TOKEN_DECIMALS, TOTAL_SUPPLY,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    bpf_loader,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    entrypoint::{
+        deserialize, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER,
+        SUCCESS,
+    },
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::{SystemInstruction, MAX_PERMITTED_DATA_LENGTH},
+    system_program, sysvar,
+};
+use spl_token::state::{Account, AccountState, Mint};
+
+pub mod actions;
+
+pub const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0xa1; 32]);
+
+// Unix timestamp the simulated clock starts at
+pub const START_TIME: i64 = 1_700_000_000;
+
+// Upper bound on the accounts one instruction may reference
+pub const MAX_INSTRUCTION_ACCOUNTS: usize = 32;
+
+// Upper bound on the pool, which grows as instructions reference new addresses
+pub const MAX_POOL_SIZE: usize = u8::MAX as usize;
+
+// Initial account pool. Keypair wallets first: only they can sign a transaction.
+pub const AUTHORITY: usize = 0;
+pub const MINTER: usize = 1;
+pub const ATTACKER: usize = 2;
+pub const CONFIG: usize = 3;
+pub const ROLE_REGISTRY: usize = 4;
+pub const MINTER_ALLOWANCE: usize = 5;
+pub const ATTACKER_ALLOWANCE: usize = 6;
+pub const MINT: usize = 7;
+pub const MINT_AUTHORITY: usize = 8;
+pub const DESTINATION: usize = 9;
+pub const TOKEN_PROGRAM: usize = 10;
+pub const TOKEN_2022_PROGRAM: usize = 11;
+pub const SYSTEM_PROGRAM: usize = 12;
+pub const CLOCK: usize = 13;
+pub const RENT: usize = 14;
+pub const PROGRAM_DATA: usize = 15;
+pub const PROGRAM: usize = 16;
+pub const REFERRAL_CONFIG: usize = 17;
+pub const TREASURY: usize = 18;
+pub const GOVERNANCE: usize = 19;
+pub const BLOCKLIST: usize = 20;
+pub const POOL_SIZE: usize = 21;
+
+// Wallets the fuzzer holds the keys of
+pub const KEYPAIRS: [usize; 3] = [AUTHORITY, MINTER, ATTACKER];
+const ALLOWANCES: [(usize, usize); 2] =
+    [(MINTER, MINTER_ALLOWANCE), (ATTACKER, ATTACKER_ALLOWANCE)];
+
+// Account as the bank stores it between instructions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub executable: bool,
+}
+
+impl SimAccount {
+    fn new(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+        SimAccount {
+            key,
+            owner,
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            executable: false,
+        }
+    }
+
+    // Address nobody has funded or allocated yet
+    fn blank(key: Pubkey) -> Self {
+        SimAccount {
+            key,
+            owner: system_program::id(),
+            lamports: 0,
+            data: Vec::new(),
+            executable: false,
+        }
+    }
+
+    fn wallet(key: Pubkey) -> Self {
+        SimAccount {
+            lamports: 100_000_000_000,
+            ..Self::blank(key)
+        }
+    }
+
+    fn program(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+        SimAccount {
+            executable: true,
+            ..Self::new(key, owner, data)
+        }
+    }
+
+    fn sysvar(key: Pubkey, data: Vec<u8>) -> Self {
+        Self::new(key, sysvar::id(), data)
+    }
+
+    fn versioned<S: VersionedAccount>(key: Pubkey, state: &S, len: usize) -> Self {
+        let mut account = Self::new(key, PROGRAM_ID, vec![0; len]);
+        state.pack_into(&mut account.data).unwrap();
+        account
+    }
+
+    fn packed<S: Pack>(key: Pubkey, state: S) -> Self {
+        let mut account = Self::new(key, spl_token::id(), vec![0; S::LEN]);
+        S::pack(state, &mut account.data).unwrap();
+        account
+    }
+}
+
+// Pool addresses, derived once per process
+struct Keys {
+    keys: [Pubkey; POOL_SIZE],
+    program_data: Vec<u8>,
+}
+
+fn keys() -> &'static Keys {
+    static KEYS: OnceLock<Keys> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let wallet = |seed: u8| Pubkey::new_from_array([seed; 32]);
+        let mint = wallet(7);
+        let mut keys = [Pubkey::default(); POOL_SIZE];
+        keys[AUTHORITY] = wallet(1);
+        keys[MINTER] = wallet(2);
+        keys[ATTACKER] = wallet(3);
+        keys[CONFIG] = find_config_address(&PROGRAM_ID).0;
+        keys[ROLE_REGISTRY] = find_role_registry_address(&PROGRAM_ID).0;
+        keys[MINTER_ALLOWANCE] = find_minter_allowance_address(&PROGRAM_ID, &keys[MINTER]).0;
+        keys[ATTACKER_ALLOWANCE] = find_minter_allowance_address(&PROGRAM_ID, &keys[ATTACKER]).0;
+        keys[MINT] = mint;
+        keys[MINT_AUTHORITY] = find_mint_authority_address(&PROGRAM_ID, &mint).0;
+        keys[DESTINATION] = wallet(9);
+        keys[TOKEN_PROGRAM] = spl_token::id();
+        keys[TOKEN_2022_PROGRAM] = spl_token_2022::id();
+        keys[SYSTEM_PROGRAM] = system_program::id();
+        keys[CLOCK] = sysvar::clock::id();
+        keys[RENT] = sysvar::rent::id();
+        keys[PROGRAM_DATA] = find_program_data_address(&PROGRAM_ID).0;
+        keys[PROGRAM] = PROGRAM_ID;
+        keys[REFERRAL_CONFIG] = find_referral_config_address(&PROGRAM_ID, &mint).0;
+        keys[TREASURY] = find_treasury_address(&PROGRAM_ID, &mint).0;
+        keys[GOVERNANCE] = find_governance_address(&PROGRAM_ID, &mint).0;
+        keys[BLOCKLIST] = find_blocklist_address(&PROGRAM_ID, &mint).0;
+        let program_data = bincode::serialize(&UpgradeableLoaderState::Program {
+            programdata_address: keys[PROGRAM_DATA],
+        })
+        .unwrap();
+        Keys { keys, program_data }
+    })
+}
+
+// Starting state of a fuzz case
+#[derive(Arbitrary, Debug, Clone)]
+pub struct Setup {
+    // Whether InitializeMint has already run
+    pub initialized: bool,
+    pub paused: bool,
+    pub supply: u64,
+    pub allowance: u64,
+    // Seconds since the minter last minted, 0 if it has not minted yet
+    pub last_mint_age: u16,
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Setup {
+            initialized: true,
+            paused: false,
+            supply: 0,
+            allowance: TOTAL_SUPPLY,
+            last_mint_age: 0,
+        }
+    }
+}
+
+// Pool account an instruction references, with the flags the transaction asks for
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub struct AccountRef {
+    pub index: u8,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountRef {
+    pub fn new(index: usize, is_signer: bool, is_writable: bool) -> Self {
+        AccountRef {
+            index: index as u8,
+            is_signer,
+            is_writable,
+        }
+    }
+}
+
+pub struct Runtime {
+    pub accounts: Vec<SimAccount>,
+    clock: Clock,
+}
+
+impl Runtime {
+    pub fn new(setup: &Setup) -> Self {
+        install_stubs();
+        let Keys { keys, program_data } = keys();
+        let upgrade_authority = keys[AUTHORITY];
+        let mut accounts: Vec<SimAccount> = keys.iter().copied().map(SimAccount::blank).collect();
+        for index in KEYPAIRS {
+            accounts[index] = SimAccount::wallet(keys[index]);
+        }
+        let clock = Clock {
+            slot: 1,
+            unix_timestamp: START_TIME,
+            ..Clock::default()
+        };
+        accounts[CLOCK] = SimAccount::sysvar(keys[CLOCK], bincode::serialize(&clock).unwrap());
+        accounts[RENT] =
+            SimAccount::sysvar(keys[RENT], bincode::serialize(&Rent::default()).unwrap());
+        accounts[TOKEN_PROGRAM] =
+            SimAccount::program(keys[TOKEN_PROGRAM], bpf_loader::id(), vec![]);
+        accounts[TOKEN_2022_PROGRAM] =
+            SimAccount::program(keys[TOKEN_2022_PROGRAM], bpf_loader::id(), vec![]);
+        accounts[SYSTEM_PROGRAM] =
+            SimAccount::program(keys[SYSTEM_PROGRAM], Pubkey::default(), vec![]);
+        accounts[PROGRAM] = SimAccount::program(
+            PROGRAM_ID,
+            bpf_loader_upgradeable::id(),
+            program_data.clone(),
+        );
+        accounts[PROGRAM_DATA] = SimAccount::new(
+            keys[PROGRAM_DATA],
+            bpf_loader_upgradeable::id(),
+            bincode::serialize(&UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(upgrade_authority),
+            })
+            .unwrap(),
+        );
+
+        if !setup.initialized {
+            // Mint allocated for InitializeMint, owned by the token program
+            accounts[MINT] = SimAccount::new(keys[MINT], spl_token::id(), vec![0; Mint::LEN]);
+            return Runtime { accounts, clock };
+        }
+
+        accounts[MINT] = SimAccount::packed(
+            keys[MINT],
+            Mint {
+                mint_authority: COption::Some(keys[MINT_AUTHORITY]),
+                supply: setup.supply % (TOTAL_SUPPLY + 1),
+                decimals: TOKEN_DECIMALS,
+                is_initialized: true,
+                freeze_authority: COption::Some(upgrade_authority),
+            },
+        );
+        accounts[DESTINATION] = SimAccount::packed(
+            keys[DESTINATION],
+            Account {
+                mint: keys[MINT],
+                owner: keys[ATTACKER],
+                state: AccountState::Initialized,
+                ..Account::default()
+            },
+        );
+        let last_mint_time = match setup.last_mint_age {
+            0 => 0,
+            age => START_TIME - i64::from(age),
+        };
+        accounts[CONFIG] = SimAccount::versioned(
+            keys[CONFIG],
+            &ProgramConfig {
+                is_initialized: true,
+                mint: keys[MINT],
+                authority: upgrade_authority,
+                authority_transfer: AuthorityTransferState::default(),
+                last_mint_time,
+                paused: setup.paused,
+            },
+            ProgramConfig::LEN,
+        );
+        accounts[ROLE_REGISTRY] = SimAccount::versioned(
+            keys[ROLE_REGISTRY],
+            &RoleRegistry {
+                is_initialized: true,
+                assignments: vec![
+                    RoleAssignment {
+                        role: Role::Minter,
+                        member: keys[MINTER],
+                    },
+                    RoleAssignment {
+                        role: Role::Pauser,
+                        member: upgrade_authority,
+                    },
+                ],
+            },
+            RoleRegistry::LEN,
+        );
+        accounts[MINTER_ALLOWANCE] = SimAccount::versioned(
+            keys[MINTER_ALLOWANCE],
+            &MinterAllowance {
+                is_initialized: true,
+                minter: keys[MINTER],
+                remaining: setup.allowance,
+                last_mint_time,
+            },
+            MinterAllowance::LEN,
+        );
+        Runtime { accounts, clock }
+    }
+
+    pub fn key(&self, index: usize) -> Pubkey {
+        self.accounts[index].key
+    }
+
+    // Pool index of `key`, adding an unfunded account for addresses seen for the first time
+    pub fn index_of(&mut self, key: &Pubkey) -> usize {
+        if let Some(index) = self.accounts.iter().position(|account| account.key == *key) {
+            return index;
+        }
+        assert!(self.accounts.len() < MAX_POOL_SIZE, "account pool is full");
+        self.accounts.push(SimAccount::blank(*key));
+        self.accounts.len() - 1
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    // Move the clock sysvar forward
+    pub fn advance_clock(&mut self, seconds: i64) {
+        self.clock.slot += 1;
+        self.clock.unix_timestamp = self.clock.unix_timestamp.saturating_add(seconds);
+        self.accounts[CLOCK].data = bincode::serialize(&self.clock).unwrap();
+    }
+
+    pub fn mint_supply(&self) -> u64 {
+        supply(&self.accounts)
+    }
+
+    // Run one top-level instruction and, when it succeeds, commit its changes after checking the
+    // invariants. A failed instruction leaves the pool untouched, like a failed transaction.
+    pub fn process(&mut self, accounts: &[AccountRef], data: &[u8]) -> ProgramResult {
+        // The runtime merges the flags of repeated accounts; only keypairs can sign and
+        // sysvars and programs are always read-only
+        let indices: Vec<usize> = accounts
+            .iter()
+            .take(MAX_INSTRUCTION_ACCOUNTS)
+            .map(|account| account.index as usize % self.accounts.len())
+            .collect();
+        let mut is_signer = vec![false; self.accounts.len()];
+        let mut is_writable = vec![false; self.accounts.len()];
+        for (account, &index) in accounts.iter().zip(&indices) {
+            is_signer[index] |= account.is_signer && KEYPAIRS.contains(&index);
+            is_writable[index] |= account.is_writable
+                && !self.accounts[index].executable
+                && self.accounts[index].owner != sysvar::id();
+        }
+
+        let mut input = serialize_input(&self.accounts, &indices, &is_signer, &is_writable, data);
+        let mut signers: BTreeSet<Pubkey> = (0..self.accounts.len())
+            .filter(|&index| is_signer[index])
+            .map(|index| self.accounts[index].key)
+            .collect();
+        STATE.with(|state| *state.borrow_mut() = SimState::new(self.clock.clone()));
+
+        let post = {
+            // SAFETY: `input` is laid out like the loader's input buffer, 8-byte aligned, with
+            // the realloc padding after every account's data, and outlives the account infos
+            let (program_id, infos, instruction_data) =
+                unsafe { deserialize(input.as_mut_ptr() as *mut u8) };
+            let result = invoke_program(program_id, &infos, instruction_data);
+            let cpi_error = STATE.with(|state| state.borrow_mut().cpi_error.take());
+            result.and(cpi_error.map_or(Ok(()), Err))?;
+
+            let mut post = self.accounts.clone();
+            for (info, &index) in infos.iter().zip(&indices) {
+                post[index].lamports = info.lamports();
+                post[index].data = info.data.borrow().to_vec();
+                post[index].owner = *info.owner;
+            }
+            post
+        };
+
+        signers.extend(STATE.with(|state| std::mem::take(&mut state.borrow_mut().pda_signers)));
+        check_invariants(&self.accounts, &post, &is_writable, &signers);
+        self.accounts = post;
+        Ok(())
+    }
+
+    pub fn return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        STATE.with(|state| state.borrow().return_data.clone())
+    }
+}
+
+// Loader input: account count, each account (or a back reference to its first occurrence),
+// instruction data and program id. The buffer is u64-backed so it is 8-byte aligned.
+fn serialize_input(
+    accounts: &[SimAccount],
+    indices: &[usize],
+    is_signer: &[bool],
+    is_writable: &[bool],
+    data: &[u8],
+) -> Vec<u64> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(indices.len() as u64).to_le_bytes());
+    for (position, &index) in indices.iter().enumerate() {
+        if let Some(first) = indices[..position].iter().position(|&other| other == index) {
+            bytes.push(first as u8);
+            bytes.extend_from_slice(&[0; 7]);
+            continue;
+        }
+        let account = &accounts[index];
+        bytes.push(NON_DUP_MARKER);
+        bytes.push(is_signer[index] as u8);
+        bytes.push(is_writable[index] as u8);
+        bytes.push(account.executable as u8);
+        // Original data length, filled in by the deserializer
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(account.key.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+        // Rent epoch
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+    }
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.extend_from_slice(PROGRAM_ID.as_ref());
+
+    let mut input = vec![0u64; bytes.len().div_ceil(8)];
+    for (word, chunk) in input.iter_mut().zip(bytes.chunks(8)) {
+        let mut buffer = [0; 8];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        *word = u64::from_ne_bytes(buffer);
+    }
+    input
+}
+
+// Invariants of a successful instruction, given the pool before and after it and every key that
+// signed: the transaction's keypairs and the PDAs a program signed for in a CPI
+fn check_invariants(
+    pre: &[SimAccount],
+    post: &[SimAccount],
+    is_writable: &[bool],
+    signers: &BTreeSet<Pubkey>,
+) {
+    // Runtime rules a program can never break on-chain
+    let lamports = |accounts: &[SimAccount]| -> u128 {
+        accounts
+            .iter()
+            .map(|account| u128::from(account.lamports))
+            .sum()
+    };
+    assert_eq!(
+        lamports(pre),
+        lamports(post),
+        "lamports created or destroyed"
+    );
+    for (index, (before, after)) in pre.iter().zip(post).enumerate() {
+        if !is_writable[index] {
+            assert_eq!(before, after, "read-only account {} changed", before.key);
+        }
+    }
+
+    // Supply cap
+    let (supply_before, supply_after) = (supply(pre), supply(post));
+    assert!(
+        supply_after <= TOTAL_SUPPLY,
+        "supply {} exceeds TOTAL_SUPPLY",
+        supply_after
+    );
+
+    let signed = |key: &Pubkey| signers.contains(key);
+    let config_before = config(pre);
+    let registry_before: Option<RoleRegistry> = state(&pre[ROLE_REGISTRY]);
+    let role_signed = |role: Role| {
+        registry_before
+            .as_ref()
+            .is_some_and(|registry| signers.iter().any(|signer| registry.has_role(signer, role)))
+    };
+    // Before InitializeMint the upgrade authority is the only one with any power
+    let authority_signed = match &config_before {
+        Some(config) => signed(&config.authority),
+        None => signed(&pre[AUTHORITY].key),
+    };
+
+    // New tokens come from a minter spending its own allowance
+    if supply_after > supply_before {
+        let minted = supply_after - supply_before;
+        let allowance_spent = ALLOWANCES.iter().any(|&(minter, allowance)| {
+            let remaining = state::<MinterAllowance>(&pre[allowance])
+                .zip(state::<MinterAllowance>(&post[allowance]))
+                .map(|(before, after)| before.remaining.checked_sub(after.remaining));
+            signed(&pre[minter].key)
+                && registry_before
+                    .as_ref()
+                    .is_some_and(|registry| registry.has_role(&pre[minter].key, Role::Minter))
+                && remaining == Some(Some(minted))
+        });
+        assert!(
+            allowance_spent,
+            "{} tokens minted without a minter signature and allowance",
+            minted
+        );
+        // Each minter waits out its own cooldown, its allowance records when it last minted
+        let cooled_down = ALLOWANCES.iter().any(|&(_, allowance)| {
+            state::<MinterAllowance>(&pre[allowance])
+                .zip(state::<MinterAllowance>(&post[allowance]))
+                .is_some_and(|(before, after)| {
+                    after.remaining < before.remaining
+                        && (before.last_mint_time == 0
+                            || after.last_mint_time >= before.last_mint_time + MINT_COOLDOWN)
+                })
+        });
+        assert!(cooled_down, "tokens minted during the minter's cooldown");
+        assert!(
+            !config_before.as_ref().is_some_and(|config| config.paused),
+            "tokens minted while paused"
+        );
+    }
+
+    // Roles and allowances belong to the authority; a minter may only spend its allowance
+    if pre[ROLE_REGISTRY] != post[ROLE_REGISTRY] {
+        assert!(
+            authority_signed,
+            "role registry changed without the authority"
+        );
+    }
+    for (minter, allowance) in ALLOWANCES {
+        if pre[allowance] != post[allowance] {
+            let spent = state::<MinterAllowance>(&pre[allowance])
+                .zip(state::<MinterAllowance>(&post[allowance]))
+                .is_some_and(|(before, after)| after.remaining < before.remaining);
+            assert!(
+                authority_signed || (spent && signed(&pre[minter].key)),
+                "minter allowance {} changed without the authority",
+                pre[allowance].key
+            );
+        }
+    }
+
+    // Program config
+    match (&config_before, config(post)) {
+        (None, Some(_)) => assert!(
+            authority_signed,
+            "program initialized without the upgrade authority"
+        ),
+        (Some(_), None) => panic!("program config destroyed"),
+        (Some(before), Some(after)) => {
+            let proposed = before.authority_transfer.proposed_authority;
+            assert_eq!(before.mint, after.mint, "config mint changed");
+            if after.authority != before.authority {
+                assert!(
+                    proposed == Some(after.authority) && signed(&after.authority),
+                    "authority changed without the proposed authority accepting"
+                );
+            }
+            if after.authority_transfer != before.authority_transfer {
+                assert!(
+                    authority_signed || proposed.is_some_and(|proposed| signed(&proposed)),
+                    "authority transfer changed without the authority"
+                );
+            }
+            if after.paused != before.paused {
+                assert!(
+                    role_signed(Role::Pauser),
+                    "pause state changed without a pauser"
+                );
+            }
+            if after.last_mint_time != before.last_mint_time {
+                assert!(
+                    role_signed(Role::Minter),
+                    "mint time changed without a minter"
+                );
+            }
+        }
+        (None, None) => {}
+    }
+}
+
+fn state<S: VersionedAccount>(account: &SimAccount) -> Option<S> {
+    if account.owner != PROGRAM_ID {
+        return None;
+    }
+    S::unpack(&account.data).ok()
+}
+
+fn config(accounts: &[SimAccount]) -> Option<ProgramConfig> {
+    state::<ProgramConfig>(&accounts[CONFIG]).filter(|config| config.is_initialized)
+}
+
+fn supply(accounts: &[SimAccount]) -> u64 {
+    let mint = &accounts[MINT];
+    if mint.owner != spl_token::id() {
+        return 0;
+    }
+    Mint::unpack(&mint.data).map_or(0, |mint| mint.supply)
+}
+
+// Syscall state of the instruction being processed
+struct SimState {
+    clock: Clock,
+    // Programs on the invoke stack, innermost last
+    callers: Vec<Pubkey>,
+    pda_signers: BTreeSet<Pubkey>,
+    // First CPI failure; it aborts the transaction even if the caller ignores it
+    cpi_error: Option<ProgramError>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+impl SimState {
+    fn new(clock: Clock) -> Self {
+        SimState {
+            clock,
+            callers: Vec::new(),
+            pda_signers: BTreeSet::new(),
+            cpi_error: None,
+            return_data: None,
+        }
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<SimState> = RefCell::new(SimState::new(Clock::default()));
+}
+
+fn install_stubs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+}
+
+fn current_program() -> Pubkey {
+    STATE.with(|state| state.borrow().callers.last().copied().unwrap_or(PROGRAM_ID))
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        if std::env::var_os("ARIA_FUZZ_LOG").is_some() {
+            println!("{}", message);
+        }
+    }
+
+    fn sol_log_compute_units(&self) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let result = invoke(instruction, account_infos, signers_seeds);
+        if let Err(error) = &result {
+            STATE.with(|state| {
+                state
+                    .borrow_mut()
+                    .cpi_error
+                    .get_or_insert_with(|| error.clone());
+            });
+        }
+        result
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = STATE.with(|state| state.borrow().clock.clone());
+        // SAFETY: the SDK passes a pointer to a `Clock`
+        unsafe { std::ptr::write(var_addr as *mut Clock, clock) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: the SDK passes a pointer to a `Rent`
+        unsafe { std::ptr::write(var_addr as *mut Rent, Rent::default()) };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        STATE.with(|state| state.borrow().return_data.clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let program_id = current_program();
+        STATE.with(|state| {
+            state.borrow_mut().return_data =
+                (!data.is_empty()).then(|| (program_id, data.to_vec()));
+        });
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        STATE.with(|state| state.borrow().callers.len() as u64)
+    }
+}
+
+// Cross-program invocation with the runtime's privilege checks: a callee account may only be a
+// signer if the caller's was or the caller signed for it as a PDA, and only writable if the
+// caller's was
+fn invoke(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let caller = current_program();
+    let mut callee_infos = Vec::with_capacity(instruction.accounts.len());
+    for meta in instruction.accounts.iter() {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let pda_signed = signers_seeds
+            .iter()
+            .any(|seeds| Pubkey::create_program_address(seeds, &caller) == Ok(meta.pubkey));
+        if meta.is_signer && !info.is_signer && !pda_signed {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if meta.is_signer && pda_signed {
+            STATE.with(|state| state.borrow_mut().pda_signers.insert(meta.pubkey));
+        }
+        let mut callee_info = info.clone();
+        callee_info.is_signer = meta.is_signer;
+        callee_info.is_writable = meta.is_writable;
+        callee_infos.push(callee_info);
+    }
+    invoke_program(&instruction.program_id, &callee_infos, &instruction.data)
+}
+
+fn invoke_program(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    STATE.with(|state| state.borrow_mut().callers.push(*program_id));
+    let result = if *program_id == PROGRAM_ID {
+        aria_token::process_instruction(program_id, accounts, data)
+    } else if *program_id == spl_token::id() {
+        spl_token::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == spl_token_2022::id() {
+        spl_token_2022::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == system_program::id() {
+        process_system_instruction(accounts, data)
+    } else if *program_id == METAPLEX_PROGRAM_ID {
+        // Legacy mint metadata is not modelled; the signer and writable checks above still apply
+        Ok(())
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    };
+    STATE.with(|state| state.borrow_mut().callers.pop());
+    result
+}
+
+// The system program instructions the program CPIs into
+fn process_system_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction: SystemInstruction =
+        bincode::deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let account = |index: usize| {
+        accounts
+            .get(index)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (account(0)?, account(1)?);
+            if to.lamports() > 0 {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            transfer(from, to, lamports)?;
+            allocate(to, space)?;
+            to.assign(&owner);
+            Ok(())
+        }
+        SystemInstruction::Transfer { lamports } => transfer(account(0)?, account(1)?, lamports),
+        SystemInstruction::Allocate { space } => allocate(account(0)?, space),
+        SystemInstruction::Assign { owner } => {
+            let account = account(0)?;
+            if !account.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if account.owner != &system_program::id() {
+                return Err(ProgramError::IllegalOwner);
+            }
+            account.assign(&owner);
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if from.owner != &system_program::id() || !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let remaining = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    let credited = to
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **to.try_borrow_mut_lamports()? = credited;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if account.owner != &system_program::id() || !account.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if space > MAX_PERMITTED_DATA_LENGTH {
+        return Err(ProgramError::InvalidArgument);
+    }
+    account.realloc(space as usize, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aria_token_interface::instruction::{ARIAInstruction, InitializeMintArgs, MintTokensArgs};
+    use aria_token_interface::metadata::find_metaplex_metadata_address;
+
+    fn mint_accounts(minter: usize) -> Vec<AccountRef> {
+        vec![
+            AccountRef::new(minter, true, true),
+            AccountRef::new(MINT, false, true),
+            AccountRef::new(DESTINATION, false, true),
+            AccountRef::new(TOKEN_PROGRAM, false, false),
+            AccountRef::new(CLOCK, false, false),
+            AccountRef::new(CONFIG, false, true),
+            AccountRef::new(MINT_AUTHORITY, false, false),
+            AccountRef::new(ROLE_REGISTRY, false, false),
+            AccountRef::new(MINTER_ALLOWANCE, false, true),
+        ]
+    }
+
+    fn mint_data(amount: u64) -> Vec<u8> {
+        ARIAInstruction::MintTokens(MintTokensArgs { amount }).pack()
+    }
+
+    #[test]
+    fn test_mint_runs_the_token_program() {
+        let mut runtime = Runtime::new(&Setup::default());
+        runtime
+            .process(&mint_accounts(MINTER), &mint_data(500))
+            .unwrap();
+        assert_eq!(runtime.mint_supply(), 500);
+        let destination = Account::unpack(&runtime.accounts[DESTINATION].data).unwrap();
+        assert_eq!(destination.amount, 500);
+
+        // Cooldown, then the cap
+        assert!(runtime
+            .process(&mint_accounts(MINTER), &mint_data(1))
+            .is_err());
+        runtime.advance_clock(MINT_COOLDOWN);
+        assert!(runtime
+            .process(&mint_accounts(MINTER), &mint_data(TOTAL_SUPPLY))
+            .is_err());
+        assert_eq!(runtime.mint_supply(), 500);
+    }
+
+    #[test]
+    fn test_failed_instruction_is_rolled_back() {
+        let mut runtime = Runtime::new(&Setup::default());
+        let before = runtime.accounts.clone();
+        let mut accounts = mint_accounts(ATTACKER);
+        accounts[8] = AccountRef::new(ATTACKER_ALLOWANCE, false, true);
+        assert!(runtime.process(&accounts, &mint_data(1)).is_err());
+
+        // Keypairs only: a PDA can never sign a transaction
+        let mut accounts = mint_accounts(MINTER);
+        accounts[0] = AccountRef::new(MINT_AUTHORITY, true, true);
+        assert!(runtime.process(&accounts, &mint_data(1)).is_err());
+        assert_eq!(runtime.accounts, before);
+    }
+
+    #[test]
+    fn test_initialize_creates_program_accounts() {
+        let mut runtime = Runtime::new(&Setup {
+            initialized: false,
+            ..Setup::default()
+        });
+        let accounts = [
+            AccountRef::new(AUTHORITY, true, true),
+            AccountRef::new(MINT, false, true),
+            AccountRef::new(RENT, false, false),
+            AccountRef::new(TOKEN_PROGRAM, false, false),
+            AccountRef::new(CONFIG, false, true),
+            AccountRef::new(MINT_AUTHORITY, false, false),
+            AccountRef::new(SYSTEM_PROGRAM, false, false),
+            AccountRef::new(ROLE_REGISTRY, false, true),
+            AccountRef::new(PROGRAM_DATA, false, false),
+        ];
+        // Legacy SPL Token mints also get Metaplex metadata
+        let metadata = find_metaplex_metadata_address(&runtime.key(MINT)).0;
+        let accounts = [
+            accounts.as_slice(),
+            &[
+                AccountRef::new(runtime.index_of(&metadata), false, true),
+                AccountRef::new(runtime.index_of(&METAPLEX_PROGRAM_ID), false, false),
+            ],
+        ]
+        .concat();
+        let data = ARIAInstruction::InitializeMint(InitializeMintArgs {
+            transfer_fee: None,
+            transfer_hook_program: None,
+            metadata_uri: String::new(),
+        })
+        .pack();
+        runtime.process(&accounts, &data).unwrap();
+        assert_eq!(runtime.accounts[CONFIG].data.len(), ProgramConfig::LEN);
+        assert_eq!(runtime.accounts[CONFIG].owner, PROGRAM_ID);
+        let mint = Mint::unpack(&runtime.accounts[MINT].data).unwrap();
+        assert_eq!(
+            mint.mint_authority,
+            COption::Some(runtime.key(MINT_AUTHORITY))
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// Versioned program accounts
+// Every program-owned state account starts with a two-byte header, the account type and the
+// layout version, followed by the borsh-encoded state. Accounts created before the header was
+// introduced start with their `is_initialized` flag instead; they are layout version 0 and are
+// upgraded in place with MigrateAccount. The first two account types are reserved for them.
+// A type whose layout changes bumps its version and decodes its older layouts in
+// `unpack_outdated`.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::AriaError;
+
+/// Length of the account type and version header
+pub const ACCOUNT_HEADER_LEN: usize = 2;
+
+/// Type tag of a program account, the first byte of its data
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AccountType {
+    /// Blank account, or an uninitialized account created before versioning
+    Uninitialized,
+    /// Initialized account created before versioning, whose first byte is `is_initialized`
+    Unversioned,
+    ProgramConfig,
+    RoleRegistry,
+    MinterAllowance,
+    ReferralConfig,
+    ReferralRecord,
+    ReferrerStats,
+    TreasuryConfig,
+    SpendRecord,
+    GovernanceConfig,
+    VoterRecord,
+    Proposal,
+    VoteRecord,
+    DelegateRecord,
+    Blocklist,
+    CheckpointRecord,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AccountHeader {
+    pub account_type: AccountType,
+    pub version: u8,
+}
+
+impl AccountHeader {
+    /// Read the header, treating accounts without one as version 0
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let header =
+            Self::deserialize(&mut &data[..]).map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(match header.account_type {
+            AccountType::Uninitialized | AccountType::Unversioned => AccountHeader {
+                account_type: header.account_type,
+                version: 0,
+            },
+            _ => header,
+        })
+    }
+}
+
+/// Program account with a type tag and a layout version
+pub trait VersionedAccount: BorshSerialize + BorshDeserialize {
+    const ACCOUNT_TYPE: AccountType;
+    /// Current layout version
+    const VERSION: u8;
+
+    /// Program derived address the account lives at
+    fn address(&self, program_id: &Pubkey) -> Pubkey;
+
+    /// Decode a current account. Blank accounts decode as uninitialized state, accounts with an
+    /// older layout fail with `AccountVersionMismatch` until they are migrated.
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let header = AccountHeader::unpack(data)?;
+        if header.account_type == AccountType::Unversioned
+            || (header.account_type == Self::ACCOUNT_TYPE && header.version != Self::VERSION)
+        {
+            return Err(AriaError::AccountVersionMismatch.into());
+        }
+        if header.account_type != Self::ACCOUNT_TYPE
+            && header.account_type != AccountType::Uninitialized
+        {
+            return Err(AriaError::InvalidAccountType.into());
+        }
+        Self::deserialize(&mut &data[ACCOUNT_HEADER_LEN..])
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Decode an account written with the older layout `version`, given its whole data, for
+    /// MigrateAccount. Returns the state and the account size the current layout needs. Only
+    /// version 0, the current state without a header, is known unless a type overrides this.
+    fn unpack_outdated(version: u8, data: &[u8]) -> Result<(Self, usize), ProgramError> {
+        if version != 0 {
+            return Err(AriaError::AccountVersionMismatch.into());
+        }
+        let state = Self::deserialize(&mut &data[..]).map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok((state, data.len() + ACCOUNT_HEADER_LEN))
+    }
+
+    /// Encode the account with the current header
+    fn pack_into(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let header = AccountHeader {
+            account_type: Self::ACCOUNT_TYPE,
+            version: Self::VERSION,
+        };
+        let mut writer = &mut data[..];
+        header
+            .serialize(&mut writer)
+            .and_then(|_| self.serialize(&mut writer))
+            .map_err(|_| ProgramError::AccountDataTooSmall)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roles::{MinterAllowance, MinterAllowanceV1};
+    use crate::ProgramConfig;
+
+    fn allowance() -> MinterAllowance {
+        MinterAllowance {
+            is_initialized: true,
+            minter: Pubkey::new_unique(),
+            remaining: 5,
+            last_mint_time: 0,
+        }
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let mut data = vec![0; MinterAllowance::LEN];
+        // Blank accounts decode as uninitialized
+        assert!(!MinterAllowance::unpack(&data).unwrap().is_initialized);
+
+        let state = allowance();
+        state.pack_into(&mut data).unwrap();
+        assert_eq!(data[0], AccountType::MinterAllowance as u8);
+        assert_eq!(data[1], MinterAllowance::VERSION);
+        assert_eq!(MinterAllowance::unpack(&data).unwrap(), state);
+
+        assert_eq!(
+            ProgramConfig::unpack(&data).unwrap_err(),
+            AriaError::InvalidAccountType.into()
+        );
+        assert_eq!(
+            state.pack_into(&mut data[..MinterAllowance::LEN - 1]),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_old_layouts_are_rejected() {
+        // Account created before versioning: the state without a header
+        let state = allowance();
+        let legacy = state.try_to_vec().unwrap();
+        assert_eq!(
+            AccountHeader::unpack(&legacy).unwrap(),
+            AccountHeader {
+                account_type: AccountType::Unversioned,
+                version: 0,
+            }
+        );
+        assert_eq!(
+            MinterAllowance::unpack(&legacy).unwrap_err(),
+            AriaError::AccountVersionMismatch.into()
+        );
+
+        let mut data = vec![0; MinterAllowance::LEN];
+        state.pack_into(&mut data).unwrap();
+        data[1] = MinterAllowance::VERSION + 1;
+        assert_eq!(
+            MinterAllowance::unpack(&data).unwrap_err(),
+            AriaError::AccountVersionMismatch.into()
+        );
+    }
+
+    #[test]
+    fn test_unpack_outdated() {
+        let old = MinterAllowanceV1 {
+            is_initialized: true,
+            minter: Pubkey::new_unique(),
+            remaining: 5,
+        };
+        let expected = MinterAllowance {
+            is_initialized: true,
+            minter: old.minter,
+            remaining: 5,
+            last_mint_time: 0,
+        };
+
+        // Version 0 has no header, version 1 has the header and no cooldown
+        let version_0 = old.try_to_vec().unwrap();
+        let mut version_1 = vec![AccountType::MinterAllowance as u8, 1];
+        version_1.extend_from_slice(&version_0);
+        for (version, data) in [(0, &version_0), (1, &version_1)] {
+            let (state, len) = MinterAllowance::unpack_outdated(version, data).unwrap();
+            assert_eq!(state, expected);
+            assert_eq!(len, MinterAllowance::LEN);
+        }
+        assert_eq!(
+            MinterAllowance::unpack_outdated(MinterAllowance::VERSION, &version_1).unwrap_err(),
+            AriaError::AccountVersionMismatch.into()
+        );
+
+        // Types whose layout never changed only know version 0
+        assert_eq!(
+            ProgramConfig::unpack_outdated(1, &version_1).unwrap_err(),
+            AriaError::AccountVersionMismatch.into()
+        );
+    }
+}
@@ -0,0 +1,81 @@
+// Balance checkpoint accounts
+// Locked balance history per owner, read by governance to weigh votes as of a slot.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::account::{AccountType, VersionedAccount, ACCOUNT_HEADER_LEN};
+use crate::error::AriaError;
+
+pub const CHECKPOINT_SEED: &[u8] = b"checkpoint";
+
+/// Number of checkpoints kept per account; the oldest are pruned first
+pub const MAX_CHECKPOINTS: usize = 64;
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub struct Checkpoint {
+    pub slot: u64,
+    pub amount: u64,
+}
+
+// Locked balance history of one owner
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Default)]
+pub struct CheckpointRecord {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    /// Set once old checkpoints have been pruned; earlier slots can no longer be answered
+    pub pruned: bool,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl CheckpointRecord {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 32 + 1 + 4 + MAX_CHECKPOINTS * 16;
+
+    /// Record the balance from `slot` onwards
+    pub fn record(&mut self, slot: u64, amount: u64) -> ProgramResult {
+        match self.checkpoints.last_mut() {
+            Some(last) if last.slot > slot => return Err(ProgramError::InvalidArgument),
+            Some(last) if last.slot == slot => last.amount = amount,
+            _ => self.checkpoints.push(Checkpoint { slot, amount }),
+        }
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            self.checkpoints.remove(0);
+            self.pruned = true;
+        }
+        Ok(())
+    }
+
+    /// Balance at the start of `slot`, i.e. after every change made in earlier slots
+    pub fn amount_at(&self, slot: u64) -> Result<u64, ProgramError> {
+        match self
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.slot < slot)
+        {
+            Some(checkpoint) => Ok(checkpoint.amount),
+            None if self.pruned => {
+                msg!("No checkpoint before slot {}", slot);
+                Err(AriaError::CheckpointUnavailable.into())
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+impl VersionedAccount for CheckpointRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::CheckpointRecord;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_checkpoint_address(program_id, &self.mint, &self.owner).0
+    }
+}
+
+pub fn find_checkpoint_address(program_id: &Pubkey, mint: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CHECKPOINT_SEED, mint.as_ref(), owner.as_ref()],
+        program_id,
+    )
+}
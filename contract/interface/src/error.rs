@@ -0,0 +1,215 @@
+// Program errors
+
+use solana_program::program_error::ProgramError;
+use thiserror::Error;
+
+#[derive(Error, Debug, Copy, Clone)]
+pub enum AriaError {
+    #[error("Invalid instruction data")]
+    InvalidInstructionData,
+
+    #[error("Insufficient authority")]
+    InsufficientAuthority,
+
+    #[error("Invalid account count")]
+    InvalidAccountCount,
+
+    #[error("Token mint mismatch")]
+    TokenMintMismatch,
+
+    #[error("Exceeds supply cap")]
+    ExceedsSupplyCap,
+
+    #[error("Mint cooldown active")]
+    MintCooldownActive,
+
+    #[error("Authority transfer request not found")]
+    AuthorityTransferNotFound,
+
+    #[error("Authority transfer request expired")]
+    AuthorityTransferExpired,
+
+    #[error("Invalid program address")]
+    InvalidProgramAddress,
+
+    #[error("Account already initialized")]
+    AccountAlreadyInitialized,
+
+    #[error("Account not initialized")]
+    AccountNotInitialized,
+
+    #[error("Arithmetic overflow")]
+    ArithmeticOverflow,
+
+    #[error("Invalid basis points")]
+    InvalidBasisPoints,
+
+    #[error("Users cannot refer themselves")]
+    SelfReferral,
+
+    #[error("Referrer mismatch")]
+    ReferrerMismatch,
+
+    #[error("Invalid approver set")]
+    InvalidApproverSet,
+
+    #[error("Invalid approval threshold")]
+    InvalidThreshold,
+
+    #[error("Signer is not an approver")]
+    NotAnApprover,
+
+    #[error("Already approved")]
+    AlreadyApproved,
+
+    #[error("Approval threshold not met")]
+    ThresholdNotMet,
+
+    #[error("Spend already executed")]
+    SpendAlreadyExecuted,
+
+    #[error("Invalid governance config")]
+    InvalidGovernanceConfig,
+
+    #[error("Invalid proposal")]
+    InvalidProposal,
+
+    #[error("Insufficient voting power")]
+    InsufficientVotingPower,
+
+    #[error("Voting is closed")]
+    VotingClosed,
+
+    #[error("Voting is still in progress")]
+    VotingInProgress,
+
+    #[error("Already voted")]
+    AlreadyVoted,
+
+    #[error("Proposal did not pass")]
+    ProposalNotPassed,
+
+    #[error("Proposal already executed")]
+    ProposalAlreadyExecuted,
+
+    #[error("Holders cannot delegate to themselves")]
+    SelfDelegation,
+
+    #[error("Signer is not the delegate")]
+    NotDelegate,
+
+    #[error("Checkpoint history does not reach back to the requested slot")]
+    CheckpointUnavailable,
+
+    #[error("Operation requires a Token-2022 mint")]
+    Token2022Required,
+
+    #[error("Address is blocked")]
+    AddressBlocked,
+
+    #[error("Blocklist is full")]
+    BlocklistFull,
+
+    #[error("Program is paused")]
+    ProgramPaused,
+
+    #[error("Role registry is full")]
+    RoleRegistryFull,
+
+    #[error("Minter allowance exceeded")]
+    MinterAllowanceExceeded,
+
+    #[error("Unknown instruction discriminator")]
+    UnknownInstruction,
+
+    #[error("Account layout is outdated, migrate it with MigrateAccount")]
+    AccountVersionMismatch,
+
+    #[error("Account has a different account type")]
+    InvalidAccountType,
+
+    #[error("Account must be writable")]
+    AccountNotWritable,
+
+    #[error("Account is not the expected sysvar")]
+    InvalidSysvar,
+
+    #[error("Mint or token account is not owned by a token program")]
+    InvalidTokenAccountOwner,
+
+    #[error("Mint has no mint authority")]
+    MintAuthorityNotSet,
+
+    #[error("Mint authority is not the program's mint authority PDA")]
+    MintAuthorityMismatch,
+
+    #[error("Metaplex metadata has no additional fields")]
+    MetadataFieldUnsupported,
+
+    #[error("Extra account metas must start with the blocklist")]
+    InvalidExtraAccountMetas,
+
+    #[error("Instruction is retired, use its V2 replacement")]
+    InstructionDeprecated,
+}
+
+impl AriaError {
+    /// Every error, in code order
+    pub const ALL: [AriaError; 49] = [
+        AriaError::InvalidInstructionData,
+        AriaError::InsufficientAuthority,
+        AriaError::InvalidAccountCount,
+        AriaError::TokenMintMismatch,
+        AriaError::ExceedsSupplyCap,
+        AriaError::MintCooldownActive,
+        AriaError::AuthorityTransferNotFound,
+        AriaError::AuthorityTransferExpired,
+        AriaError::InvalidProgramAddress,
+        AriaError::AccountAlreadyInitialized,
+        AriaError::AccountNotInitialized,
+        AriaError::ArithmeticOverflow,
+        AriaError::InvalidBasisPoints,
+        AriaError::SelfReferral,
+        AriaError::ReferrerMismatch,
+        AriaError::InvalidApproverSet,
+        AriaError::InvalidThreshold,
+        AriaError::NotAnApprover,
+        AriaError::AlreadyApproved,
+        AriaError::ThresholdNotMet,
+        AriaError::SpendAlreadyExecuted,
+        AriaError::InvalidGovernanceConfig,
+        AriaError::InvalidProposal,
+        AriaError::InsufficientVotingPower,
+        AriaError::VotingClosed,
+        AriaError::VotingInProgress,
+        AriaError::AlreadyVoted,
+        AriaError::ProposalNotPassed,
+        AriaError::ProposalAlreadyExecuted,
+        AriaError::SelfDelegation,
+        AriaError::NotDelegate,
+        AriaError::CheckpointUnavailable,
+        AriaError::Token2022Required,
+        AriaError::AddressBlocked,
+        AriaError::BlocklistFull,
+        AriaError::ProgramPaused,
+        AriaError::RoleRegistryFull,
+        AriaError::MinterAllowanceExceeded,
+        AriaError::UnknownInstruction,
+        AriaError::AccountVersionMismatch,
+        AriaError::InvalidAccountType,
+        AriaError::AccountNotWritable,
+        AriaError::InvalidSysvar,
+        AriaError::InvalidTokenAccountOwner,
+        AriaError::MintAuthorityNotSet,
+        AriaError::MintAuthorityMismatch,
+        AriaError::MetadataFieldUnsupported,
+        AriaError::InvalidExtraAccountMetas,
+        AriaError::InstructionDeprecated,
+    ];
+}
+
+impl From<AriaError> for ProgramError {
+    fn from(e: AriaError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,419 @@
+// Structured events
+// Every state change emits one borsh-encoded event through `sol_log_data`, so indexers do not
+// have to parse the human-readable `msg!` logs. The log data is the 8-byte discriminator of
+// the event type followed by the borsh-encoded event; the discriminator is the first 8 bytes
+// of sha256("event:<EventName>") and never changes for an event type. New fields are only
+// appended to the end of an event.
+//
+// Off-chain, `AriaEvent::from_log` decodes a "Program data: ..." log line.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+use crate::account::AccountType;
+use crate::governance::Vote;
+use crate::metadata::MetadataField;
+use crate::roles::Role;
+use crate::treasury::TreasuryAsset;
+
+/// Prefix of the log line written by `sol_log_data`
+pub const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Event type with a stable discriminator
+pub trait Event: BorshSerialize {
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Discriminator followed by the borsh-encoded event
+    fn to_log_data(&self) -> Vec<u8> {
+        let mut data = Self::DISCRIMINATOR.to_vec();
+        // Serializing into a Vec cannot fail
+        self.serialize(&mut data).unwrap();
+        data
+    }
+}
+
+/// Emit `event` to the program logs
+pub fn emit<E: Event>(event: &E) {
+    sol_log_data(&[&event.to_log_data()]);
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct MintInitialized {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub token_program: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct TokensMinted {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct AuthorityTransferProposed {
+    pub authority: Pubkey,
+    pub proposed_authority: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct AuthorityTransferAccepted {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct RoleGranted {
+    pub role: Role,
+    pub member: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub member: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct MinterAllowanceUpdated {
+    pub minter: Pubkey,
+    pub remaining: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct Paused {
+    pub pauser: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct Unpaused {
+    pub pauser: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ReferralShareSet {
+    pub mint: Pubkey,
+    pub share_bps: u16,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ReferrerRegistered {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ReferralPayment {
+    pub mint: Pubkey,
+    pub payer: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub referral_amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct TreasuryConfigured {
+    pub mint: Pubkey,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct SpendProposed {
+    pub mint: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub asset: TreasuryAsset,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct SpendApproved {
+    pub mint: Pubkey,
+    pub index: u64,
+    pub approver: Pubkey,
+    pub approvals: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct SpendExecuted {
+    pub mint: Pubkey,
+    pub index: u64,
+    pub asset: TreasuryAsset,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct GovernanceConfigured {
+    pub mint: Pubkey,
+    pub voting_period: i64,
+    pub quorum_bps: u16,
+    pub approval_threshold_bps: u16,
+    pub min_proposal_power: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct GoverningTokensDeposited {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub deposited_amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct GoverningTokensWithdrawn {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub deposited_amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ProposalCreated {
+    pub mint: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub voting_ends_at: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub vote: Vote,
+    pub weight: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ProposalExecuted {
+    pub proposal: Pubkey,
+    pub index: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct DelegateSet {
+    pub mint: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct DelegateRevoked {
+    pub mint: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct WithheldFeesHarvested {
+    pub mint: Pubkey,
+    pub source_count: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct WithheldFeesWithdrawn {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct TransferFeeUpdated {
+    pub mint: Pubkey,
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct MetadataUpdated {
+    pub mint: Pubkey,
+    pub field: MetadataField,
+    pub value: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct TransferHookInitialized {
+    pub mint: Pubkey,
+    pub compliance_authority: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ExtraAccountMetasInitialized {
+    pub mint: Pubkey,
+    pub meta_count: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ExtraAccountMetasUpdated {
+    pub mint: Pubkey,
+    pub meta_count: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct ComplianceAuthoritySet {
+    pub mint: Pubkey,
+    pub compliance_authority: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct AddressBlocked {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct AddressUnblocked {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub account_type: AccountType,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+// Implement `Event` for each event type and build the `AriaEvent` decoder over all of them
+macro_rules! events {
+    ($($name:ident = $discriminator:expr,)*) => {
+        $(
+            impl Event for $name {
+                const DISCRIMINATOR: [u8; 8] = $discriminator;
+            }
+        )*
+
+        /// Any event emitted by the program
+        #[derive(Debug, PartialEq, Clone)]
+        pub enum AriaEvent {
+            $($name($name),)*
+        }
+
+        impl AriaEvent {
+            /// Decode the data of one `sol_log_data` call; None for data of other programs
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                if data.len() < 8 {
+                    return None;
+                }
+                let (discriminator, body) = data.split_at(8);
+                $(
+                    if discriminator == $name::DISCRIMINATOR {
+                        return $name::try_from_slice(body).ok().map(AriaEvent::$name);
+                    }
+                )*
+                None
+            }
+        }
+
+        #[cfg(test)]
+        const EVENT_NAMES: &[(&str, [u8; 8])] = &[$((stringify!($name), $name::DISCRIMINATOR),)*];
+    };
+}
+
+events! {
+    MintInitialized = [46, 171, 107, 161, 175, 104, 236, 230],
+    TokensMinted = [207, 212, 128, 194, 175, 54, 64, 24],
+    AuthorityTransferProposed = [103, 244, 27, 116, 177, 4, 100, 119],
+    AuthorityTransferAccepted = [149, 165, 140, 221, 104, 203, 239, 121],
+    AuthorityTransferCancelled = [31, 228, 187, 148, 20, 99, 237, 48],
+    RoleGranted = [220, 183, 89, 228, 143, 63, 246, 58],
+    RoleRevoked = [167, 183, 52, 229, 126, 206, 62, 61],
+    MinterAllowanceUpdated = [89, 157, 202, 182, 221, 13, 211, 6],
+    Paused = [172, 248, 5, 253, 49, 255, 255, 232],
+    Unpaused = [156, 150, 47, 174, 120, 216, 93, 117],
+    ReferralShareSet = [240, 168, 10, 13, 143, 39, 39, 68],
+    ReferrerRegistered = [106, 198, 28, 51, 115, 46, 57, 3],
+    ReferralPayment = [31, 247, 183, 187, 24, 196, 121, 201],
+    TreasuryConfigured = [124, 246, 106, 48, 101, 163, 135, 61],
+    SpendProposed = [226, 5, 1, 200, 140, 227, 44, 226],
+    SpendApproved = [203, 131, 83, 160, 153, 205, 201, 150],
+    SpendExecuted = [27, 251, 149, 186, 175, 148, 73, 5],
+    GovernanceConfigured = [20, 49, 34, 211, 4, 57, 158, 250],
+    GoverningTokensDeposited = [34, 193, 252, 167, 45, 206, 235, 17],
+    GoverningTokensWithdrawn = [64, 12, 42, 41, 58, 143, 139, 243],
+    ProposalCreated = [186, 8, 160, 108, 81, 13, 51, 206],
+    VoteCast = [39, 53, 195, 104, 188, 17, 225, 213],
+    ProposalExecuted = [92, 213, 189, 201, 101, 83, 111, 83],
+    DelegateSet = [103, 126, 239, 131, 201, 31, 212, 253],
+    DelegateRevoked = [10, 200, 133, 29, 238, 207, 193, 124],
+    WithheldFeesHarvested = [73, 135, 172, 158, 159, 108, 23, 225],
+    WithheldFeesWithdrawn = [242, 252, 85, 249, 107, 109, 15, 116],
+    TransferFeeUpdated = [57, 2, 237, 161, 155, 18, 22, 95],
+    MetadataUpdated = [132, 36, 215, 246, 166, 90, 189, 44],
+    TransferHookInitialized = [101, 121, 76, 195, 166, 39, 198, 176],
+    ComplianceAuthoritySet = [143, 236, 86, 84, 149, 218, 52, 98],
+    AddressBlocked = [49, 185, 252, 29, 76, 36, 115, 12],
+    AddressUnblocked = [12, 41, 86, 45, 28, 216, 106, 21],
+    AccountMigrated = [153, 121, 252, 128, 30, 241, 166, 101],
+    ExtraAccountMetasInitialized = [171, 7, 254, 0, 117, 203, 227, 117],
+    ExtraAccountMetasUpdated = [166, 91, 240, 17, 80, 22, 112, 155],
+}
+
+impl AriaEvent {
+    /// Decode a "Program data: ..." line from the transaction logs.
+    /// Returns None for other log lines and for data that is not an ARIA event.
+    pub fn from_log(line: &str) -> Option<Self> {
+        let encoded = line.strip_prefix(PROGRAM_DATA_PREFIX)?;
+        // The program logs a single data field per event
+        let data = STANDARD.decode(encoded.trim()).ok()?;
+        Self::decode(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::hash::hash;
+
+    #[test]
+    fn test_discriminators_are_stable() {
+        for (name, discriminator) in EVENT_NAMES {
+            let expected = hash(format!("event:{}", name).as_bytes()).to_bytes();
+            assert_eq!(discriminator[..], expected[..8], "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_decode_event() {
+        let event = TokensMinted {
+            mint: Pubkey::new_unique(),
+            minter: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            amount: 1_000,
+        };
+        let line = format!(
+            "{}{}",
+            PROGRAM_DATA_PREFIX,
+            STANDARD.encode(event.to_log_data())
+        );
+        assert_eq!(
+            AriaEvent::from_log(&line),
+            Some(AriaEvent::TokensMinted(event))
+        );
+
+        // Other log lines and truncated or foreign data are ignored
+        assert_eq!(
+            AriaEvent::from_log("Program log: Instruction: Mint Tokens"),
+            None
+        );
+        let paused = Paused {
+            pauser: Pubkey::new_unique(),
+        };
+        let data = paused.to_log_data();
+        assert_eq!(AriaEvent::decode(&data[..20]), None);
+        assert_eq!(AriaEvent::decode(&[0; 40]), None);
+    }
+}
@@ -0,0 +1,26 @@
+// Token-2022 transfer fee settings
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::entrypoint::ProgramResult;
+
+use crate::error::AriaError;
+use crate::MAX_BASIS_POINTS;
+
+/// Transfer fee settings for the Token-2022 transfer-fee extension
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub struct TransferFeeParams {
+    /// Fee charged on each transfer, in basis points
+    pub transfer_fee_basis_points: u16,
+    /// Upper bound on the fee of a single transfer, in base units
+    pub maximum_fee: u64,
+}
+
+impl TransferFeeParams {
+    /// Fail with `InvalidBasisPoints` above 100%
+    pub fn check(&self) -> ProgramResult {
+        if self.transfer_fee_basis_points > MAX_BASIS_POINTS {
+            return Err(AriaError::InvalidBasisPoints.into());
+        }
+        Ok(())
+    }
+}
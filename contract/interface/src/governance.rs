@@ -0,0 +1,293 @@
+// Governance accounts
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::account::{AccountType, VersionedAccount, ACCOUNT_HEADER_LEN};
+use crate::error::AriaError;
+use crate::MAX_BASIS_POINTS;
+
+pub const GOVERNANCE_SEED: &[u8] = b"governance";
+pub const GOVERNANCE_AUTHORITY_SEED: &[u8] = b"governance_authority";
+pub const GOVERNANCE_VAULT_SEED: &[u8] = b"governance_vault";
+pub const VOTER_SEED: &[u8] = b"voter";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VOTE_SEED: &[u8] = b"vote";
+pub const DELEGATE_SEED: &[u8] = b"delegate";
+
+/// Maximum length of a proposal description, in bytes
+pub const MAX_DESCRIPTION_LEN: usize = 256;
+/// Maximum number of instructions carried by a proposal
+pub const MAX_PROPOSAL_INSTRUCTIONS: usize = 8;
+
+// Governance parameters for a mint
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct GovernanceConfig {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    /// Length of the voting window in seconds
+    pub voting_period: i64,
+    /// Minimum total votes, as basis points of the supply when the proposal is created
+    pub quorum_bps: u16,
+    /// Minimum share of for votes among for and against votes
+    pub approval_threshold_bps: u16,
+    /// Voting power needed to create a proposal
+    pub min_proposal_power: u64,
+    pub proposal_count: u64,
+}
+
+impl GovernanceConfig {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 8 + 2 + 2 + 8 + 8;
+}
+
+impl VersionedAccount for GovernanceConfig {
+    const ACCOUNT_TYPE: AccountType = AccountType::GovernanceConfig;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_governance_address(program_id, &self.mint).0
+    }
+}
+
+// Governing tokens deposited by a voter
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Default)]
+pub struct VoterRecord {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub deposited_amount: u64,
+}
+
+impl VoterRecord {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 32 + 8;
+}
+
+impl VersionedAccount for VoterRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::VoterRecord;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_voter_record_address(program_id, &self.mint, &self.owner).0
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone)]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// Serialized instruction executed when a proposal passes
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone)]
+pub struct ProposalInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<ProposalAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl From<&Instruction> for ProposalInstruction {
+    fn from(instruction: &Instruction) -> Self {
+        ProposalInstruction {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| ProposalAccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: instruction.data.clone(),
+        }
+    }
+}
+
+impl From<&ProposalInstruction> for Instruction {
+    fn from(instruction: &ProposalInstruction) -> Self {
+        Instruction {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: instruction.data.clone(),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub enum Vote {
+    For,
+    Against,
+    Abstain,
+}
+
+// A governance proposal. Quorum, threshold and voting power are fixed when the proposal is created.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct Proposal {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub created_at: i64,
+    /// Voting power is the locked balance at the start of this slot
+    pub snapshot_slot: u64,
+    pub voting_ends_at: i64,
+    pub quorum_votes: u64,
+    pub approval_threshold_bps: u16,
+    pub for_votes: u64,
+    pub against_votes: u64,
+    pub abstain_votes: u64,
+    pub executed: bool,
+    pub executed_at: i64,
+    pub description: String,
+    pub instructions: Vec<ProposalInstruction>,
+}
+
+impl Proposal {
+    pub fn total_votes(&self) -> u64 {
+        self.for_votes
+            .saturating_add(self.against_votes)
+            .saturating_add(self.abstain_votes)
+    }
+
+    // Quorum reached and for votes meet the approval threshold
+    pub fn is_passed(&self) -> bool {
+        if self.for_votes == 0 || self.total_votes() < self.quorum_votes {
+            return false;
+        }
+        let decisive = self.for_votes as u128 + self.against_votes as u128;
+        self.for_votes as u128 * MAX_BASIS_POINTS as u128
+            >= decisive * self.approval_threshold_bps as u128
+    }
+
+    /// Add `weight` to the tally of `vote`
+    pub fn add_votes(&mut self, vote: Vote, weight: u64) -> ProgramResult {
+        let tally = match vote {
+            Vote::For => &mut self.for_votes,
+            Vote::Against => &mut self.against_votes,
+            Vote::Abstain => &mut self.abstain_votes,
+        };
+        *tally = tally
+            .checked_add(weight)
+            .ok_or(AriaError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+impl VersionedAccount for Proposal {
+    const ACCOUNT_TYPE: AccountType = AccountType::Proposal;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_proposal_address(program_id, &self.mint, self.index).0
+    }
+}
+
+// One vote per owner of voting power and proposal
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct VoteRecord {
+    pub is_initialized: bool,
+    pub proposal: Pubkey,
+    /// Owner of the voting power
+    pub voter: Pubkey,
+    /// The voter, or the delegate who voted on their behalf
+    pub cast_by: Pubkey,
+    pub vote: Vote,
+    pub weight: u64,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 32 + 32 + 1 + 8;
+}
+
+impl VersionedAccount for VoteRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::VoteRecord;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_vote_record_address(program_id, &self.proposal, &self.voter).0
+    }
+}
+
+// Voting power assigned by a holder to a delegate. Closed when revoked.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct DelegateRecord {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+    pub delegated_at: i64,
+}
+
+impl DelegateRecord {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 32 + 32 + 8;
+}
+
+impl VersionedAccount for DelegateRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::DelegateRecord;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_delegate_record_address(program_id, &self.mint, &self.delegator).0
+    }
+}
+
+pub fn find_governance_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GOVERNANCE_SEED, mint.as_ref()], program_id)
+}
+
+/// PDA that signs executed proposals. Transfer the program authority here to put it under governance.
+pub fn find_governance_authority_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GOVERNANCE_AUTHORITY_SEED, mint.as_ref()], program_id)
+}
+
+/// PDA owning the token account that holds deposited governing tokens
+pub fn find_governance_vault_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GOVERNANCE_VAULT_SEED, mint.as_ref()], program_id)
+}
+
+pub fn find_voter_record_address(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTER_SEED, mint.as_ref(), owner.as_ref()], program_id)
+}
+
+pub fn find_proposal_address(program_id: &Pubkey, mint: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PROPOSAL_SEED, mint.as_ref(), &index.to_le_bytes()],
+        program_id,
+    )
+}
+
+pub fn find_vote_record_address(
+    program_id: &Pubkey,
+    proposal: &Pubkey,
+    voter: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_SEED, proposal.as_ref(), voter.as_ref()], program_id)
+}
+
+pub fn find_delegate_record_address(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    delegator: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DELEGATE_SEED, mint.as_ref(), delegator.as_ref()],
+        program_id,
+    )
+}
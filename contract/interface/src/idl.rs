@@ -0,0 +1,400 @@
+// JSON IDL
+// Describes instructions, their arguments and accounts, the program account layouts with their
+// PDA seeds, and the error codes, so clients in other languages can encode instructions and
+// decode accounts without reimplementing the Rust definitions. Argument and field types come
+// from the borsh schema of the instruction args types and the account types, account lists
+// from the `InstructionDiscriminator::accounts` table and error codes and messages from
+// `AriaError::ALL`.
+//
+// The generated IDL is checked in at `idl/aria_token.json`; a test fails when it drifts from
+// the code. Regenerate it with `ARIA_UPDATE_IDL=1 cargo test -p aria-token-interface idl`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use borsh::schema::{BorshSchema, BorshSchemaContainer, Declaration, Definition, Fields};
+use serde_json::{json, Value};
+
+use crate::account::{VersionedAccount, ACCOUNT_HEADER_LEN};
+use crate::checkpoint::{CheckpointRecord, CHECKPOINT_SEED};
+use crate::error::AriaError;
+use crate::governance::{
+    DelegateRecord, GovernanceConfig, Proposal, VoteRecord, VoterRecord, DELEGATE_SEED,
+    GOVERNANCE_AUTHORITY_SEED, GOVERNANCE_SEED, GOVERNANCE_VAULT_SEED, PROPOSAL_SEED, VOTER_SEED,
+    VOTE_SEED,
+};
+use crate::instruction::InstructionDiscriminator;
+use crate::referral::{
+    ReferralConfig, ReferralRecord, ReferrerStats, REFERRAL_CONFIG_SEED, REFERRAL_RECORD_SEED,
+    REFERRER_STATS_SEED,
+};
+use crate::roles::{MinterAllowance, RoleRegistry, MINTER_ALLOWANCE_SEED, ROLES_SEED};
+use crate::transfer_hook::{Blocklist, BLOCKLIST_SEED};
+use crate::treasury::{
+    SpendRecord, TreasuryConfig, SPEND_SEED, TREASURY_SEED, TREASURY_VAULT_SEED,
+};
+use crate::view::{ConfigView, PendingAuthorityTransfer, ProgramVersion};
+use crate::{ProgramConfig, CONFIG_SEED, MINT_AUTHORITY_SEED};
+
+/// Path of the checked-in IDL, relative to the interface crate
+pub const IDL_PATH: &str = "../idl/aria_token.json";
+
+// Seed of the transfer hook interface's extra-account-metas PDA
+const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+enum Seed {
+    Const(&'static [u8]),
+    Pubkey(&'static str),
+    U64(&'static str),
+}
+
+fn seeds_json(seeds: &[Seed]) -> Value {
+    seeds
+        .iter()
+        .map(|seed| match seed {
+            Seed::Const(bytes) => json!({
+                "kind": "const",
+                "value": String::from_utf8_lossy(bytes),
+            }),
+            Seed::Pubkey(name) => json!({ "kind": "pubkey", "name": name }),
+            Seed::U64(name) => json!({ "kind": "u64_le", "name": name }),
+        })
+        .collect()
+}
+
+fn fields_json(fields: &Fields) -> Value {
+    match fields {
+        Fields::NamedFields(fields) => fields
+            .iter()
+            .map(|(name, declaration)| json!({ "name": name, "type": declaration }))
+            .collect(),
+        Fields::UnnamedFields(fields) => fields
+            .iter()
+            .map(|declaration| json!({ "type": declaration }))
+            .collect(),
+        Fields::Empty => json!([]),
+    }
+}
+
+fn struct_fields(definitions: &HashMap<Declaration, Definition>, declaration: &str) -> Value {
+    match definitions.get(declaration) {
+        Some(Definition::Struct { fields }) => fields_json(fields),
+        _ => json!([]),
+    }
+}
+
+// Named type definition; enum variants are inlined with their fields
+fn type_json(
+    definitions: &HashMap<Declaration, Definition>,
+    name: &str,
+    definition: &Definition,
+) -> Option<Value> {
+    match definition {
+        Definition::Struct { fields } => Some(json!({
+            "name": name,
+            "kind": "struct",
+            "fields": fields_json(fields),
+        })),
+        Definition::Enum { variants } => Some(json!({
+            "name": name,
+            "kind": "enum",
+            "variants": variants
+                .iter()
+                .map(|(variant, declaration)| json!({
+                    "name": variant,
+                    "fields": struct_fields(definitions, declaration),
+                }))
+                .collect::<Vec<_>>(),
+        })),
+        _ => None,
+    }
+}
+
+// Structs the schema derive adds for the variants of an enum, named `{Enum}{Variant}`
+fn variant_declarations(definitions: &HashMap<Declaration, Definition>) -> BTreeSet<Declaration> {
+    definitions
+        .iter()
+        .filter_map(|(name, definition)| match definition {
+            Definition::Enum { variants } => Some((name, variants)),
+            _ => None,
+        })
+        .flat_map(|(name, variants)| {
+            variants
+                .iter()
+                .map(move |(variant, _)| format!("{}{}", name, variant))
+        })
+        .filter(|declaration| definitions.contains_key(declaration))
+        .collect()
+}
+
+macro_rules! state_accounts {
+    ($($account:ty => [$($seed:expr),* $(,)?]),* $(,)?) => {
+        vec![$((
+            <$account as BorshSchema>::schema_container(),
+            (
+                <$account as VersionedAccount>::ACCOUNT_TYPE as u8,
+                <$account as VersionedAccount>::VERSION,
+            ),
+            vec![$($seed),*],
+        )),*]
+    };
+}
+
+/// Generate the IDL
+pub fn generate_idl() -> Value {
+    let state_accounts = state_accounts! {
+        ProgramConfig => [Seed::Const(CONFIG_SEED)],
+        RoleRegistry => [Seed::Const(ROLES_SEED)],
+        MinterAllowance => [Seed::Const(MINTER_ALLOWANCE_SEED), Seed::Pubkey("minter")],
+        ReferralConfig => [Seed::Const(REFERRAL_CONFIG_SEED), Seed::Pubkey("mint")],
+        ReferralRecord => [
+            Seed::Const(REFERRAL_RECORD_SEED),
+            Seed::Pubkey("mint"),
+            Seed::Pubkey("user"),
+        ],
+        ReferrerStats => [
+            Seed::Const(REFERRER_STATS_SEED),
+            Seed::Pubkey("mint"),
+            Seed::Pubkey("referrer"),
+        ],
+        TreasuryConfig => [Seed::Const(TREASURY_SEED), Seed::Pubkey("mint")],
+        SpendRecord => [Seed::Const(SPEND_SEED), Seed::Pubkey("mint"), Seed::U64("index")],
+        GovernanceConfig => [Seed::Const(GOVERNANCE_SEED), Seed::Pubkey("mint")],
+        VoterRecord => [Seed::Const(VOTER_SEED), Seed::Pubkey("mint"), Seed::Pubkey("owner")],
+        Proposal => [Seed::Const(PROPOSAL_SEED), Seed::Pubkey("mint"), Seed::U64("index")],
+        VoteRecord => [
+            Seed::Const(VOTE_SEED),
+            Seed::Pubkey("proposal"),
+            Seed::Pubkey("voter"),
+        ],
+        DelegateRecord => [
+            Seed::Const(DELEGATE_SEED),
+            Seed::Pubkey("mint"),
+            Seed::Pubkey("delegator"),
+        ],
+        Blocklist => [Seed::Const(BLOCKLIST_SEED), Seed::Pubkey("mint")],
+        CheckpointRecord => [
+            Seed::Const(CHECKPOINT_SEED),
+            Seed::Pubkey("mint"),
+            Seed::Pubkey("owner"),
+        ],
+    };
+    // PDAs without program state: signers and vaults
+    let pdas = [
+        (
+            "mint_authority",
+            vec![Seed::Const(MINT_AUTHORITY_SEED), Seed::Pubkey("mint")],
+        ),
+        (
+            "governance_authority",
+            vec![Seed::Const(GOVERNANCE_AUTHORITY_SEED), Seed::Pubkey("mint")],
+        ),
+        (
+            "governance_vault",
+            vec![Seed::Const(GOVERNANCE_VAULT_SEED), Seed::Pubkey("mint")],
+        ),
+        (
+            "treasury_vault",
+            vec![Seed::Const(TREASURY_VAULT_SEED), Seed::Pubkey("mint")],
+        ),
+        (
+            "extra_account_metas",
+            vec![Seed::Const(EXTRA_ACCOUNT_METAS_SEED), Seed::Pubkey("mint")],
+        ),
+    ];
+
+    // Return data of the view instructions
+    let returns = [
+        ("GetConfig", ConfigView::schema_container()),
+        ("GetRemainingMintable", u64::schema_container()),
+        ("GetNextMintTimeV2", i64::schema_container()),
+        (
+            "GetPendingAuthorityTransfer",
+            Option::<PendingAuthorityTransfer>::schema_container(),
+        ),
+        ("GetProgramVersion", ProgramVersion::schema_container()),
+    ];
+
+    // Every definition used by instruction arguments, return data and accounts
+    let args_schemas: BTreeMap<u8, BorshSchemaContainer> = InstructionDiscriminator::ALL
+        .into_iter()
+        .filter_map(|discriminator| {
+            let schema = discriminator.args_schema()?;
+            Some((discriminator as u8, schema))
+        })
+        .collect();
+    let mut definitions = HashMap::new();
+    for schema in args_schemas.values() {
+        definitions.extend(schema.definitions.clone());
+    }
+    for (_, schema) in returns.iter() {
+        definitions.extend(schema.definitions.clone());
+    }
+    for (schema, _, _) in state_accounts.iter() {
+        definitions.extend(schema.definitions.clone());
+    }
+
+    let instructions: Vec<Value> = InstructionDiscriminator::ALL
+        .into_iter()
+        .map(|discriminator| {
+            let name = format!("{:?}", discriminator);
+            let accounts: Vec<Value> = discriminator
+                .accounts()
+                .iter()
+                .enumerate()
+                .map(|(index, account)| {
+                    let mut value = json!({
+                        "index": index,
+                        "docs": account.docs,
+                    });
+                    if account.variadic {
+                        value["variadic"] = json!(true);
+                    }
+                    // The flags of a group are given per account in its docs
+                    if !account.group {
+                        value["signer"] = json!(account.signer);
+                        value["writable"] = json!(account.writable);
+                    }
+                    value
+                })
+                .collect();
+            let mut instruction = json!({
+                "name": name,
+                "discriminator": discriminator as u8,
+                "args": args_schemas
+                    .get(&(discriminator as u8))
+                    .map_or(json!([]), |schema| struct_fields(&definitions, &schema.declaration)),
+                "accounts": accounts,
+            });
+            if let Some((_, schema)) = returns.iter().find(|(view, _)| *view == name) {
+                instruction["returns"] = json!(schema.declaration);
+            }
+            instruction
+        })
+        .collect();
+
+    let accounts: Vec<Value> = state_accounts
+        .iter()
+        .map(|(schema, (account_type, version), seeds)| {
+            json!({
+                "name": schema.declaration,
+                "account_type": account_type,
+                "version": version,
+                "seeds": seeds_json(seeds),
+                "fields": struct_fields(&definitions, &schema.declaration),
+            })
+        })
+        .collect();
+
+    // Named types other than the instruction enum, the accounts and enum variant payloads
+    let mut skip = variant_declarations(&definitions);
+    skip.extend(
+        args_schemas
+            .values()
+            .map(|schema| schema.declaration.clone()),
+    );
+    skip.insert("Pubkey".to_string());
+    skip.extend(
+        state_accounts
+            .iter()
+            .map(|(schema, _, _)| schema.declaration.clone()),
+    );
+    let types: BTreeMap<&Declaration, Value> = definitions
+        .iter()
+        .filter(|(name, _)| {
+            !skip.contains(*name) && name.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+        .filter_map(|(name, definition)| {
+            type_json(&definitions, name, definition).map(|value| (name, value))
+        })
+        .collect();
+
+    json!({
+        "name": "aria_token",
+        "version": env!("CARGO_PKG_VERSION"),
+        "encoding": "borsh",
+        "account_header_len": ACCOUNT_HEADER_LEN,
+        "instructions": instructions,
+        "accounts": accounts,
+        "pdas": pdas
+            .iter()
+            .map(|(name, seeds)| json!({ "name": name, "seeds": seeds_json(seeds) }))
+            .collect::<Vec<_>>(),
+        "types": types.into_values().collect::<Vec<_>>(),
+        "errors": AriaError::ALL
+            .iter()
+            .map(|error| json!({
+                "code": *error as u32,
+                "name": format!("{:?}", error),
+                "msg": error.to_string(),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// The IDL as written to `idl/aria_token.json`
+pub fn idl_json() -> String {
+    let mut idl = serde_json::to_string_pretty(&generate_idl()).unwrap();
+    idl.push('\n');
+    idl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::AccountSpec;
+    use std::path::Path;
+
+    #[test]
+    fn test_every_instruction_has_accounts() {
+        for discriminator in InstructionDiscriminator::ALL {
+            let accounts = discriminator.accounts();
+            assert!(
+                !accounts.is_empty()
+                    || discriminator == InstructionDiscriminator::GetProgramVersion,
+                "{:?} has no accounts",
+                discriminator
+            );
+            // Only the last entry can stand for any number of accounts
+            let fixed = &accounts[..accounts.len().saturating_sub(1)];
+            assert!(
+                fixed.iter().all(|account| !account.variadic),
+                "{:?}",
+                discriminator
+            );
+        }
+        assert_eq!(
+            InstructionDiscriminator::MintTokens.accounts()[0],
+            AccountSpec::signer("Account holding the minter role")
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        for (code, error) in AriaError::ALL.iter().enumerate() {
+            assert_eq!(*error as usize, code);
+        }
+        let idl = generate_idl();
+        let errors = idl["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), AriaError::ALL.len());
+        assert_eq!(errors[1]["name"], "InsufficientAuthority");
+        assert_eq!(
+            errors[1]["msg"],
+            AriaError::InsufficientAuthority.to_string()
+        );
+    }
+
+    #[test]
+    fn test_idl_is_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(IDL_PATH);
+        let idl = idl_json();
+        if std::env::var_os("ARIA_UPDATE_IDL").is_some() {
+            std::fs::write(&path, &idl).unwrap();
+        }
+        let checked_in = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            checked_in == idl,
+            "{} is out of date; regenerate it with `ARIA_UPDATE_IDL=1 cargo test -p aria-token-interface idl`",
+            path.display()
+        );
+    }
+}
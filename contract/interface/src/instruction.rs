@@ -0,0 +1,1359 @@
+// Program instructions
+
+use std::io::{self, Read, Write};
+
+use borsh::schema::BorshSchemaContainer;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::account::AccountType;
+use crate::error::AriaError;
+use crate::{fees, governance, metadata, roles, treasury};
+
+/// Program instructions. The data of an instruction is its `InstructionDiscriminator` byte
+/// followed by its borsh-encoded args type, if it takes arguments. The arguments of an existing
+/// instruction never change: a new layout is added as a new instruction (`MintTokensV2`) with
+/// its own discriminator and args type (`MintTokensV2Args`), and the old one stays decodable.
+/// The accounts each instruction expects are listed, in order, by
+/// `InstructionDiscriminator::accounts`.
+#[derive(Debug)]
+pub enum ARIAInstruction {
+    /// Initialize a new ARIA token and the program config. Token-2022 mints get the
+    /// ARIA name, symbol and `metadata_uri` in the metadata extension and can enable
+    /// the transfer-fee and transfer-hook extensions; the mint account must be sized
+    /// for the metadata pointer and the extensions used. Legacy SPL Token mints get a
+    /// Metaplex metadata account instead. The authority funds the metadata rent. The
+    /// authority becomes the admin and is granted every role. Only the program's upgrade
+    /// authority can initialize it, or the bootstrap key the program was built with under the
+    /// `bootstrap-authority` feature.
+    InitializeMint(InitializeMintArgs),
+
+    /// Mint tokens to an account
+    MintTokens(MintTokensArgs),
+
+    /// Retired: replaced by ProposeAuthorityTransferV2, fails with `InstructionDeprecated`.
+    /// Proposals were never stored, and the SPL mint authority now stays with the mint
+    /// authority PDA.
+    ProposeAuthorityTransfer(ProposeAuthorityTransferArgs),
+
+    /// Retired: replaced by AcceptAuthorityTransferV2, fails with `InstructionDeprecated`.
+    /// Handing the SPL mint authority to a wallet would take minting away from the program.
+    AcceptAuthorityTransfer,
+
+    /// Retired: replaced by CancelAuthorityTransferV2, fails with `InstructionDeprecated`
+    CancelAuthorityTransfer,
+
+    /// Create or update the referral share for a mint
+    SetReferralShare(SetReferralShareArgs),
+
+    /// Register the signer's referrer. Can only be done once per user.
+    RegisterReferrer(RegisterReferrerArgs),
+
+    /// Pay tokens to a recipient, routing the referral share to the payer's referrer
+    PayWithReferral(PayWithReferralArgs),
+
+    /// Create or update the treasury approver set and threshold
+    ConfigureTreasury(ConfigureTreasuryArgs),
+
+    /// Propose a spend from the treasury vault. The proposer's approval is implied.
+    ProposeSpend(ProposeSpendArgs),
+
+    /// Approve a pending spend
+    ApproveSpend(ApproveSpendArgs),
+
+    /// Execute a spend once the approval threshold is met
+    ExecuteSpend(ExecuteSpendArgs),
+
+    /// Create or update governance parameters
+    ConfigureGovernance(ConfigureGovernanceArgs),
+
+    /// Deposit ARI into the governance vault to gain voting power
+    DepositGoverningTokens(DepositGoverningTokensArgs),
+
+    /// Withdraw deposited ARI
+    WithdrawGoverningTokens(WithdrawGoverningTokensArgs),
+
+    /// Create a proposal carrying instructions to execute if it passes
+    CreateProposal(CreateProposalArgs),
+
+    /// Vote on a proposal with the voter's deposit at the proposal's snapshot slot and any power
+    /// delegated to them
+    CastVote(CastVoteArgs),
+
+    /// Execute a passed proposal after its voting window, signed by the governance authority PDA
+    ExecuteProposal,
+
+    /// Delegate the signer's voting power, replacing any previous delegate
+    SetDelegate(SetDelegateArgs),
+
+    /// Revoke the signer's delegation and close the delegate record
+    RevokeDelegate,
+
+    /// Move withheld transfer fees from token accounts into the mint
+    HarvestWithheldFees,
+
+    /// Withdraw the fees withheld in the mint to the treasury
+    WithdrawWithheldFees,
+
+    /// Update the transfer fee; Token-2022 applies it two epochs later
+    UpdateTransferFee(UpdateTransferFeeArgs),
+
+    /// Update a field of the token metadata: the Token-2022 metadata extension, where the
+    /// authority pays for any growth, or the Metaplex metadata of a legacy SPL Token mint,
+    /// which has no additional `Key` fields
+    UpdateMetadata(UpdateMetadataArgs),
+
+    /// Create the blocklist and the extra-account-metas PDA of a mint that uses this
+    /// program as its transfer hook. An extra-account-metas PDA created by the interface's
+    /// InitializeExtraAccountMetaList is left as is.
+    InitializeTransferHook(InitializeTransferHookArgs),
+
+    /// Hand the blocklist to a new compliance authority
+    SetComplianceAuthority(SetComplianceAuthorityArgs),
+
+    /// Reject transfers from and to token accounts owned by `address`
+    BlockAddress(BlockAddressArgs),
+
+    /// Remove `address` from the blocklist
+    UnblockAddress(UnblockAddressArgs),
+
+    /// Stop every state-changing instruction except unpausing, role changes and
+    /// authority recovery
+    Pause,
+
+    /// Resume normal operation
+    Unpause,
+
+    /// Grant `role` to `member`; allowed while paused
+    GrantRole(GrantRoleArgs),
+
+    /// Revoke `role` from `member`; allowed while paused
+    RevokeRole(RevokeRoleArgs),
+
+    /// Set, increase or decrease the amount `minter` may still mint
+    UpdateMinterAllowance(UpdateMinterAllowanceArgs),
+
+    /// Upgrade a program account with an older layout to the current one, reallocating it for
+    /// the account type and version header. Anyone may migrate an account; the payer funds the
+    /// extra rent.
+    MigrateAccount(MigrateAccountArgs),
+
+    /// Return the program config as a `view::ConfigView`
+    GetConfig,
+
+    /// Return, as a `u64`, how much can still be minted under the supply cap, capped by the
+    /// remaining allowance of `minter` when one is given. Ignores the cooldown and the pause.
+    GetRemainingMintable(GetRemainingMintableArgs),
+
+    /// Retired: replaced by GetNextMintTimeV2, fails with `InstructionDeprecated`. The cooldown
+    /// is kept per minter and the config alone cannot tell when a minter may mint again.
+    GetNextMintTime,
+
+    /// Return the authority transfer that AcceptAuthorityTransferV2 would accept now, as an
+    /// `Option<view::PendingAuthorityTransfer>`
+    GetPendingAuthorityTransfer,
+
+    /// Return the version of the program as a `view::ProgramVersion`. Takes no accounts.
+    GetProgramVersion,
+
+    /// Propose a new program authority, replacing any earlier proposal
+    ProposeAuthorityTransferV2(ProposeAuthorityTransferV2Args),
+
+    /// Accept a proposal within `AUTHORITY_TRANSFER_EXPIRY` seconds and become the program
+    /// authority. The SPL mint authority stays with the mint authority PDA.
+    AcceptAuthorityTransferV2,
+
+    /// Cancel the pending authority transfer
+    CancelAuthorityTransferV2,
+
+    /// Return, as an `i64`, the unix timestamp from which the cooldown allows `minter` to call
+    /// MintTokens again; 0 before its first mint or when it has no allowance account
+    GetNextMintTimeV2(GetNextMintTimeV2Args),
+}
+
+impl ARIAInstruction {
+    /// Decode instruction data: the discriminator selects the instruction, whose arguments
+    /// must be exactly the rest of the data. Discriminators this program version does not know
+    /// are rejected with `UnknownInstruction`.
+    pub fn unpack(data: &[u8]) -> Result<Self, AriaError> {
+        let (&discriminator, mut args) = data
+            .split_first()
+            .ok_or(AriaError::InvalidInstructionData)?;
+        let discriminator = InstructionDiscriminator::try_from(discriminator)?;
+        let instruction = Self::deserialize_args(discriminator, &mut args)
+            .map_err(|_| AriaError::InvalidInstructionData)?;
+        if !args.is_empty() {
+            return Err(AriaError::InvalidInstructionData);
+        }
+        Ok(instruction)
+    }
+
+    /// Decode the arguments of the instruction with `discriminator`
+    fn deserialize_args<R: Read>(
+        discriminator: InstructionDiscriminator,
+        reader: &mut R,
+    ) -> io::Result<Self> {
+        use InstructionDiscriminator as D;
+        Ok(match discriminator {
+            D::InitializeMint => {
+                Self::InitializeMint(InitializeMintArgs::deserialize_reader(reader)?)
+            }
+            D::MintTokens => Self::MintTokens(MintTokensArgs::deserialize_reader(reader)?),
+            D::ProposeAuthorityTransfer => Self::ProposeAuthorityTransfer(
+                ProposeAuthorityTransferArgs::deserialize_reader(reader)?,
+            ),
+            D::AcceptAuthorityTransfer => Self::AcceptAuthorityTransfer,
+            D::CancelAuthorityTransfer => Self::CancelAuthorityTransfer,
+            D::SetReferralShare => {
+                Self::SetReferralShare(SetReferralShareArgs::deserialize_reader(reader)?)
+            }
+            D::RegisterReferrer => {
+                Self::RegisterReferrer(RegisterReferrerArgs::deserialize_reader(reader)?)
+            }
+            D::PayWithReferral => {
+                Self::PayWithReferral(PayWithReferralArgs::deserialize_reader(reader)?)
+            }
+            D::ConfigureTreasury => {
+                Self::ConfigureTreasury(ConfigureTreasuryArgs::deserialize_reader(reader)?)
+            }
+            D::ProposeSpend => Self::ProposeSpend(ProposeSpendArgs::deserialize_reader(reader)?),
+            D::ApproveSpend => Self::ApproveSpend(ApproveSpendArgs::deserialize_reader(reader)?),
+            D::ExecuteSpend => Self::ExecuteSpend(ExecuteSpendArgs::deserialize_reader(reader)?),
+            D::ConfigureGovernance => {
+                Self::ConfigureGovernance(ConfigureGovernanceArgs::deserialize_reader(reader)?)
+            }
+            D::DepositGoverningTokens => Self::DepositGoverningTokens(
+                DepositGoverningTokensArgs::deserialize_reader(reader)?,
+            ),
+            D::WithdrawGoverningTokens => Self::WithdrawGoverningTokens(
+                WithdrawGoverningTokensArgs::deserialize_reader(reader)?,
+            ),
+            D::CreateProposal => {
+                Self::CreateProposal(CreateProposalArgs::deserialize_reader(reader)?)
+            }
+            D::CastVote => Self::CastVote(CastVoteArgs::deserialize_reader(reader)?),
+            D::ExecuteProposal => Self::ExecuteProposal,
+            D::SetDelegate => Self::SetDelegate(SetDelegateArgs::deserialize_reader(reader)?),
+            D::RevokeDelegate => Self::RevokeDelegate,
+            D::HarvestWithheldFees => Self::HarvestWithheldFees,
+            D::WithdrawWithheldFees => Self::WithdrawWithheldFees,
+            D::UpdateTransferFee => {
+                Self::UpdateTransferFee(UpdateTransferFeeArgs::deserialize_reader(reader)?)
+            }
+            D::UpdateMetadata => {
+                Self::UpdateMetadata(UpdateMetadataArgs::deserialize_reader(reader)?)
+            }
+            D::InitializeTransferHook => Self::InitializeTransferHook(
+                InitializeTransferHookArgs::deserialize_reader(reader)?,
+            ),
+            D::SetComplianceAuthority => Self::SetComplianceAuthority(
+                SetComplianceAuthorityArgs::deserialize_reader(reader)?,
+            ),
+            D::BlockAddress => Self::BlockAddress(BlockAddressArgs::deserialize_reader(reader)?),
+            D::UnblockAddress => {
+                Self::UnblockAddress(UnblockAddressArgs::deserialize_reader(reader)?)
+            }
+            D::Pause => Self::Pause,
+            D::Unpause => Self::Unpause,
+            D::GrantRole => Self::GrantRole(GrantRoleArgs::deserialize_reader(reader)?),
+            D::RevokeRole => Self::RevokeRole(RevokeRoleArgs::deserialize_reader(reader)?),
+            D::UpdateMinterAllowance => {
+                Self::UpdateMinterAllowance(UpdateMinterAllowanceArgs::deserialize_reader(reader)?)
+            }
+            D::MigrateAccount => {
+                Self::MigrateAccount(MigrateAccountArgs::deserialize_reader(reader)?)
+            }
+            D::GetConfig => Self::GetConfig,
+            D::GetRemainingMintable => {
+                Self::GetRemainingMintable(GetRemainingMintableArgs::deserialize_reader(reader)?)
+            }
+            D::GetNextMintTime => Self::GetNextMintTime,
+            D::GetPendingAuthorityTransfer => Self::GetPendingAuthorityTransfer,
+            D::GetProgramVersion => Self::GetProgramVersion,
+            D::ProposeAuthorityTransferV2 => Self::ProposeAuthorityTransferV2(
+                ProposeAuthorityTransferV2Args::deserialize_reader(reader)?,
+            ),
+            D::AcceptAuthorityTransferV2 => Self::AcceptAuthorityTransferV2,
+            D::CancelAuthorityTransferV2 => Self::CancelAuthorityTransferV2,
+            D::GetNextMintTimeV2 => {
+                Self::GetNextMintTimeV2(GetNextMintTimeV2Args::deserialize_reader(reader)?)
+            }
+        })
+    }
+
+    /// Encode the instruction data: the discriminator followed by the arguments
+    pub fn pack(&self) -> Vec<u8> {
+        // Serializing into a Vec cannot fail
+        self.try_to_vec().unwrap()
+    }
+
+    pub fn discriminator(&self) -> InstructionDiscriminator {
+        match self {
+            Self::InitializeMint(..) => InstructionDiscriminator::InitializeMint,
+            Self::MintTokens(..) => InstructionDiscriminator::MintTokens,
+            Self::ProposeAuthorityTransfer(..) => {
+                InstructionDiscriminator::ProposeAuthorityTransfer
+            }
+            Self::AcceptAuthorityTransfer => InstructionDiscriminator::AcceptAuthorityTransfer,
+            Self::CancelAuthorityTransfer => InstructionDiscriminator::CancelAuthorityTransfer,
+            Self::SetReferralShare(..) => InstructionDiscriminator::SetReferralShare,
+            Self::RegisterReferrer(..) => InstructionDiscriminator::RegisterReferrer,
+            Self::PayWithReferral(..) => InstructionDiscriminator::PayWithReferral,
+            Self::ConfigureTreasury(..) => InstructionDiscriminator::ConfigureTreasury,
+            Self::ProposeSpend(..) => InstructionDiscriminator::ProposeSpend,
+            Self::ApproveSpend(..) => InstructionDiscriminator::ApproveSpend,
+            Self::ExecuteSpend(..) => InstructionDiscriminator::ExecuteSpend,
+            Self::ConfigureGovernance(..) => InstructionDiscriminator::ConfigureGovernance,
+            Self::DepositGoverningTokens(..) => InstructionDiscriminator::DepositGoverningTokens,
+            Self::WithdrawGoverningTokens(..) => InstructionDiscriminator::WithdrawGoverningTokens,
+            Self::CreateProposal(..) => InstructionDiscriminator::CreateProposal,
+            Self::CastVote(..) => InstructionDiscriminator::CastVote,
+            Self::ExecuteProposal => InstructionDiscriminator::ExecuteProposal,
+            Self::SetDelegate(..) => InstructionDiscriminator::SetDelegate,
+            Self::RevokeDelegate => InstructionDiscriminator::RevokeDelegate,
+            Self::HarvestWithheldFees => InstructionDiscriminator::HarvestWithheldFees,
+            Self::WithdrawWithheldFees => InstructionDiscriminator::WithdrawWithheldFees,
+            Self::UpdateTransferFee(..) => InstructionDiscriminator::UpdateTransferFee,
+            Self::UpdateMetadata(..) => InstructionDiscriminator::UpdateMetadata,
+            Self::InitializeTransferHook(..) => InstructionDiscriminator::InitializeTransferHook,
+            Self::SetComplianceAuthority(..) => InstructionDiscriminator::SetComplianceAuthority,
+            Self::BlockAddress(..) => InstructionDiscriminator::BlockAddress,
+            Self::UnblockAddress(..) => InstructionDiscriminator::UnblockAddress,
+            Self::Pause => InstructionDiscriminator::Pause,
+            Self::Unpause => InstructionDiscriminator::Unpause,
+            Self::GrantRole(..) => InstructionDiscriminator::GrantRole,
+            Self::RevokeRole(..) => InstructionDiscriminator::RevokeRole,
+            Self::UpdateMinterAllowance(..) => InstructionDiscriminator::UpdateMinterAllowance,
+            Self::MigrateAccount(..) => InstructionDiscriminator::MigrateAccount,
+            Self::GetConfig => InstructionDiscriminator::GetConfig,
+            Self::GetRemainingMintable(..) => InstructionDiscriminator::GetRemainingMintable,
+            Self::GetNextMintTime => InstructionDiscriminator::GetNextMintTime,
+            Self::GetPendingAuthorityTransfer => {
+                InstructionDiscriminator::GetPendingAuthorityTransfer
+            }
+            Self::GetProgramVersion => InstructionDiscriminator::GetProgramVersion,
+            Self::ProposeAuthorityTransferV2(..) => {
+                InstructionDiscriminator::ProposeAuthorityTransferV2
+            }
+            Self::AcceptAuthorityTransferV2 => InstructionDiscriminator::AcceptAuthorityTransferV2,
+            Self::CancelAuthorityTransferV2 => InstructionDiscriminator::CancelAuthorityTransferV2,
+            Self::GetNextMintTimeV2(..) => InstructionDiscriminator::GetNextMintTimeV2,
+        }
+    }
+}
+
+impl BorshSerialize for ARIAInstruction {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (self.discriminator() as u8).serialize(writer)?;
+        match self {
+            Self::InitializeMint(args) => args.serialize(writer),
+            Self::MintTokens(args) => args.serialize(writer),
+            Self::ProposeAuthorityTransfer(args) => args.serialize(writer),
+            Self::SetReferralShare(args) => args.serialize(writer),
+            Self::RegisterReferrer(args) => args.serialize(writer),
+            Self::PayWithReferral(args) => args.serialize(writer),
+            Self::ConfigureTreasury(args) => args.serialize(writer),
+            Self::ProposeSpend(args) => args.serialize(writer),
+            Self::ApproveSpend(args) => args.serialize(writer),
+            Self::ExecuteSpend(args) => args.serialize(writer),
+            Self::ConfigureGovernance(args) => args.serialize(writer),
+            Self::DepositGoverningTokens(args) => args.serialize(writer),
+            Self::WithdrawGoverningTokens(args) => args.serialize(writer),
+            Self::CreateProposal(args) => args.serialize(writer),
+            Self::CastVote(args) => args.serialize(writer),
+            Self::SetDelegate(args) => args.serialize(writer),
+            Self::UpdateTransferFee(args) => args.serialize(writer),
+            Self::UpdateMetadata(args) => args.serialize(writer),
+            Self::InitializeTransferHook(args) => args.serialize(writer),
+            Self::SetComplianceAuthority(args) => args.serialize(writer),
+            Self::BlockAddress(args) => args.serialize(writer),
+            Self::UnblockAddress(args) => args.serialize(writer),
+            Self::GrantRole(args) => args.serialize(writer),
+            Self::RevokeRole(args) => args.serialize(writer),
+            Self::UpdateMinterAllowance(args) => args.serialize(writer),
+            Self::MigrateAccount(args) => args.serialize(writer),
+            Self::GetRemainingMintable(args) => args.serialize(writer),
+            Self::ProposeAuthorityTransferV2(args) => args.serialize(writer),
+            Self::GetNextMintTimeV2(args) => args.serialize(writer),
+            Self::AcceptAuthorityTransfer
+            | Self::CancelAuthorityTransfer
+            | Self::ExecuteProposal
+            | Self::RevokeDelegate
+            | Self::HarvestWithheldFees
+            | Self::WithdrawWithheldFees
+            | Self::Pause
+            | Self::Unpause
+            | Self::GetConfig
+            | Self::GetNextMintTime
+            | Self::GetPendingAuthorityTransfer
+            | Self::GetProgramVersion
+            | Self::AcceptAuthorityTransferV2
+            | Self::CancelAuthorityTransferV2 => Ok(()),
+        }
+    }
+}
+
+impl BorshDeserialize for ARIAInstruction {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let discriminator = InstructionDiscriminator::try_from(u8::deserialize_reader(reader)?)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        Self::deserialize_args(discriminator, reader)
+    }
+}
+
+/// Arguments of an instruction, borsh-encoded after its discriminator. An args type is frozen
+/// with its instruction: a new argument layout is a new instruction with its own discriminator
+/// and a new args type carrying its version (`ProposeAuthorityTransferV2Args`), while the old
+/// instruction and args type stay decodable.
+pub trait InstructionArgs: BorshSerialize + BorshDeserialize {
+    const DISCRIMINATOR: InstructionDiscriminator;
+    /// Version of the instruction's argument layout, 1 for an instruction without a V suffix
+    const VERSION: u8;
+}
+
+// Ties each args type to its instruction and version
+macro_rules! instruction_args {
+    ($($args:ident => $instruction:ident, $version:literal;)*) => {
+        $(
+            impl InstructionArgs for $args {
+                const DISCRIMINATOR: InstructionDiscriminator =
+                    InstructionDiscriminator::$instruction;
+                const VERSION: u8 = $version;
+            }
+        )*
+
+        impl InstructionDiscriminator {
+            /// Borsh schema of the instruction's arguments, `None` when it takes none
+            pub fn args_schema(self) -> Option<BorshSchemaContainer> {
+                match self {
+                    $(Self::$instruction => Some($args::schema_container()),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+/// Arguments of `InitializeMint`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct InitializeMintArgs {
+    pub transfer_fee: Option<fees::TransferFeeParams>,
+    pub transfer_hook_program: Option<Pubkey>,
+    pub metadata_uri: String,
+}
+
+/// Arguments of `MintTokens`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct MintTokensArgs {
+    pub amount: u64,
+}
+
+/// Arguments of `ProposeAuthorityTransfer`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ProposeAuthorityTransferArgs {
+    pub new_authority: Pubkey,
+}
+
+/// Arguments of `SetReferralShare`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct SetReferralShareArgs {
+    pub share_bps: u16,
+}
+
+/// Arguments of `RegisterReferrer`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct RegisterReferrerArgs {
+    pub referrer: Pubkey,
+}
+
+/// Arguments of `PayWithReferral`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct PayWithReferralArgs {
+    pub amount: u64,
+}
+
+/// Arguments of `ConfigureTreasury`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ConfigureTreasuryArgs {
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+/// Arguments of `ProposeSpend`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ProposeSpendArgs {
+    pub asset: treasury::TreasuryAsset,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// Arguments of `ApproveSpend`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ApproveSpendArgs {
+    pub index: u64,
+}
+
+/// Arguments of `ExecuteSpend`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ExecuteSpendArgs {
+    pub index: u64,
+}
+
+/// Arguments of `ConfigureGovernance`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ConfigureGovernanceArgs {
+    pub voting_period: i64,
+    pub quorum_bps: u16,
+    pub approval_threshold_bps: u16,
+    pub min_proposal_power: u64,
+}
+
+/// Arguments of `DepositGoverningTokens`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct DepositGoverningTokensArgs {
+    pub amount: u64,
+}
+
+/// Arguments of `WithdrawGoverningTokens`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct WithdrawGoverningTokensArgs {
+    pub amount: u64,
+}
+
+/// Arguments of `CreateProposal`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct CreateProposalArgs {
+    pub description: String,
+    pub instructions: Vec<governance::ProposalInstruction>,
+}
+
+/// Arguments of `CastVote`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct CastVoteArgs {
+    pub vote: governance::Vote,
+}
+
+/// Arguments of `SetDelegate`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct SetDelegateArgs {
+    pub delegate: Pubkey,
+}
+
+/// Arguments of `UpdateTransferFee`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct UpdateTransferFeeArgs {
+    pub transfer_fee: fees::TransferFeeParams,
+}
+
+/// Arguments of `UpdateMetadata`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct UpdateMetadataArgs {
+    pub field: metadata::MetadataField,
+    pub value: String,
+}
+
+/// Arguments of `InitializeTransferHook`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct InitializeTransferHookArgs {
+    pub compliance_authority: Pubkey,
+}
+
+/// Arguments of `SetComplianceAuthority`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct SetComplianceAuthorityArgs {
+    pub compliance_authority: Pubkey,
+}
+
+/// Arguments of `BlockAddress`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct BlockAddressArgs {
+    pub address: Pubkey,
+}
+
+/// Arguments of `UnblockAddress`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct UnblockAddressArgs {
+    pub address: Pubkey,
+}
+
+/// Arguments of `GrantRole`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct GrantRoleArgs {
+    pub role: roles::Role,
+    pub member: Pubkey,
+}
+
+/// Arguments of `RevokeRole`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct RevokeRoleArgs {
+    pub role: roles::Role,
+    pub member: Pubkey,
+}
+
+/// Arguments of `UpdateMinterAllowance`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct UpdateMinterAllowanceArgs {
+    pub minter: Pubkey,
+    pub change: roles::AllowanceChange,
+}
+
+/// Arguments of `MigrateAccount`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct MigrateAccountArgs {
+    pub account_type: AccountType,
+}
+
+/// Arguments of `GetRemainingMintable`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct GetRemainingMintableArgs {
+    pub minter: Option<Pubkey>,
+}
+
+/// Arguments of `ProposeAuthorityTransferV2`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct ProposeAuthorityTransferV2Args {
+    pub new_authority: Pubkey,
+}
+
+/// Arguments of `GetNextMintTimeV2`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+pub struct GetNextMintTimeV2Args {
+    pub minter: Pubkey,
+}
+
+instruction_args! {
+    InitializeMintArgs => InitializeMint, 1;
+    MintTokensArgs => MintTokens, 1;
+    ProposeAuthorityTransferArgs => ProposeAuthorityTransfer, 1;
+    SetReferralShareArgs => SetReferralShare, 1;
+    RegisterReferrerArgs => RegisterReferrer, 1;
+    PayWithReferralArgs => PayWithReferral, 1;
+    ConfigureTreasuryArgs => ConfigureTreasury, 1;
+    ProposeSpendArgs => ProposeSpend, 1;
+    ApproveSpendArgs => ApproveSpend, 1;
+    ExecuteSpendArgs => ExecuteSpend, 1;
+    ConfigureGovernanceArgs => ConfigureGovernance, 1;
+    DepositGoverningTokensArgs => DepositGoverningTokens, 1;
+    WithdrawGoverningTokensArgs => WithdrawGoverningTokens, 1;
+    CreateProposalArgs => CreateProposal, 1;
+    CastVoteArgs => CastVote, 1;
+    SetDelegateArgs => SetDelegate, 1;
+    UpdateTransferFeeArgs => UpdateTransferFee, 1;
+    UpdateMetadataArgs => UpdateMetadata, 1;
+    InitializeTransferHookArgs => InitializeTransferHook, 1;
+    SetComplianceAuthorityArgs => SetComplianceAuthority, 1;
+    BlockAddressArgs => BlockAddress, 1;
+    UnblockAddressArgs => UnblockAddress, 1;
+    GrantRoleArgs => GrantRole, 1;
+    RevokeRoleArgs => RevokeRole, 1;
+    UpdateMinterAllowanceArgs => UpdateMinterAllowance, 1;
+    MigrateAccountArgs => MigrateAccount, 1;
+    GetRemainingMintableArgs => GetRemainingMintable, 1;
+    ProposeAuthorityTransferV2Args => ProposeAuthorityTransferV2, 2;
+    GetNextMintTimeV2Args => GetNextMintTimeV2, 2;
+}
+
+/// First byte of the instruction data. Values are frozen once released and never reused.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstructionDiscriminator {
+    InitializeMint = 0,
+    MintTokens = 1,
+    ProposeAuthorityTransfer = 2,
+    AcceptAuthorityTransfer = 3,
+    CancelAuthorityTransfer = 4,
+    SetReferralShare = 5,
+    RegisterReferrer = 6,
+    PayWithReferral = 7,
+    ConfigureTreasury = 8,
+    ProposeSpend = 9,
+    ApproveSpend = 10,
+    ExecuteSpend = 11,
+    ConfigureGovernance = 12,
+    DepositGoverningTokens = 13,
+    WithdrawGoverningTokens = 14,
+    CreateProposal = 15,
+    CastVote = 16,
+    ExecuteProposal = 17,
+    SetDelegate = 18,
+    RevokeDelegate = 19,
+    HarvestWithheldFees = 20,
+    WithdrawWithheldFees = 21,
+    UpdateTransferFee = 22,
+    UpdateMetadata = 23,
+    InitializeTransferHook = 24,
+    SetComplianceAuthority = 25,
+    BlockAddress = 26,
+    UnblockAddress = 27,
+    Pause = 28,
+    Unpause = 29,
+    GrantRole = 30,
+    RevokeRole = 31,
+    UpdateMinterAllowance = 32,
+    MigrateAccount = 33,
+    GetConfig = 34,
+    GetRemainingMintable = 35,
+    GetNextMintTime = 36,
+    GetPendingAuthorityTransfer = 37,
+    GetProgramVersion = 38,
+    ProposeAuthorityTransferV2 = 39,
+    AcceptAuthorityTransferV2 = 40,
+    CancelAuthorityTransferV2 = 41,
+    GetNextMintTimeV2 = 42,
+}
+
+impl InstructionDiscriminator {
+    pub const ALL: [Self; 43] = [
+        Self::InitializeMint,
+        Self::MintTokens,
+        Self::ProposeAuthorityTransfer,
+        Self::AcceptAuthorityTransfer,
+        Self::CancelAuthorityTransfer,
+        Self::SetReferralShare,
+        Self::RegisterReferrer,
+        Self::PayWithReferral,
+        Self::ConfigureTreasury,
+        Self::ProposeSpend,
+        Self::ApproveSpend,
+        Self::ExecuteSpend,
+        Self::ConfigureGovernance,
+        Self::DepositGoverningTokens,
+        Self::WithdrawGoverningTokens,
+        Self::CreateProposal,
+        Self::CastVote,
+        Self::ExecuteProposal,
+        Self::SetDelegate,
+        Self::RevokeDelegate,
+        Self::HarvestWithheldFees,
+        Self::WithdrawWithheldFees,
+        Self::UpdateTransferFee,
+        Self::UpdateMetadata,
+        Self::InitializeTransferHook,
+        Self::SetComplianceAuthority,
+        Self::BlockAddress,
+        Self::UnblockAddress,
+        Self::Pause,
+        Self::Unpause,
+        Self::GrantRole,
+        Self::RevokeRole,
+        Self::UpdateMinterAllowance,
+        Self::MigrateAccount,
+        Self::GetConfig,
+        Self::GetRemainingMintable,
+        Self::GetNextMintTime,
+        Self::GetPendingAuthorityTransfer,
+        Self::GetProgramVersion,
+        Self::ProposeAuthorityTransferV2,
+        Self::AcceptAuthorityTransferV2,
+        Self::CancelAuthorityTransferV2,
+        Self::GetNextMintTimeV2,
+    ];
+}
+
+/// Account an instruction expects at one position of its account list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountSpec {
+    pub docs: &'static str,
+    pub signer: bool,
+    pub writable: bool,
+    /// Any number of accounts from this position on
+    pub variadic: bool,
+    /// Repeating group of accounts whose flags are given in `docs`
+    pub group: bool,
+}
+
+impl AccountSpec {
+    const fn new(docs: &'static str, signer: bool, writable: bool) -> Self {
+        AccountSpec {
+            docs,
+            signer,
+            writable,
+            variadic: false,
+            group: false,
+        }
+    }
+
+    pub const fn readonly(docs: &'static str) -> Self {
+        Self::new(docs, false, false)
+    }
+
+    pub const fn writable(docs: &'static str) -> Self {
+        Self::new(docs, false, true)
+    }
+
+    pub const fn signer(docs: &'static str) -> Self {
+        Self::new(docs, true, false)
+    }
+
+    pub const fn signer_writable(docs: &'static str) -> Self {
+        Self::new(docs, true, true)
+    }
+
+    pub const fn group(docs: &'static str) -> Self {
+        AccountSpec {
+            variadic: true,
+            group: true,
+            ..Self::new(docs, false, false)
+        }
+    }
+
+    pub const fn variadic(self) -> Self {
+        AccountSpec {
+            variadic: true,
+            ..self
+        }
+    }
+}
+
+// Account list with a 'static lifetime, which const fn constructors do not get by promotion
+macro_rules! accounts {
+    ($($account:expr),* $(,)?) => {{
+        const ACCOUNTS: &[AccountSpec] = &[$($account),*];
+        ACCOUNTS
+    }};
+}
+
+impl InstructionDiscriminator {
+    /// Accounts the instruction expects, in order. The IDL is generated from this table.
+    pub const fn accounts(self) -> &'static [AccountSpec] {
+        match self {
+            Self::InitializeMint => accounts![
+                AccountSpec::signer_writable(
+                    "Program admin (upgrade authority); pays for the config and role registry",
+                ),
+                AccountSpec::writable("The mint account to initialize"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The token program"),
+                AccountSpec::writable("The program config PDA"),
+                AccountSpec::readonly("The mint authority PDA"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::writable("The role registry PDA"),
+                AccountSpec::readonly(
+                    "The program's program-data account, owned by the upgradeable BPF loader",
+                ),
+                AccountSpec::writable(
+                    "The Metaplex metadata PDA of the mint (legacy SPL Token mints only)",
+                ),
+                AccountSpec::readonly(
+                    "The Metaplex token metadata program (legacy SPL Token mints only)",
+                ),
+            ],
+            Self::MintTokens => accounts![
+                AccountSpec::signer("Account holding the minter role"),
+                AccountSpec::writable("The mint account"),
+                AccountSpec::writable("The destination account"),
+                AccountSpec::readonly("The token program"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::writable("The program config PDA"),
+                AccountSpec::readonly("The mint authority PDA"),
+                AccountSpec::readonly("The role registry PDA"),
+                AccountSpec::writable("The minter's allowance PDA"),
+            ],
+            Self::ProposeAuthorityTransfer => accounts![
+                AccountSpec::signer("Current mint authority account"),
+                AccountSpec::writable("The mint account"),
+                AccountSpec::readonly("New mint authority account"),
+                AccountSpec::readonly("The clock sysvar"),
+            ],
+            Self::AcceptAuthorityTransfer => accounts![
+                AccountSpec::signer("New mint authority account"),
+                AccountSpec::writable("The mint account"),
+                AccountSpec::readonly("Current mint authority account"),
+                AccountSpec::readonly("The token program"),
+                AccountSpec::readonly("The clock sysvar"),
+            ],
+            Self::CancelAuthorityTransfer => accounts![
+                AccountSpec::signer("Current mint authority account"),
+                AccountSpec::writable("The mint account"),
+            ],
+            Self::SetReferralShare => accounts![
+                AccountSpec::signer_writable(
+                    "Account holding the rewarder role, pays for the config account",
+                ),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("The referral config PDA"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::readonly("The role registry PDA"),
+            ],
+            Self::RegisterReferrer => accounts![
+                AccountSpec::signer_writable("User account, pays for the new accounts"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::writable("The user's referral record PDA"),
+                AccountSpec::writable("The referrer's stats PDA"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::readonly("The program config PDA"),
+            ],
+            Self::PayWithReferral => accounts![
+                AccountSpec::signer("Payer account"),
+                AccountSpec::writable("The payer's token account"),
+                AccountSpec::writable("The recipient token account"),
+                AccountSpec::writable("The referrer's token account"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::readonly("The referral config PDA"),
+                AccountSpec::readonly("The payer's referral record PDA"),
+                AccountSpec::writable("The referrer's stats PDA"),
+                AccountSpec::readonly("The token program"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly("Transfer hook accounts, for mints with a transfer hook")
+                    .variadic(),
+            ],
+            Self::ConfigureTreasury => accounts![
+                AccountSpec::signer_writable("Program authority, pays for the treasury account"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("The treasury config PDA"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+            ],
+            Self::ProposeSpend => accounts![
+                AccountSpec::signer_writable("Approver account, pays for the spend record"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::writable("The treasury config PDA"),
+                AccountSpec::writable("The spend record PDA for the next spend index"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::readonly("The program config PDA"),
+            ],
+            Self::ApproveSpend => accounts![
+                AccountSpec::signer("Approver account"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::readonly("The treasury config PDA"),
+                AccountSpec::writable("The spend record PDA"),
+                AccountSpec::readonly("The program config PDA"),
+            ],
+            Self::ExecuteSpend => accounts![
+                AccountSpec::signer("Approver account"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::readonly("The treasury config PDA"),
+                AccountSpec::writable("The spend record PDA"),
+                AccountSpec::writable("The treasury vault PDA"),
+                AccountSpec::writable("The vault's token account (token spends only)"),
+                AccountSpec::writable(
+                    "The recipient: a wallet for SOL, a token account for tokens",
+                ),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The token program"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly("Transfer hook accounts, for mints with a transfer hook")
+                    .variadic(),
+            ],
+            Self::ConfigureGovernance => accounts![
+                AccountSpec::signer_writable(
+                    "Program authority account, pays for the governance account",
+                ),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("The governance config PDA"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+            ],
+            Self::DepositGoverningTokens => accounts![
+                AccountSpec::signer_writable(
+                    "Owner account, pays for the voter and checkpoint records",
+                ),
+                AccountSpec::writable("The owner's token account"),
+                AccountSpec::writable("The governance vault's token account"),
+                AccountSpec::writable("The owner's voter record PDA"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The token program"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::writable("The owner's checkpoint record PDA"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly("Transfer hook accounts, for mints with a transfer hook")
+                    .variadic(),
+            ],
+            Self::WithdrawGoverningTokens => accounts![
+                AccountSpec::signer("Owner account"),
+                AccountSpec::writable("The destination token account"),
+                AccountSpec::writable("The governance vault's token account"),
+                AccountSpec::writable("The owner's voter record PDA"),
+                AccountSpec::readonly("The governance vault PDA"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The token program"),
+                AccountSpec::writable("The owner's checkpoint record PDA"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly("Transfer hook accounts, for mints with a transfer hook")
+                    .variadic(),
+            ],
+            Self::CreateProposal => accounts![
+                AccountSpec::signer_writable("Proposer account, pays for the proposal"),
+                AccountSpec::writable("The governance config PDA"),
+                AccountSpec::readonly("The proposer's voter record PDA"),
+                AccountSpec::writable("The proposal PDA for the next proposal index"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::readonly("The program config PDA"),
+            ],
+            Self::CastVote => accounts![
+                AccountSpec::signer_writable("Voter account, pays for the vote records"),
+                AccountSpec::writable("The proposal PDA"),
+                AccountSpec::readonly(concat!(
+                    "The voter's checkpoint record PDA ",
+                    "(may be uninitialized for a delegate without a deposit)",
+                )),
+                AccountSpec::writable("The voter's vote record PDA"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::group(concat!(
+                    "For each delegator voted for, in groups of three: ",
+                    "`[]` the delegate record PDA, ",
+                    "`[]` the delegator's checkpoint record PDA, ",
+                    "`[writable]` the delegator's vote record PDA",
+                )),
+            ],
+            Self::ExecuteProposal => accounts![
+                AccountSpec::writable("The proposal PDA"),
+                AccountSpec::readonly("The governance authority PDA"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly(
+                    "Every account and program referenced by the proposal's instructions",
+                )
+                .variadic(),
+            ],
+            Self::SetDelegate => accounts![
+                AccountSpec::signer_writable("Delegator account, pays for the delegate record"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::writable("The delegator's delegate record PDA"),
+                AccountSpec::readonly("The clock sysvar"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::readonly("The program config PDA"),
+            ],
+            Self::RevokeDelegate => accounts![
+                AccountSpec::signer_writable("Delegator account, receives the record's lamports"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::writable("The delegator's delegate record PDA"),
+                AccountSpec::readonly("The program config PDA"),
+            ],
+            Self::HarvestWithheldFees => accounts![
+                AccountSpec::writable("The Token-2022 mint account"),
+                AccountSpec::readonly("The Token-2022 program"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("Token accounts to harvest from").variadic(),
+            ],
+            Self::WithdrawWithheldFees => accounts![
+                AccountSpec::writable("The Token-2022 mint account"),
+                AccountSpec::writable("The treasury vault's token account"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly("The mint authority PDA"),
+                AccountSpec::readonly("The Token-2022 program"),
+            ],
+            Self::UpdateTransferFee => accounts![
+                AccountSpec::signer("Program authority account"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("The Token-2022 mint account"),
+                AccountSpec::readonly("The mint authority PDA"),
+                AccountSpec::readonly("The Token-2022 program"),
+            ],
+            Self::UpdateMetadata => accounts![
+                AccountSpec::signer_writable("Program authority account"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("The mint account"),
+                AccountSpec::readonly("The mint authority PDA"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The token program"),
+                AccountSpec::readonly("The system program"),
+                AccountSpec::writable(
+                    "The Metaplex metadata PDA of the mint (legacy SPL Token mints only)",
+                ),
+                AccountSpec::readonly(
+                    "The Metaplex token metadata program (legacy SPL Token mints only)",
+                ),
+            ],
+            Self::InitializeTransferHook => accounts![
+                AccountSpec::signer_writable("The mint's freeze authority, pays for both accounts"),
+                AccountSpec::readonly("The Token-2022 mint account"),
+                AccountSpec::writable("The extra-account-metas PDA"),
+                AccountSpec::writable("The blocklist PDA"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+            ],
+            Self::SetComplianceAuthority => accounts![
+                AccountSpec::signer("Compliance authority account"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::writable("The blocklist PDA"),
+            ],
+            Self::BlockAddress => accounts![
+                AccountSpec::signer("Compliance authority account"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::writable("The blocklist PDA"),
+            ],
+            Self::UnblockAddress => accounts![
+                AccountSpec::signer("Compliance authority account"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::writable("The blocklist PDA"),
+            ],
+            Self::Pause => accounts![
+                AccountSpec::signer("Account holding the pauser role"),
+                AccountSpec::writable("The program config PDA"),
+                AccountSpec::readonly("The role registry PDA"),
+            ],
+            Self::Unpause => accounts![
+                AccountSpec::signer("Account holding the pauser role"),
+                AccountSpec::writable("The program config PDA"),
+                AccountSpec::readonly("The role registry PDA"),
+            ],
+            Self::GrantRole => accounts![
+                AccountSpec::signer("Program authority (admin) account"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("The role registry PDA"),
+            ],
+            Self::RevokeRole => accounts![
+                AccountSpec::signer("Program authority (admin) account"),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("The role registry PDA"),
+            ],
+            Self::UpdateMinterAllowance => accounts![
+                AccountSpec::signer_writable(
+                    "Program authority (admin) account, pays for the allowance account",
+                ),
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::writable("The minter's allowance PDA"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+            ],
+            Self::MigrateAccount => accounts![
+                AccountSpec::signer_writable("Payer of the extra rent"),
+                AccountSpec::writable("The program account to migrate"),
+                AccountSpec::readonly("The rent sysvar"),
+                AccountSpec::readonly("The system program"),
+            ],
+            Self::GetConfig => accounts![AccountSpec::readonly("The program config PDA"),],
+            Self::GetRemainingMintable => accounts![
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly("The mint account"),
+                AccountSpec::readonly(
+                    "The minter's allowance PDA, only read when `minter` is given",
+                ),
+            ],
+            Self::GetNextMintTime => accounts![AccountSpec::readonly("The program config PDA"),],
+            Self::GetPendingAuthorityTransfer => accounts![
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly("The clock sysvar"),
+            ],
+            Self::GetProgramVersion => accounts![],
+            Self::ProposeAuthorityTransferV2 => accounts![
+                AccountSpec::signer("Current program authority"),
+                AccountSpec::writable("The program config PDA"),
+                AccountSpec::readonly("New program authority account"),
+                AccountSpec::readonly("The clock sysvar"),
+            ],
+            Self::AcceptAuthorityTransferV2 => accounts![
+                AccountSpec::signer("Proposed program authority"),
+                AccountSpec::writable("The program config PDA"),
+                AccountSpec::readonly("The clock sysvar"),
+            ],
+            Self::CancelAuthorityTransferV2 => accounts![
+                AccountSpec::signer("Current program authority"),
+                AccountSpec::writable("The program config PDA"),
+            ],
+            Self::GetNextMintTimeV2 => accounts![
+                AccountSpec::readonly("The program config PDA"),
+                AccountSpec::readonly("The minter's allowance PDA"),
+            ],
+        }
+    }
+}
+
+impl TryFrom<u8> for InstructionDiscriminator {
+    type Error = AriaError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|discriminator| *discriminator as u8 == value)
+            .ok_or(AriaError::UnknownInstruction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_instruction() -> Vec<ARIAInstruction> {
+        let key = Pubkey::new_unique();
+        let transfer_fee = fees::TransferFeeParams {
+            transfer_fee_basis_points: 50,
+            maximum_fee: 1_000,
+        };
+        vec![
+            ARIAInstruction::InitializeMint(InitializeMintArgs {
+                transfer_fee: Some(transfer_fee),
+                transfer_hook_program: Some(key),
+                metadata_uri: "https://aria.example/ari.json".to_string(),
+            }),
+            ARIAInstruction::MintTokens(MintTokensArgs { amount: 1 }),
+            ARIAInstruction::ProposeAuthorityTransfer(ProposeAuthorityTransferArgs {
+                new_authority: key,
+            }),
+            ARIAInstruction::AcceptAuthorityTransfer,
+            ARIAInstruction::CancelAuthorityTransfer,
+            ARIAInstruction::SetReferralShare(SetReferralShareArgs { share_bps: 500 }),
+            ARIAInstruction::RegisterReferrer(RegisterReferrerArgs { referrer: key }),
+            ARIAInstruction::PayWithReferral(PayWithReferralArgs { amount: 1 }),
+            ARIAInstruction::ConfigureTreasury(ConfigureTreasuryArgs {
+                approvers: vec![key],
+                threshold: 1,
+            }),
+            ARIAInstruction::ProposeSpend(ProposeSpendArgs {
+                asset: treasury::TreasuryAsset::Sol,
+                recipient: key,
+                amount: 1,
+            }),
+            ARIAInstruction::ApproveSpend(ApproveSpendArgs { index: 0 }),
+            ARIAInstruction::ExecuteSpend(ExecuteSpendArgs { index: 0 }),
+            ARIAInstruction::ConfigureGovernance(ConfigureGovernanceArgs {
+                voting_period: 60,
+                quorum_bps: 1_000,
+                approval_threshold_bps: 5_000,
+                min_proposal_power: 1,
+            }),
+            ARIAInstruction::DepositGoverningTokens(DepositGoverningTokensArgs { amount: 1 }),
+            ARIAInstruction::WithdrawGoverningTokens(WithdrawGoverningTokensArgs { amount: 1 }),
+            ARIAInstruction::CreateProposal(CreateProposalArgs {
+                description: "Proposal".to_string(),
+                instructions: vec![],
+            }),
+            ARIAInstruction::CastVote(CastVoteArgs {
+                vote: governance::Vote::For,
+            }),
+            ARIAInstruction::ExecuteProposal,
+            ARIAInstruction::SetDelegate(SetDelegateArgs { delegate: key }),
+            ARIAInstruction::RevokeDelegate,
+            ARIAInstruction::HarvestWithheldFees,
+            ARIAInstruction::WithdrawWithheldFees,
+            ARIAInstruction::UpdateTransferFee(UpdateTransferFeeArgs { transfer_fee }),
+            ARIAInstruction::UpdateMetadata(UpdateMetadataArgs {
+                field: metadata::MetadataField::Uri,
+                value: "https://aria.example/v2.json".to_string(),
+            }),
+            ARIAInstruction::InitializeTransferHook(InitializeTransferHookArgs {
+                compliance_authority: key,
+            }),
+            ARIAInstruction::SetComplianceAuthority(SetComplianceAuthorityArgs {
+                compliance_authority: key,
+            }),
+            ARIAInstruction::BlockAddress(BlockAddressArgs { address: key }),
+            ARIAInstruction::UnblockAddress(UnblockAddressArgs { address: key }),
+            ARIAInstruction::Pause,
+            ARIAInstruction::Unpause,
+            ARIAInstruction::GrantRole(GrantRoleArgs {
+                role: roles::Role::Minter,
+                member: key,
+            }),
+            ARIAInstruction::RevokeRole(RevokeRoleArgs {
+                role: roles::Role::Minter,
+                member: key,
+            }),
+            ARIAInstruction::UpdateMinterAllowance(UpdateMinterAllowanceArgs {
+                minter: key,
+                change: roles::AllowanceChange::Set(1),
+            }),
+            ARIAInstruction::MigrateAccount(MigrateAccountArgs {
+                account_type: AccountType::ProgramConfig,
+            }),
+            ARIAInstruction::GetConfig,
+            ARIAInstruction::GetRemainingMintable(GetRemainingMintableArgs { minter: Some(key) }),
+            ARIAInstruction::GetNextMintTime,
+            ARIAInstruction::GetPendingAuthorityTransfer,
+            ARIAInstruction::GetProgramVersion,
+            ARIAInstruction::ProposeAuthorityTransferV2(ProposeAuthorityTransferV2Args {
+                new_authority: key,
+            }),
+            ARIAInstruction::AcceptAuthorityTransferV2,
+            ARIAInstruction::CancelAuthorityTransferV2,
+            ARIAInstruction::GetNextMintTimeV2(GetNextMintTimeV2Args { minter: key }),
+        ]
+    }
+
+    #[test]
+    fn test_discriminators_are_frozen() {
+        let instructions = every_instruction();
+        assert_eq!(instructions.len(), InstructionDiscriminator::ALL.len());
+        for instruction in instructions {
+            let discriminator = instruction.discriminator();
+            let data = instruction.pack();
+            assert_eq!(data[0], discriminator as u8, "{:?}", instruction);
+            // Only instructions with an args type carry bytes after the discriminator
+            assert_eq!(
+                discriminator.args_schema().is_some(),
+                data.len() > 1,
+                "{:?}",
+                instruction
+            );
+            let name = format!("{:?}", discriminator);
+            assert!(format!("{:?}", instruction).starts_with(&name));
+            assert_eq!(
+                ARIAInstruction::unpack(&data).unwrap().discriminator(),
+                discriminator
+            );
+        }
+        assert_eq!(InstructionDiscriminator::MintTokens as u8, 1);
+        assert_eq!(InstructionDiscriminator::UpdateMinterAllowance as u8, 32);
+        assert_eq!(InstructionDiscriminator::MigrateAccount as u8, 33);
+        assert_eq!(InstructionDiscriminator::GetProgramVersion as u8, 38);
+    }
+
+    #[test]
+    fn test_unpack_errors() {
+        assert!(matches!(
+            ARIAInstruction::unpack(&[]),
+            Err(AriaError::InvalidInstructionData)
+        ));
+        assert!(matches!(
+            ARIAInstruction::unpack(&[200, 1, 2, 3]),
+            Err(AriaError::UnknownInstruction)
+        ));
+        // Known discriminator with truncated arguments
+        assert!(matches!(
+            ARIAInstruction::unpack(&[InstructionDiscriminator::MintTokens as u8, 1]),
+            Err(AriaError::InvalidInstructionData)
+        ));
+        // Bytes after the arguments
+        let mut data = ARIAInstruction::MintTokens(MintTokensArgs { amount: 1 }).pack();
+        data.push(0);
+        assert!(matches!(
+            ARIAInstruction::unpack(&data),
+            Err(AriaError::InvalidInstructionData)
+        ));
+        assert!(matches!(
+            ARIAInstruction::unpack(&[InstructionDiscriminator::Pause as u8, 0]),
+            Err(AriaError::InvalidInstructionData)
+        ));
+    }
+
+    fn check_args<T: InstructionArgs>() {
+        let instruction = format!("{:?}", T::DISCRIMINATOR);
+        assert!(std::any::type_name::<T>().ends_with(&format!("::{}Args", instruction)));
+        let expected_version = match instruction.rsplit_once('V') {
+            Some((_, version))
+                if !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                version.parse().unwrap()
+            }
+            _ => 1,
+        };
+        assert_eq!(T::VERSION, expected_version, "{}", instruction);
+    }
+
+    #[test]
+    fn test_args_versions() {
+        check_args::<MintTokensArgs>();
+        check_args::<GetRemainingMintableArgs>();
+        check_args::<ProposeAuthorityTransferArgs>();
+        check_args::<ProposeAuthorityTransferV2Args>();
+        check_args::<GetNextMintTimeV2Args>();
+    }
+}
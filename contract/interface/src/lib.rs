@@ -0,0 +1,109 @@
+// ARIA Token interface
+// Instructions, account state, errors, events and PDA derivation for the ARIA token program,
+// without the program entrypoint. Clients and programs calling into ARIA depend on this crate.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{bpf_loader_upgradeable, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+pub mod account;
+pub mod checkpoint;
+pub mod error;
+pub mod events;
+pub mod fees;
+pub mod governance;
+#[cfg(feature = "idl")]
+pub mod idl;
+pub mod instruction;
+pub mod metadata;
+pub mod referral;
+pub mod roles;
+pub mod transfer_hook;
+pub mod treasury;
+pub mod view;
+
+use account::{AccountType, VersionedAccount, ACCOUNT_HEADER_LEN};
+use error::AriaError;
+
+// Token configuration
+pub const TOKEN_NAME: &str = "ARIA Token";
+pub const TOKEN_SYMBOL: &str = "ARI";
+pub const TOKEN_DECIMALS: u8 = 9;
+pub const TOTAL_SUPPLY: u64 = 100_000_000_000_000_000; // 100 million tokens with 9 decimals
+pub const MINT_COOLDOWN: i64 = 3600; // Minting cooldown period in seconds (1 hour)
+pub const AUTHORITY_TRANSFER_EXPIRY: i64 = 86400; // Authority transfer validity period in seconds (24 hours)
+
+// PDA seeds
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+
+// Basis point denominator (100%)
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+// Compute `amount * bps / 10_000`, rounding down
+pub fn apply_basis_points(amount: u64, bps: u16) -> Option<u64> {
+    let share = (amount as u128)
+        .checked_mul(bps as u128)?
+        .checked_div(MAX_BASIS_POINTS as u128)?;
+    u64::try_from(share).ok()
+}
+
+// Authority transfer state, stored in the program config
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Default)]
+pub struct AuthorityTransferState {
+    pub proposed_authority: Option<Pubkey>,
+    pub proposal_time: i64,
+}
+
+impl AuthorityTransferState {
+    pub const LEN: usize = 33 + 8;
+}
+
+// Global program config, created by InitializeMint.
+// The SPL mint authority is the program's mint authority PDA; `authority` is the admin,
+// allowed to configure the other subsystems and to grant roles in the role registry.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct ProgramConfig {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub authority_transfer: AuthorityTransferState,
+    /// Unix timestamp of the most recent mint by any minter. The cooldown is per minter and
+    /// uses `MinterAllowance::last_mint_time`.
+    pub last_mint_time: i64,
+    pub paused: bool,
+}
+
+impl ProgramConfig {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 32 + AuthorityTransferState::LEN + 8 + 1;
+
+    /// Fail with `ProgramPaused` while the program is paused
+    pub fn check_not_paused(&self) -> ProgramResult {
+        if self.paused {
+            msg!("Program is paused");
+            return Err(AriaError::ProgramPaused.into());
+        }
+        Ok(())
+    }
+}
+
+impl VersionedAccount for ProgramConfig {
+    const ACCOUNT_TYPE: AccountType = AccountType::ProgramConfig;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_config_address(program_id).0
+    }
+}
+
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+pub fn find_mint_authority_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINT_AUTHORITY_SEED, mint.as_ref()], program_id)
+}
+
+/// Program-data account of the deployed program, which records its upgrade authority
+pub fn find_program_data_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id())
+}
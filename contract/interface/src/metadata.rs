@@ -0,0 +1,46 @@
+// Token metadata fields
+// Token-2022 mints keep their metadata in the mint; legacy SPL Token mints use a Metaplex
+// metadata account.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{pubkey, pubkey::Pubkey};
+use spl_token_metadata_interface::state::Field;
+
+/// Metaplex token metadata program, which holds the metadata of legacy SPL Token mints
+pub const METAPLEX_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+pub const METAPLEX_METADATA_SEED: &[u8] = b"metadata";
+
+/// Metaplex metadata account of a legacy SPL Token mint
+pub fn find_metaplex_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            METAPLEX_METADATA_SEED,
+            METAPLEX_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &METAPLEX_PROGRAM_ID,
+    )
+}
+
+/// Metadata field changed by `UpdateMetadata`
+// The BorshSchema derive in borsh 0.10 copies each variant's fields into a struct that is never
+// read, so the payload fields carry their own `allow(dead_code)`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone)]
+pub enum MetadataField {
+    Name,
+    Symbol,
+    Uri,
+    /// Additional key-value entry, added if the key is not present yet. Token-2022 only.
+    Key(#[allow(dead_code)] String),
+}
+
+impl From<MetadataField> for Field {
+    fn from(field: MetadataField) -> Self {
+        match field {
+            MetadataField::Name => Field::Name,
+            MetadataField::Symbol => Field::Symbol,
+            MetadataField::Uri => Field::Uri,
+            MetadataField::Key(key) => Field::Key(key),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// Referral registry accounts
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::account::{AccountType, VersionedAccount, ACCOUNT_HEADER_LEN};
+use crate::apply_basis_points;
+use crate::error::AriaError;
+
+pub const REFERRAL_CONFIG_SEED: &[u8] = b"referral_config";
+pub const REFERRAL_RECORD_SEED: &[u8] = b"referral";
+pub const REFERRER_STATS_SEED: &[u8] = b"referrer_stats";
+
+// Referral share configuration for a mint
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct ReferralConfig {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub share_bps: u16,
+}
+
+impl ReferralConfig {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 2;
+}
+
+impl VersionedAccount for ReferralConfig {
+    const ACCOUNT_TYPE: AccountType = AccountType::ReferralConfig;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_referral_config_address(program_id, &self.mint).0
+    }
+}
+
+// Link between a user and their referrer, written once and never modified
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct ReferralRecord {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub referrer: Pubkey,
+    pub registered_at: i64,
+}
+
+impl ReferralRecord {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 32 + 32 + 8;
+}
+
+impl VersionedAccount for ReferralRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::ReferralRecord;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_referral_record_address(program_id, &self.mint, &self.user).0
+    }
+}
+
+// Running referral totals for a referrer
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Default)]
+pub struct ReferrerStats {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub referrer: Pubkey,
+    pub referral_count: u64,
+    pub total_rewards: u64,
+}
+
+impl ReferrerStats {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 32 + 8 + 8;
+}
+
+impl VersionedAccount for ReferrerStats {
+    const ACCOUNT_TYPE: AccountType = AccountType::ReferrerStats;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_referrer_stats_address(program_id, &self.mint, &self.referrer).0
+    }
+}
+
+pub fn find_referral_config_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REFERRAL_CONFIG_SEED, mint.as_ref()], program_id)
+}
+
+pub fn find_referral_record_address(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    user: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REFERRAL_RECORD_SEED, mint.as_ref(), user.as_ref()],
+        program_id,
+    )
+}
+
+pub fn find_referrer_stats_address(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    referrer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REFERRER_STATS_SEED, mint.as_ref(), referrer.as_ref()],
+        program_id,
+    )
+}
+
+/// Referrer's share of `amount` at `share_bps`, rounded down
+pub fn referral_share(amount: u64, share_bps: u16) -> Result<u64, ProgramError> {
+    apply_basis_points(amount, share_bps).ok_or_else(|| AriaError::ArithmeticOverflow.into())
+}
@@ -0,0 +1,131 @@
+// Roles and minter allowances
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::account::{AccountType, VersionedAccount, ACCOUNT_HEADER_LEN};
+use crate::error::AriaError;
+
+pub const ROLES_SEED: &[u8] = b"roles";
+pub const MINTER_ALLOWANCE_SEED: &[u8] = b"minter_allowance";
+
+/// Maximum number of role assignments in the registry
+pub const MAX_ROLE_ASSIGNMENTS: usize = 32;
+
+/// Role granted by the admin
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Role {
+    /// Mints tokens through MintTokens
+    Minter,
+    /// Pauses and unpauses the program
+    Pauser,
+    /// Configures referral rewards
+    Rewarder,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Minter, Role::Pauser, Role::Rewarder];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub struct RoleAssignment {
+    pub role: Role,
+    pub member: Pubkey,
+}
+
+// Global role registry, created by InitializeMint
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Default)]
+pub struct RoleRegistry {
+    pub is_initialized: bool,
+    pub assignments: Vec<RoleAssignment>,
+}
+
+impl RoleRegistry {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 4 + MAX_ROLE_ASSIGNMENTS * (1 + 32);
+
+    pub fn has_role(&self, member: &Pubkey, role: Role) -> bool {
+        self.assignments
+            .iter()
+            .any(|assignment| assignment.role == role && assignment.member == *member)
+    }
+}
+
+impl VersionedAccount for RoleRegistry {
+    const ACCOUNT_TYPE: AccountType = AccountType::RoleRegistry;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_role_registry_address(program_id).0
+    }
+}
+
+// Remaining amount a minter may mint, and when it last minted for its cooldown
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Default)]
+pub struct MinterAllowance {
+    pub is_initialized: bool,
+    pub minter: Pubkey,
+    pub remaining: u64,
+    /// Unix timestamp of the minter's last MintTokens, 0 before its first
+    pub last_mint_time: i64,
+}
+
+impl MinterAllowance {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 8 + 8;
+}
+
+impl VersionedAccount for MinterAllowance {
+    const ACCOUNT_TYPE: AccountType = AccountType::MinterAllowance;
+    const VERSION: u8 = 2;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_minter_allowance_address(program_id, &self.minter).0
+    }
+
+    // Versions 0 and 1 had no cooldown of their own; migrated minters start without one
+    fn unpack_outdated(version: u8, data: &[u8]) -> Result<(Self, usize), ProgramError> {
+        let mut state = match version {
+            0 => data,
+            1 => &data[ACCOUNT_HEADER_LEN..],
+            _ => return Err(AriaError::AccountVersionMismatch.into()),
+        };
+        let allowance = MinterAllowanceV1::deserialize(&mut state)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok((
+            MinterAllowance {
+                is_initialized: allowance.is_initialized,
+                minter: allowance.minter,
+                remaining: allowance.remaining,
+                last_mint_time: 0,
+            },
+            Self::LEN,
+        ))
+    }
+}
+
+/// Minter allowance layout of versions 0 and 1, before the per-minter cooldown
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub struct MinterAllowanceV1 {
+    pub is_initialized: bool,
+    pub minter: Pubkey,
+    pub remaining: u64,
+}
+
+/// Change applied to a minter allowance by `UpdateMinterAllowance`
+// See `MetadataField` for the field-level `allow(dead_code)`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub enum AllowanceChange {
+    /// Replace the remaining allowance
+    Set(#[allow(dead_code)] u64),
+    /// Add to the remaining allowance
+    Increase(#[allow(dead_code)] u64),
+    /// Subtract from the remaining allowance; fails if more than remains
+    Decrease(#[allow(dead_code)] u64),
+}
+
+pub fn find_role_registry_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROLES_SEED], program_id)
+}
+
+pub fn find_minter_allowance_address(program_id: &Pubkey, minter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINTER_ALLOWANCE_SEED, minter.as_ref()], program_id)
+}
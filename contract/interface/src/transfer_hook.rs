@@ -0,0 +1,61 @@
+// Transfer hook blocklist and extra account metas
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed};
+use spl_transfer_hook_interface::get_extra_account_metas_address_and_bump_seed;
+
+use crate::account::{AccountType, VersionedAccount, ACCOUNT_HEADER_LEN};
+
+pub const BLOCKLIST_SEED: &[u8] = b"blocklist";
+
+/// Maximum number of blocked owners per mint
+pub const MAX_BLOCKED: usize = 256;
+
+// Blocked owners of one mint, managed by the compliance authority
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Default)]
+pub struct Blocklist {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub compliance_authority: Pubkey,
+    pub blocked: Vec<Pubkey>,
+}
+
+impl Blocklist {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + 32 + 4 + MAX_BLOCKED * 32;
+
+    pub fn is_blocked(&self, owner: &Pubkey) -> bool {
+        self.blocked.contains(owner)
+    }
+}
+
+impl VersionedAccount for Blocklist {
+    const ACCOUNT_TYPE: AccountType = AccountType::Blocklist;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_blocklist_address(program_id, &self.mint).0
+    }
+}
+
+pub fn find_blocklist_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BLOCKLIST_SEED, mint.as_ref()], program_id)
+}
+
+pub fn find_extra_account_metas_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    get_extra_account_metas_address_and_bump_seed(mint, program_id)
+}
+
+/// Extra accounts Token-2022 passes to Execute: the blocklist PDA of the mint (account 1)
+pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>, ProgramError> {
+    Ok(vec![ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: BLOCKLIST_SEED.to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+        ],
+        false,
+        false,
+    )?])
+}
@@ -0,0 +1,108 @@
+// Treasury and spend proposal accounts
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::account::{AccountType, VersionedAccount, ACCOUNT_HEADER_LEN};
+
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const TREASURY_VAULT_SEED: &[u8] = b"treasury_vault";
+pub const SPEND_SEED: &[u8] = b"spend";
+
+/// Maximum number of treasury approvers
+pub const MAX_TREASURY_APPROVERS: usize = 10;
+
+// Asset moved by a spend
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub enum TreasuryAsset {
+    /// Lamports held directly by the vault PDA
+    Sol,
+    /// Tokens of the treasury mint held by a token account owned by the vault PDA
+    Token,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub enum SpendStatus {
+    Pending,
+    Executed,
+}
+
+// Treasury approver set and spend counter for a mint
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct TreasuryConfig {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub spend_count: u64,
+}
+
+impl TreasuryConfig {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN + 1 + 32 + (4 + 32 * MAX_TREASURY_APPROVERS) + 1 + 8;
+
+    pub fn is_approver(&self, key: &Pubkey) -> bool {
+        self.approvers.contains(key)
+    }
+}
+
+impl VersionedAccount for TreasuryConfig {
+    const ACCOUNT_TYPE: AccountType = AccountType::TreasuryConfig;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_treasury_address(program_id, &self.mint).0
+    }
+}
+
+// A proposed outflow, kept after execution as the record of the spend
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq)]
+pub struct SpendRecord {
+    pub is_initialized: bool,
+    pub mint: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub asset: TreasuryAsset,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub approvals: Vec<Pubkey>,
+    pub status: SpendStatus,
+    pub created_at: i64,
+    pub executed_at: i64,
+}
+
+impl SpendRecord {
+    pub const LEN: usize = ACCOUNT_HEADER_LEN
+        + (1 + 32 + 8 + 32 + 1 + 32 + 8 + (4 + 32 * MAX_TREASURY_APPROVERS) + 1 + 8 + 8);
+
+    // Approvals that still belong to the current approver set
+    pub fn approval_count(&self, config: &TreasuryConfig) -> usize {
+        self.approvals
+            .iter()
+            .filter(|approver| config.is_approver(approver))
+            .count()
+    }
+}
+
+impl VersionedAccount for SpendRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::SpendRecord;
+    const VERSION: u8 = 1;
+
+    fn address(&self, program_id: &Pubkey) -> Pubkey {
+        find_spend_address(program_id, &self.mint, self.index).0
+    }
+}
+
+pub fn find_treasury_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED, mint.as_ref()], program_id)
+}
+
+pub fn find_treasury_vault_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_VAULT_SEED, mint.as_ref()], program_id)
+}
+
+pub fn find_spend_address(program_id: &Pubkey, mint: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SPEND_SEED, mint.as_ref(), &index.to_le_bytes()],
+        program_id,
+    )
+}
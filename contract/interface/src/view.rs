@@ -0,0 +1,82 @@
+// View results
+// View instructions change no state and write their result, borsh-encoded, as the program's
+// return data. Simulating a transaction with one of them answers the question without fetching
+// or decoding any account. `GetRemainingMintable` returns a `u64` and `GetNextMintTimeV2` an `i64`;
+// the other views return the types below.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Result of `GetConfig`
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub struct ConfigView {
+    pub mint: Pubkey,
+    /// Program authority (admin)
+    pub authority: Pubkey,
+    pub paused: bool,
+    /// Unix timestamp of the last mint by any minter, 0 before the first one
+    pub last_mint_time: i64,
+}
+
+/// Result of `GetPendingAuthorityTransfer`, wrapped in an `Option` that is `None` when no
+/// transfer can be accepted
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Clone, Copy)]
+pub struct PendingAuthorityTransfer {
+    pub proposed_authority: Pubkey,
+    pub proposal_time: i64,
+    /// Last unix timestamp at which the transfer can be accepted
+    pub expires_at: i64,
+}
+
+/// Result of `GetProgramVersion`: the version of the deployed program crate
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ProgramVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+/// Decode the return data of a view instruction. `returned_by` is the program that set the
+/// data, as reported by the simulation; data set by any other program is rejected.
+pub fn decode_return_data<T: BorshDeserialize>(
+    program_id: &Pubkey,
+    returned_by: &Pubkey,
+    data: &[u8],
+) -> Result<T, ProgramError> {
+    if returned_by != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    T::try_from_slice(data).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_return_data() {
+        let program_id = Pubkey::new_unique();
+        let view = PendingAuthorityTransfer {
+            proposed_authority: Pubkey::new_unique(),
+            proposal_time: 1_700_000_000,
+            expires_at: 1_700_086_400,
+        };
+        let data = Some(view).try_to_vec().unwrap();
+        assert_eq!(
+            decode_return_data::<Option<PendingAuthorityTransfer>>(&program_id, &program_id, &data),
+            Ok(Some(view))
+        );
+        assert_eq!(
+            decode_return_data::<Option<PendingAuthorityTransfer>>(
+                &program_id,
+                &Pubkey::new_unique(),
+                &data
+            ),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        assert_eq!(
+            decode_return_data::<u64>(&program_id, &program_id, &[1, 2]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// Balance checkpoints
+// Every operation that locks ARI in the program records the owner's new locked balance
+// together with the slot. Readers such as governance and airdrop eligibility look up the
+// balance "as of" a slot instead of the live balance, so moving tokens between wallets
+// after a snapshot gains nothing.
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey, sysvar::rent::Rent,
+};
+
+use crate::utils::{check_pda, create_pda_account, load_state, store_state};
+
+pub use aria_token_interface::checkpoint::*;
+
+/// Locked balance of `owner` at the start of `slot`.
+/// An account that was never created has no history and a balance of zero.
+pub fn balance_at(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    checkpoint_info: &AccountInfo,
+    slot: u64,
+) -> Result<u64, ProgramError> {
+    let (checkpoint_address, _) = find_checkpoint_address(program_id, mint, owner);
+    check_pda(checkpoint_info, &checkpoint_address)?;
+    if checkpoint_info.owner != program_id {
+        return Ok(0);
+    }
+    let record: CheckpointRecord = load_state(checkpoint_info, program_id)?;
+    if !record.is_initialized {
+        return Ok(0);
+    }
+    record.amount_at(slot)
+}
+
+// Record the new locked balance of `owner`, creating the checkpoint record if needed
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn write_checkpoint<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    checkpoint_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    rent: &Rent,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    slot: u64,
+    amount: u64,
+) -> ProgramResult {
+    let (checkpoint_address, bump) = find_checkpoint_address(program_id, mint, owner);
+    check_pda(checkpoint_info, &checkpoint_address)?;
+    create_pda_account(
+        payer_info,
+        checkpoint_info,
+        system_program_info,
+        rent,
+        CheckpointRecord::LEN,
+        program_id,
+        &[CHECKPOINT_SEED, mint.as_ref(), owner.as_ref(), &[bump]],
+    )?;
+    record_checkpoint(program_id, checkpoint_info, mint, owner, slot, amount)
+}
+
+// Record the new locked balance of `owner` in an existing checkpoint record
+pub(crate) fn record_checkpoint(
+    program_id: &Pubkey,
+    checkpoint_info: &AccountInfo,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    slot: u64,
+    amount: u64,
+) -> ProgramResult {
+    let (checkpoint_address, _) = find_checkpoint_address(program_id, mint, owner);
+    check_pda(checkpoint_info, &checkpoint_address)?;
+    let mut record: CheckpointRecord = load_state(checkpoint_info, program_id)?;
+    if !record.is_initialized {
+        record = CheckpointRecord {
+            is_initialized: true,
+            mint: *mint,
+            owner: *owner,
+            ..CheckpointRecord::default()
+        };
+    }
+    record.record(slot, amount)?;
+    store_state(&record, checkpoint_info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use crate::migration::ACCOUNT_HEADER_LEN;
+    use crate::AriaError;
+
+    #[test]
+    fn test_amount_at() {
+        let mut record = CheckpointRecord::default();
+        assert_eq!(record.amount_at(10).unwrap(), 0);
+
+        record.record(10, 100).unwrap();
+        record.record(20, 40).unwrap();
+        // Later change in the same slot replaces the checkpoint
+        record.record(20, 50).unwrap();
+        assert_eq!(record.checkpoints.len(), 2);
+
+        assert_eq!(record.amount_at(10).unwrap(), 0);
+        assert_eq!(record.amount_at(11).unwrap(), 100);
+        assert_eq!(record.amount_at(20).unwrap(), 100);
+        assert_eq!(record.amount_at(21).unwrap(), 50);
+
+        assert_eq!(
+            record.record(19, 1).unwrap_err(),
+            ProgramError::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_pruning() {
+        let mut record = CheckpointRecord::default();
+        for slot in 0..=MAX_CHECKPOINTS as u64 {
+            record.record(slot + 1, slot).unwrap();
+        }
+        assert_eq!(record.checkpoints.len(), MAX_CHECKPOINTS);
+        assert!(record.pruned);
+        assert!(ACCOUNT_HEADER_LEN + record.try_to_vec().unwrap().len() <= CheckpointRecord::LEN);
+
+        assert_eq!(
+            record.amount_at(2).unwrap_err(),
+            AriaError::CheckpointUnavailable.into()
+        );
+        assert_eq!(record.amount_at(3).unwrap(), 1);
+    }
+}
@@ -0,0 +1,387 @@
+// Token-2022 transfer fees
+// InitializeMint can enable the transfer-fee extension with the program's mint authority PDA
+// as both fee config and withdraw authority. Withheld fees are harvested from holder accounts
+// into the mint and withdrawn from there to the treasury vault's token account only.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token_2022::extension::transfer_fee::instruction as fee_instruction;
+
+use crate::events::{self, emit};
+use crate::token::{check_mint_token_program, check_token_2022};
+use crate::treasury::find_treasury_vault_address;
+use crate::utils::check_pda;
+use crate::validation::TokenAccount;
+use crate::MINT_AUTHORITY_SEED;
+use crate::{
+    find_mint_authority_address, load_active_config, load_config_with_authority, AriaError,
+};
+
+pub use aria_token_interface::fees::*;
+
+// Enable the transfer-fee extension on an uninitialized mint.
+// Must run before the mint itself is initialized.
+pub(crate) fn initialize_transfer_fee<'a>(
+    program_id: &Pubkey,
+    mint_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    params: &TransferFeeParams,
+) -> ProgramResult {
+    check_token_2022(token_program_info, "Transfer fees")?;
+    params.check()?;
+
+    let (mint_authority_address, _) = find_mint_authority_address(program_id, mint_info.key);
+    let instruction = fee_instruction::initialize_transfer_fee_config(
+        token_program_info.key,
+        mint_info.key,
+        Some(&mint_authority_address),
+        Some(&mint_authority_address),
+        params.transfer_fee_basis_points,
+        params.maximum_fee,
+    )?;
+    invoke(
+        &instruction,
+        &[mint_info.clone(), token_program_info.clone()],
+    )?;
+
+    msg!(
+        "Transfer fee enabled: {} bps, maximum {}",
+        params.transfer_fee_basis_points,
+        params.maximum_fee
+    );
+    Ok(())
+}
+
+pub fn process_harvest_withheld_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    // Remaining accounts are the token accounts to harvest from
+    let source_infos = account_info_iter.as_slice();
+
+    load_active_config(program_id, config_info)?;
+
+    check_token_2022(token_program_info, "Transfer fees")?;
+    check_mint_token_program(mint_info, token_program_info)?;
+    if source_infos.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let sources: Vec<&Pubkey> = source_infos.iter().map(|info| info.key).collect();
+    let instruction = fee_instruction::harvest_withheld_tokens_to_mint(
+        token_program_info.key,
+        mint_info.key,
+        &sources,
+    )?;
+    let mut account_infos = vec![mint_info.clone()];
+    account_infos.extend(source_infos.iter().cloned());
+    account_infos.push(token_program_info.clone());
+    invoke(&instruction, &account_infos)?;
+
+    emit(&events::WithheldFeesHarvested {
+        mint: *mint_info.key,
+        source_count: sources.len() as u32,
+    });
+    msg!("Harvested withheld fees from {} accounts", sources.len());
+    Ok(())
+}
+
+pub fn process_withdraw_withheld_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let mint_info = next_account_info(account_info_iter)?;
+    let treasury_token_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    check_token_2022(token_program_info, "Transfer fees")?;
+    check_mint_token_program(mint_info, token_program_info)?;
+
+    let config = load_active_config(program_id, config_info)?;
+    if config.mint != *mint_info.key {
+        return Err(AriaError::TokenMintMismatch.into());
+    }
+    let (mint_authority_address, mint_authority_bump) =
+        find_mint_authority_address(program_id, mint_info.key);
+    check_pda(mint_authority_info, &mint_authority_address)?;
+
+    // Fees can only go to the treasury, so anyone may trigger the withdrawal
+    let (vault_address, _) = find_treasury_vault_address(program_id, mint_info.key);
+    let treasury_account = TokenAccount::load_writable(treasury_token_info, mint_info.key)?;
+    if treasury_account.state.owner != vault_address {
+        msg!("Withheld fees must be withdrawn to the treasury vault");
+        return Err(AriaError::InvalidProgramAddress.into());
+    }
+
+    let instruction = fee_instruction::withdraw_withheld_tokens_from_mint(
+        token_program_info.key,
+        mint_info.key,
+        treasury_token_info.key,
+        &mint_authority_address,
+        &[],
+    )?;
+    invoke_signed(
+        &instruction,
+        &[
+            mint_info.clone(),
+            treasury_token_info.clone(),
+            mint_authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[
+            MINT_AUTHORITY_SEED,
+            mint_info.key.as_ref(),
+            &[mint_authority_bump],
+        ]],
+    )?;
+
+    emit(&events::WithheldFeesWithdrawn {
+        mint: *mint_info.key,
+        destination: *treasury_token_info.key,
+    });
+    msg!(
+        "Withdrew withheld fees to treasury account {}",
+        treasury_token_info.key
+    );
+    Ok(())
+}
+
+pub fn process_update_transfer_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: TransferFeeParams,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let mint_authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    // Only the program authority may change the fee
+    let config = load_config_with_authority(program_id, config_info, authority_info)?;
+    config.check_not_paused()?;
+    if config.mint != *mint_info.key {
+        return Err(AriaError::TokenMintMismatch.into());
+    }
+
+    check_token_2022(token_program_info, "Transfer fees")?;
+    check_mint_token_program(mint_info, token_program_info)?;
+    params.check()?;
+
+    let (mint_authority_address, mint_authority_bump) =
+        find_mint_authority_address(program_id, mint_info.key);
+    check_pda(mint_authority_info, &mint_authority_address)?;
+
+    let instruction = fee_instruction::set_transfer_fee(
+        token_program_info.key,
+        mint_info.key,
+        &mint_authority_address,
+        &[],
+        params.transfer_fee_basis_points,
+        params.maximum_fee,
+    )?;
+    invoke_signed(
+        &instruction,
+        &[
+            mint_info.clone(),
+            mint_authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[
+            MINT_AUTHORITY_SEED,
+            mint_info.key.as_ref(),
+            &[mint_authority_bump],
+        ]],
+    )?;
+
+    // Token-2022 applies the new fee two epochs from now
+    emit(&events::TransferFeeUpdated {
+        mint: *mint_info.key,
+        transfer_fee_basis_points: params.transfer_fee_basis_points,
+        maximum_fee: params.maximum_fee,
+    });
+    msg!(
+        "Transfer fee updated: {} bps, maximum {}",
+        params.transfer_fee_basis_points,
+        params.maximum_fee
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token_2022::extension::transfer_fee::MAX_FEE_BASIS_POINTS;
+    use crate::test_utils::*;
+    use solana_program::program_pack::Pack;
+    use spl_token_2022::state::{Account, AccountState, Mint};
+
+    const FEE: TransferFeeParams = TransferFeeParams {
+        transfer_fee_basis_points: 50,
+        maximum_fee: 5_000,
+    };
+
+    fn token_2022_program_account() -> TestAccount {
+        TestAccount::new(spl_token_2022::id(), solana_program::bpf_loader::id(), 0)
+    }
+
+    // Token-2022 mint; the fee extension data is irrelevant because token CPIs are stubbed
+    fn token_2022_mint() -> TestAccount {
+        let mut mint =
+            TestAccount::new(Pubkey::new_unique(), spl_token_2022::id(), Mint::LEN).writable();
+        Mint::pack(
+            Mint {
+                is_initialized: true,
+                decimals: crate::TOKEN_DECIMALS,
+                ..Mint::default()
+            },
+            &mut mint.data,
+        )
+        .unwrap();
+        mint
+    }
+
+    fn token_2022_account(mint: &Pubkey, owner: &Pubkey) -> TestAccount {
+        let mut account =
+            TestAccount::new(Pubkey::new_unique(), spl_token_2022::id(), Account::LEN).writable();
+        Account::pack(
+            Account {
+                mint: *mint,
+                owner: *owner,
+                state: AccountState::Initialized,
+                ..Account::default()
+            },
+            &mut account.data,
+        )
+        .unwrap();
+        account
+    }
+
+    #[test]
+    fn test_initialize_transfer_fee_requires_token_2022() {
+        let program_id = Pubkey::new_unique();
+        let mut mint = mint_account(None, 0);
+        let mut legacy_program = token_program_account();
+        let result =
+            initialize_transfer_fee(&program_id, &mint.info(), &legacy_program.info(), &FEE);
+        assert_eq!(result.unwrap_err(), AriaError::Token2022Required.into());
+
+        let mut mint = token_2022_mint();
+        let mut token_program = token_2022_program_account();
+        let too_high = TransferFeeParams {
+            transfer_fee_basis_points: MAX_FEE_BASIS_POINTS + 1,
+            maximum_fee: 0,
+        };
+        let result =
+            initialize_transfer_fee(&program_id, &mint.info(), &token_program.info(), &too_high);
+        assert_eq!(result.unwrap_err(), AriaError::InvalidBasisPoints.into());
+        initialize_transfer_fee(&program_id, &mint.info(), &token_program.info(), &FEE).unwrap();
+    }
+
+    #[test]
+    fn test_withdraw_withheld_fees_to_treasury_only() {
+        let program_id = Pubkey::new_unique();
+        let mut mint = token_2022_mint();
+        let mut config = config_account(&program_id, &mint.key, &Pubkey::new_unique());
+        let (vault_key, _) = find_treasury_vault_address(&program_id, &mint.key);
+        let mut treasury_token = token_2022_account(&mint.key, &vault_key);
+        let mut other_token = token_2022_account(&mint.key, &Pubkey::new_unique());
+        let (mint_authority_key, _) = find_mint_authority_address(&program_id, &mint.key);
+        let mut mint_authority = TestAccount::new(mint_authority_key, Pubkey::default(), 0);
+        let mut token_program = token_2022_program_account();
+
+        let accounts = vec![
+            mint.info(),
+            other_token.info(),
+            config.info(),
+            mint_authority.info(),
+            token_program.info(),
+        ];
+        let result = process_withdraw_withheld_fees(&program_id, &accounts);
+        assert_eq!(result.unwrap_err(), AriaError::InvalidProgramAddress.into());
+        drop(accounts);
+
+        let accounts = vec![
+            mint.info(),
+            treasury_token.info(),
+            config.info(),
+            mint_authority.info(),
+            token_program.info(),
+        ];
+        process_withdraw_withheld_fees(&program_id, &accounts).unwrap();
+    }
+
+    #[test]
+    fn test_update_transfer_fee_authority() {
+        let program_id = Pubkey::new_unique();
+        let mut authority = TestAccount::wallet().signer();
+        let mut mint = token_2022_mint();
+        let mut config = config_account(&program_id, &mint.key, &authority.key);
+        let (mint_authority_key, _) = find_mint_authority_address(&program_id, &mint.key);
+        let mut mint_authority = TestAccount::new(mint_authority_key, Pubkey::default(), 0);
+        let mut token_program = token_2022_program_account();
+        let mut intruder = TestAccount::wallet().signer();
+
+        let accounts = vec![
+            intruder.info(),
+            config.info(),
+            mint.info(),
+            mint_authority.info(),
+            token_program.info(),
+        ];
+        let result = process_update_transfer_fee(&program_id, &accounts, FEE);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop(accounts);
+
+        let accounts = vec![
+            authority.info(),
+            config.info(),
+            mint.info(),
+            mint_authority.info(),
+            token_program.info(),
+        ];
+        process_update_transfer_fee(&program_id, &accounts, FEE).unwrap();
+    }
+
+    #[test]
+    fn test_harvest_withheld_fees() {
+        let program_id = Pubkey::new_unique();
+        let mut mint = token_2022_mint();
+        let mut config = config_account(&program_id, &mint.key, &Pubkey::new_unique());
+        let mut holder = token_2022_account(&mint.key, &Pubkey::new_unique());
+        let mut token_program = token_2022_program_account();
+
+        let accounts = vec![mint.info(), token_program.info(), config.info()];
+        let result = process_harvest_withheld_fees(&program_id, &accounts);
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+        drop(accounts);
+
+        let accounts = vec![
+            mint.info(),
+            token_program.info(),
+            config.info(),
+            holder.info(),
+        ];
+        process_harvest_withheld_fees(&program_id, &accounts).unwrap();
+    }
+}
@@ -0,0 +1,1489 @@
+// Token-weighted governance
+// ARI deposited into the governance vault gives voting power. Proposals carry a list of
+// instructions that are executed by CPI, signed by the governance authority PDA, once the
+// voting window has closed with quorum and the approval threshold met.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::rent::Rent,
+};
+
+use crate::checkpoint::{balance_at, record_checkpoint, write_checkpoint};
+use crate::events::{self, emit};
+use crate::migration::ACCOUNT_HEADER_LEN;
+use crate::token::{transfer_checked, unpack_mint};
+use crate::utils::{
+    apply_basis_points, check_pda, check_system_program, close_pda_account, create_pda_account,
+    load_state, store_state, MAX_BASIS_POINTS,
+};
+use crate::validation::{check_signer, load_clock, load_rent, MintAccount, TokenAccount};
+use crate::{load_active_config, load_config_with_authority, AriaError};
+
+pub use aria_token_interface::governance::*;
+
+// Load an initialized governance config after checking its address
+fn load_governance(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    governance_info: &AccountInfo,
+) -> Result<GovernanceConfig, ProgramError> {
+    let (governance_address, _) = find_governance_address(program_id, mint);
+    check_pda(governance_info, &governance_address)?;
+    let governance: GovernanceConfig = load_state(governance_info, program_id)?;
+    if !governance.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+    Ok(governance)
+}
+
+// Load an initialized voter record after checking its address
+fn load_voter_record(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    voter_info: &AccountInfo,
+) -> Result<VoterRecord, ProgramError> {
+    let (voter_address, _) = find_voter_record_address(program_id, mint, owner);
+    check_pda(voter_info, &voter_address)?;
+    let voter: VoterRecord = load_state(voter_info, program_id)?;
+    if !voter.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+    Ok(voter)
+}
+
+// Load an initialized proposal
+fn load_proposal(
+    program_id: &Pubkey,
+    proposal_info: &AccountInfo,
+) -> Result<Proposal, ProgramError> {
+    let proposal: Proposal = load_state(proposal_info, program_id)?;
+    if !proposal.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+    let (proposal_address, _) = find_proposal_address(program_id, &proposal.mint, proposal.index);
+    check_pda(proposal_info, &proposal_address)?;
+    Ok(proposal)
+}
+
+// Verify a token account holding deposited governing tokens
+fn check_vault_token_account(
+    vault_token_info: &AccountInfo,
+    vault_address: &Pubkey,
+    mint: &Pubkey,
+) -> ProgramResult {
+    let vault_account = TokenAccount::load_writable(vault_token_info, mint)?;
+    if vault_account.state.owner != *vault_address {
+        return Err(AriaError::InvalidProgramAddress.into());
+    }
+    Ok(())
+}
+
+pub fn process_configure_governance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    voting_period: i64,
+    quorum_bps: u16,
+    approval_threshold_bps: u16,
+    min_proposal_power: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if voting_period <= 0
+        || quorum_bps > MAX_BASIS_POINTS
+        || approval_threshold_bps == 0
+        || approval_threshold_bps > MAX_BASIS_POINTS
+    {
+        return Err(AriaError::InvalidGovernanceConfig.into());
+    }
+
+    // Only the program authority may configure governance
+    let config = load_config_with_authority(program_id, config_info, authority_info)?;
+    config.check_not_paused()?;
+    let mint = config.mint;
+
+    let (governance_address, bump) = find_governance_address(program_id, &mint);
+    check_pda(governance_info, &governance_address)?;
+    check_system_program(system_program_info)?;
+
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        authority_info,
+        governance_info,
+        system_program_info,
+        rent,
+        GovernanceConfig::LEN,
+        program_id,
+        &[GOVERNANCE_SEED, mint.as_ref(), &[bump]],
+    )?;
+
+    // Keep the proposal counter when updating existing governance
+    let existing: GovernanceConfig = load_state(governance_info, program_id)?;
+    let proposal_count = if existing.is_initialized {
+        existing.proposal_count
+    } else {
+        0
+    };
+
+    let governance = GovernanceConfig {
+        is_initialized: true,
+        mint,
+        voting_period,
+        quorum_bps,
+        approval_threshold_bps,
+        min_proposal_power,
+        proposal_count,
+    };
+    store_state(&governance, governance_info)?;
+
+    emit(&events::GovernanceConfigured {
+        mint,
+        voting_period,
+        quorum_bps,
+        approval_threshold_bps,
+        min_proposal_power,
+    });
+    msg!(
+        "Governance configured: voting period {}s, quorum {} bps, threshold {} bps",
+        voting_period,
+        quorum_bps,
+        approval_threshold_bps
+    );
+    Ok(())
+}
+
+pub fn process_deposit_governing_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let vault_token_info = next_account_info(account_info_iter)?;
+    let voter_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let checkpoint_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    load_active_config(program_id, config_info)?;
+
+    // Verify owner
+    check_signer(owner_info)?;
+
+    // Verify token program and load the mint it owns
+    let mint = MintAccount::load(mint_info, token_program_info)?;
+    check_system_program(system_program_info)?;
+
+    if amount == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let (vault_address, _) = find_governance_vault_address(program_id, mint_info.key);
+    check_vault_token_account(vault_token_info, &vault_address, mint_info.key)?;
+
+    let (voter_address, voter_bump) =
+        find_voter_record_address(program_id, mint_info.key, owner_info.key);
+    check_pda(voter_info, &voter_address)?;
+
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        owner_info,
+        voter_info,
+        system_program_info,
+        rent,
+        VoterRecord::LEN,
+        program_id,
+        &[
+            VOTER_SEED,
+            mint_info.key.as_ref(),
+            owner_info.key.as_ref(),
+            &[voter_bump],
+        ],
+    )?;
+    let mut voter: VoterRecord = load_state(voter_info, program_id)?;
+    if !voter.is_initialized {
+        voter = VoterRecord {
+            is_initialized: true,
+            mint: *mint_info.key,
+            owner: *owner_info.key,
+            ..VoterRecord::default()
+        };
+    }
+
+    transfer_checked(
+        token_program_info,
+        source_info,
+        mint_info,
+        vault_token_info,
+        owner_info,
+        account_info_iter.as_slice(),
+        amount,
+        mint.state.decimals,
+        &[],
+    )?;
+
+    voter.deposited_amount = voter
+        .deposited_amount
+        .checked_add(amount)
+        .ok_or(AriaError::ArithmeticOverflow)?;
+    store_state(&voter, voter_info)?;
+
+    let clock = load_clock(clock_info)?;
+    write_checkpoint(
+        program_id,
+        owner_info,
+        checkpoint_info,
+        system_program_info,
+        rent,
+        mint_info.key,
+        owner_info.key,
+        clock.slot,
+        voter.deposited_amount,
+    )?;
+
+    emit(&events::GoverningTokensDeposited {
+        mint: *mint_info.key,
+        owner: *owner_info.key,
+        amount,
+        deposited_amount: voter.deposited_amount,
+    });
+    msg!(
+        "Deposited {} governing tokens for {}, total {}",
+        amount,
+        owner_info.key,
+        voter.deposited_amount
+    );
+    Ok(())
+}
+
+pub fn process_withdraw_governing_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let owner_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let vault_token_info = next_account_info(account_info_iter)?;
+    let voter_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let checkpoint_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    load_active_config(program_id, config_info)?;
+
+    // Verify owner
+    check_signer(owner_info)?;
+
+    // Verify token program and load the mint it owns
+    let mint = MintAccount::load(mint_info, token_program_info)?;
+
+    let (vault_address, vault_bump) = find_governance_vault_address(program_id, mint_info.key);
+    check_pda(vault_info, &vault_address)?;
+    check_vault_token_account(vault_token_info, &vault_address, mint_info.key)?;
+
+    let mut voter = load_voter_record(program_id, mint_info.key, owner_info.key, voter_info)?;
+
+    voter.deposited_amount = voter
+        .deposited_amount
+        .checked_sub(amount)
+        .ok_or(AriaError::InsufficientVotingPower)?;
+
+    transfer_checked(
+        token_program_info,
+        vault_token_info,
+        mint_info,
+        destination_info,
+        vault_info,
+        account_info_iter.as_slice(),
+        amount,
+        mint.state.decimals,
+        &[&[GOVERNANCE_VAULT_SEED, mint_info.key.as_ref(), &[vault_bump]]],
+    )?;
+
+    store_state(&voter, voter_info)?;
+
+    // Withdrawals after a proposal's snapshot do not change its voting power
+    let clock = load_clock(clock_info)?;
+    record_checkpoint(
+        program_id,
+        checkpoint_info,
+        mint_info.key,
+        owner_info.key,
+        clock.slot,
+        voter.deposited_amount,
+    )?;
+
+    emit(&events::GoverningTokensWithdrawn {
+        mint: *mint_info.key,
+        owner: *owner_info.key,
+        amount,
+        deposited_amount: voter.deposited_amount,
+    });
+    msg!(
+        "Withdrew {} governing tokens for {}, remaining {}",
+        amount,
+        owner_info.key,
+        voter.deposited_amount
+    );
+    Ok(())
+}
+
+pub fn process_create_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    description: String,
+    instructions: Vec<ProposalInstruction>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let proposer_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let voter_info = next_account_info(account_info_iter)?;
+    let proposal_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    load_active_config(program_id, config_info)?;
+
+    // Verify proposer
+    check_signer(proposer_info)?;
+
+    if description.len() > MAX_DESCRIPTION_LEN
+        || instructions.is_empty()
+        || instructions.len() > MAX_PROPOSAL_INSTRUCTIONS
+    {
+        return Err(AriaError::InvalidProposal.into());
+    }
+
+    let mut governance = load_governance(program_id, mint_info.key, governance_info)?;
+    let voter = load_voter_record(program_id, mint_info.key, proposer_info.key, voter_info)?;
+    if voter.deposited_amount < governance.min_proposal_power {
+        return Err(AriaError::InsufficientVotingPower.into());
+    }
+
+    let index = governance.proposal_count;
+    let (proposal_address, bump) = find_proposal_address(program_id, mint_info.key, index);
+    check_pda(proposal_info, &proposal_address)?;
+    check_system_program(system_program_info)?;
+
+    // Quorum is measured against the supply at creation
+    let mint_data = unpack_mint(mint_info)?;
+    let quorum_votes = apply_basis_points(mint_data.supply, governance.quorum_bps)
+        .ok_or(AriaError::ArithmeticOverflow)?;
+
+    let clock = load_clock(clock_info)?;
+    let proposal = Proposal {
+        is_initialized: true,
+        mint: *mint_info.key,
+        index,
+        proposer: *proposer_info.key,
+        created_at: clock.unix_timestamp,
+        snapshot_slot: clock.slot,
+        voting_ends_at: clock
+            .unix_timestamp
+            .checked_add(governance.voting_period)
+            .ok_or(AriaError::ArithmeticOverflow)?,
+        quorum_votes,
+        approval_threshold_bps: governance.approval_threshold_bps,
+        for_votes: 0,
+        against_votes: 0,
+        abstain_votes: 0,
+        executed: false,
+        executed_at: 0,
+        description,
+        instructions,
+    };
+    let proposal_len = proposal
+        .try_to_vec()
+        .map_err(|_| AriaError::InvalidProposal)?
+        .len();
+
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        proposer_info,
+        proposal_info,
+        system_program_info,
+        rent,
+        ACCOUNT_HEADER_LEN + proposal_len,
+        program_id,
+        &[
+            PROPOSAL_SEED,
+            mint_info.key.as_ref(),
+            &index.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+    store_state(&proposal, proposal_info)?;
+
+    governance.proposal_count = index.checked_add(1).ok_or(AriaError::ArithmeticOverflow)?;
+    store_state(&governance, governance_info)?;
+
+    emit(&events::ProposalCreated {
+        mint: *mint_info.key,
+        index,
+        proposer: *proposer_info.key,
+        voting_ends_at: proposal.voting_ends_at,
+    });
+    msg!(
+        "Proposal {} created by {}, voting ends at {}",
+        index,
+        proposer_info.key,
+        proposal.voting_ends_at
+    );
+    Ok(())
+}
+
+// Create the vote record of `owner` on a proposal.
+// Vote records are keyed by the owner of the voting power, so power is counted once
+// whether it is cast directly or by a delegate.
+#[allow(clippy::too_many_arguments)]
+fn record_vote<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    proposal_info: &AccountInfo<'a>,
+    vote_record_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    rent: &Rent,
+    owner: &Pubkey,
+    vote: Vote,
+    weight: u64,
+) -> ProgramResult {
+    let (vote_record_address, vote_record_bump) =
+        find_vote_record_address(program_id, proposal_info.key, owner);
+    check_pda(vote_record_info, &vote_record_address)?;
+    if vote_record_info.owner == program_id {
+        let existing: VoteRecord = load_state(vote_record_info, program_id)?;
+        if existing.is_initialized {
+            msg!("Voting power of {} was already used", owner);
+            return Err(AriaError::AlreadyVoted.into());
+        }
+    }
+
+    create_pda_account(
+        payer_info,
+        vote_record_info,
+        system_program_info,
+        rent,
+        VoteRecord::LEN,
+        program_id,
+        &[
+            VOTE_SEED,
+            proposal_info.key.as_ref(),
+            owner.as_ref(),
+            &[vote_record_bump],
+        ],
+    )?;
+
+    let vote_record = VoteRecord {
+        is_initialized: true,
+        proposal: *proposal_info.key,
+        voter: *owner,
+        cast_by: *payer_info.key,
+        vote,
+        weight,
+    };
+    store_state(&vote_record, vote_record_info)
+}
+
+pub fn process_cast_vote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    vote: Vote,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let voter_authority_info = next_account_info(account_info_iter)?;
+    let proposal_info = next_account_info(account_info_iter)?;
+    let checkpoint_info = next_account_info(account_info_iter)?;
+    let vote_record_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    // Remaining accounts come in groups of three, one per delegator
+    let delegator_accounts = account_info_iter.as_slice();
+
+    load_active_config(program_id, config_info)?;
+
+    // Verify voter
+    check_signer(voter_authority_info)?;
+    if !delegator_accounts.len().is_multiple_of(3) {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    check_system_program(system_program_info)?;
+
+    let mut proposal = load_proposal(program_id, proposal_info)?;
+    let clock = load_clock(clock_info)?;
+    if clock.unix_timestamp >= proposal.voting_ends_at {
+        return Err(AriaError::VotingClosed.into());
+    }
+    let rent = &load_rent(rent_info)?;
+
+    // The voter's own balance at the snapshot. A delegate without a history passes an empty account.
+    let own_weight = balance_at(
+        program_id,
+        &proposal.mint,
+        voter_authority_info.key,
+        checkpoint_info,
+        proposal.snapshot_slot,
+    )?;
+    if own_weight > 0 {
+        record_vote(
+            program_id,
+            voter_authority_info,
+            proposal_info,
+            vote_record_info,
+            system_program_info,
+            rent,
+            voter_authority_info.key,
+            vote,
+            own_weight,
+        )?;
+    }
+    let mut total_weight = own_weight;
+
+    // Power delegated to the voter
+    for group in delegator_accounts.chunks(3) {
+        let delegate_record_info = &group[0];
+        let delegator_checkpoint_info = &group[1];
+        let delegator_vote_record_info = &group[2];
+
+        let delegate_record: DelegateRecord = load_state(delegate_record_info, program_id)?;
+        if !delegate_record.is_initialized || delegate_record.mint != proposal.mint {
+            return Err(AriaError::AccountNotInitialized.into());
+        }
+        let (delegate_record_address, _) =
+            find_delegate_record_address(program_id, &proposal.mint, &delegate_record.delegator);
+        check_pda(delegate_record_info, &delegate_record_address)?;
+        if delegate_record.delegate != *voter_authority_info.key {
+            return Err(AriaError::NotDelegate.into());
+        }
+
+        let delegated_weight = balance_at(
+            program_id,
+            &proposal.mint,
+            &delegate_record.delegator,
+            delegator_checkpoint_info,
+            proposal.snapshot_slot,
+        )?;
+        if delegated_weight == 0 {
+            continue;
+        }
+        record_vote(
+            program_id,
+            voter_authority_info,
+            proposal_info,
+            delegator_vote_record_info,
+            system_program_info,
+            rent,
+            &delegate_record.delegator,
+            vote,
+            delegated_weight,
+        )?;
+        total_weight = total_weight
+            .checked_add(delegated_weight)
+            .ok_or(AriaError::ArithmeticOverflow)?;
+    }
+
+    if total_weight == 0 {
+        return Err(AriaError::InsufficientVotingPower.into());
+    }
+
+    proposal.add_votes(vote, total_weight)?;
+    store_state(&proposal, proposal_info)?;
+
+    emit(&events::VoteCast {
+        proposal: *proposal_info.key,
+        voter: *voter_authority_info.key,
+        vote,
+        weight: total_weight,
+    });
+    msg!(
+        "Vote {:?} with weight {} on proposal {}",
+        vote,
+        total_weight,
+        proposal.index
+    );
+    Ok(())
+}
+
+pub fn process_set_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    delegate: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let delegator_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let delegate_record_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let config = load_active_config(program_id, config_info)?;
+    if config.mint != *mint_info.key {
+        return Err(AriaError::TokenMintMismatch.into());
+    }
+
+    // Verify delegator
+    check_signer(delegator_info)?;
+    if delegate == *delegator_info.key {
+        return Err(AriaError::SelfDelegation.into());
+    }
+
+    let (delegate_record_address, bump) =
+        find_delegate_record_address(program_id, mint_info.key, delegator_info.key);
+    check_pda(delegate_record_info, &delegate_record_address)?;
+    check_system_program(system_program_info)?;
+
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        delegator_info,
+        delegate_record_info,
+        system_program_info,
+        rent,
+        DelegateRecord::LEN,
+        program_id,
+        &[
+            DELEGATE_SEED,
+            mint_info.key.as_ref(),
+            delegator_info.key.as_ref(),
+            &[bump],
+        ],
+    )?;
+
+    // Replaces any previous delegate
+    let clock = load_clock(clock_info)?;
+    let delegate_record = DelegateRecord {
+        is_initialized: true,
+        mint: *mint_info.key,
+        delegator: *delegator_info.key,
+        delegate,
+        delegated_at: clock.unix_timestamp,
+    };
+    store_state(&delegate_record, delegate_record_info)?;
+
+    emit(&events::DelegateSet {
+        mint: *mint_info.key,
+        delegator: *delegator_info.key,
+        delegate,
+    });
+    msg!(
+        "{} delegated voting power to {}",
+        delegator_info.key,
+        delegate
+    );
+    Ok(())
+}
+
+pub fn process_revoke_delegate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let delegator_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let delegate_record_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let config = load_active_config(program_id, config_info)?;
+    if config.mint != *mint_info.key {
+        return Err(AriaError::TokenMintMismatch.into());
+    }
+
+    // Verify delegator
+    check_signer(delegator_info)?;
+
+    let (delegate_record_address, _) =
+        find_delegate_record_address(program_id, mint_info.key, delegator_info.key);
+    check_pda(delegate_record_info, &delegate_record_address)?;
+    let delegate_record: DelegateRecord = load_state(delegate_record_info, program_id)?;
+    if !delegate_record.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+
+    // Votes already cast by the delegate stay counted
+    close_pda_account(delegate_record_info, delegator_info)?;
+
+    emit(&events::DelegateRevoked {
+        mint: *mint_info.key,
+        delegator: *delegator_info.key,
+        delegate: delegate_record.delegate,
+    });
+    msg!(
+        "{} revoked delegation to {}",
+        delegator_info.key,
+        delegate_record.delegate
+    );
+    Ok(())
+}
+
+pub fn process_execute_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let proposal_info = next_account_info(account_info_iter)?;
+    let governance_authority_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    // Remaining accounts are passed to the proposal's instructions
+    let remaining_accounts = account_info_iter.as_slice();
+
+    load_active_config(program_id, config_info)?;
+    let mut proposal = load_proposal(program_id, proposal_info)?;
+    if proposal.executed {
+        return Err(AriaError::ProposalAlreadyExecuted.into());
+    }
+
+    let clock = load_clock(clock_info)?;
+    if clock.unix_timestamp < proposal.voting_ends_at {
+        return Err(AriaError::VotingInProgress.into());
+    }
+    if !proposal.is_passed() {
+        return Err(AriaError::ProposalNotPassed.into());
+    }
+
+    let (authority_address, authority_bump) =
+        find_governance_authority_address(program_id, &proposal.mint);
+    check_pda(governance_authority_info, &authority_address)?;
+
+    // Mark as executed before the CPIs so a proposal can never run twice
+    proposal.executed = true;
+    proposal.executed_at = clock.unix_timestamp;
+    store_state(&proposal, proposal_info)?;
+
+    let mut account_infos = remaining_accounts.to_vec();
+    account_infos.push(governance_authority_info.clone());
+    account_infos.push(config_info.clone());
+    let authority_seeds: &[&[u8]] = &[
+        GOVERNANCE_AUTHORITY_SEED,
+        proposal.mint.as_ref(),
+        &[authority_bump],
+    ];
+    for proposal_instruction in proposal.instructions.iter() {
+        let instruction = Instruction::from(proposal_instruction);
+        invoke_signed(&instruction, &account_infos, &[authority_seeds])?;
+    }
+
+    emit(&events::ProposalExecuted {
+        proposal: *proposal_info.key,
+        index: proposal.index,
+    });
+    msg!(
+        "Proposal {} executed with {} instructions",
+        proposal.index,
+        proposal.instructions.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use crate::checkpoint::{find_checkpoint_address, CheckpointRecord};
+    use crate::test_utils::*;
+    use crate::ProgramConfig;
+
+    const NOW: i64 = 1_700_000_000;
+    const SLOT: u64 = 100;
+    const VOTING_PERIOD: i64 = 3 * 86_400;
+
+    struct GovernanceEnv {
+        program_id: Pubkey,
+        mint: TestAccount,
+        governance: TestAccount,
+        clock: TestAccount,
+        rent: TestAccount,
+        system_program: TestAccount,
+        program_config: TestAccount,
+    }
+
+    // A voter with a deposit made before the proposals under test
+    struct Voter {
+        wallet: TestAccount,
+        record: TestAccount,
+        checkpoints: TestAccount,
+    }
+
+    impl GovernanceEnv {
+        // Supply of 1,000 with a 10% quorum and a simple majority
+        fn new() -> Self {
+            let program_id = Pubkey::new_unique();
+            let mint = mint_account(Some(Pubkey::new_unique()), 1_000);
+            let (governance_key, _) = find_governance_address(&program_id, &mint.key);
+            let mut governance =
+                TestAccount::new(governance_key, program_id, GovernanceConfig::LEN).writable();
+            GovernanceConfig {
+                is_initialized: true,
+                mint: mint.key,
+                voting_period: VOTING_PERIOD,
+                quorum_bps: 1_000,
+                approval_threshold_bps: 5_000,
+                min_proposal_power: 10,
+                proposal_count: 0,
+            }
+            .pack_into(&mut governance.data)
+            .unwrap();
+            let program_config = config_account(&program_id, &mint.key, &Pubkey::new_unique());
+            GovernanceEnv {
+                program_id,
+                mint,
+                governance,
+                clock: slot_clock_account(NOW, SLOT),
+                rent: rent_account(),
+                system_program: system_program_account(),
+                program_config,
+            }
+        }
+
+        fn voter(&self, deposited_amount: u64) -> Voter {
+            self.voter_at(deposited_amount, SLOT - 1)
+        }
+
+        // Voter whose deposit was made in `slot`
+        fn voter_at(&self, deposited_amount: u64, slot: u64) -> Voter {
+            let wallet = TestAccount::wallet().signer();
+            let (record_key, _) =
+                find_voter_record_address(&self.program_id, &self.mint.key, &wallet.key);
+            let mut record =
+                TestAccount::new(record_key, self.program_id, VoterRecord::LEN).writable();
+            VoterRecord {
+                is_initialized: true,
+                mint: self.mint.key,
+                owner: wallet.key,
+                deposited_amount,
+            }
+            .pack_into(&mut record.data)
+            .unwrap();
+            let (checkpoint_key, _) =
+                find_checkpoint_address(&self.program_id, &self.mint.key, &wallet.key);
+            let mut checkpoints =
+                TestAccount::new(checkpoint_key, self.program_id, CheckpointRecord::LEN).writable();
+            let mut history = CheckpointRecord {
+                is_initialized: true,
+                mint: self.mint.key,
+                owner: wallet.key,
+                ..CheckpointRecord::default()
+            };
+            history.record(slot, deposited_amount).unwrap();
+            history.pack_into(&mut checkpoints.data).unwrap();
+            Voter {
+                wallet,
+                record,
+                checkpoints,
+            }
+        }
+
+        fn create_proposal(&mut self, proposer: &mut Voter) -> Result<TestAccount, ProgramError> {
+            let (proposal_key, _) = find_proposal_address(&self.program_id, &self.mint.key, 0);
+            let mut proposal = TestAccount::new(proposal_key, self.program_id, 1_024).writable();
+            let instruction = ProposalInstruction {
+                program_id: Pubkey::new_unique(),
+                accounts: vec![ProposalAccountMeta {
+                    pubkey: find_governance_authority_address(&self.program_id, &self.mint.key).0,
+                    is_signer: true,
+                    is_writable: false,
+                }],
+                data: vec![1, 2, 3],
+            };
+            let accounts = vec![
+                proposer.wallet.info(),
+                self.governance.info(),
+                proposer.record.info(),
+                proposal.info(),
+                self.mint.info(),
+                self.clock.info(),
+                self.rent.info(),
+                self.system_program.info(),
+                self.program_config.info(),
+            ];
+            process_create_proposal(
+                &self.program_id,
+                &accounts,
+                "Move the treasury under governance".to_string(),
+                vec![instruction],
+            )?;
+            drop(accounts);
+            Ok(proposal)
+        }
+
+        fn vote(
+            &mut self,
+            voter: &mut Voter,
+            proposal: &mut TestAccount,
+            vote: Vote,
+        ) -> ProgramResult {
+            let (vote_record_key, _) =
+                find_vote_record_address(&self.program_id, &proposal.key, &voter.wallet.key);
+            let mut vote_record =
+                TestAccount::new(vote_record_key, self.program_id, VoteRecord::LEN).writable();
+            self.vote_with_record(voter, proposal, &mut vote_record, vote)
+        }
+
+        fn vote_with_record(
+            &mut self,
+            voter: &mut Voter,
+            proposal: &mut TestAccount,
+            vote_record: &mut TestAccount,
+            vote: Vote,
+        ) -> ProgramResult {
+            let accounts = vec![
+                voter.wallet.info(),
+                proposal.info(),
+                voter.checkpoints.info(),
+                vote_record.info(),
+                self.clock.info(),
+                self.rent.info(),
+                self.system_program.info(),
+                self.program_config.info(),
+            ];
+            process_cast_vote(&self.program_id, &accounts, vote)
+        }
+
+        fn execute(&mut self, proposal: &mut TestAccount) -> ProgramResult {
+            let (authority_key, _) =
+                find_governance_authority_address(&self.program_id, &self.mint.key);
+            let mut authority = TestAccount::new(authority_key, Pubkey::default(), 0);
+            let accounts = vec![
+                proposal.info(),
+                authority.info(),
+                self.clock.info(),
+                self.program_config.info(),
+            ];
+            process_execute_proposal(&self.program_id, &accounts)
+        }
+    }
+
+    // Initialized delegate record assigning `delegator`'s power to `delegate`
+    fn delegate_record(env: &GovernanceEnv, delegator: &Pubkey, delegate: &Pubkey) -> TestAccount {
+        let (key, _) = find_delegate_record_address(&env.program_id, &env.mint.key, delegator);
+        let mut account = TestAccount::new(key, env.program_id, DelegateRecord::LEN);
+        DelegateRecord {
+            is_initialized: true,
+            mint: env.mint.key,
+            delegator: *delegator,
+            delegate: *delegate,
+            delegated_at: NOW,
+        }
+        .pack_into(&mut account.data)
+        .unwrap();
+        account
+    }
+
+    fn vote_record_account(env: &GovernanceEnv, proposal: &Pubkey, voter: &Pubkey) -> TestAccount {
+        let (key, _) = find_vote_record_address(&env.program_id, proposal, voter);
+        TestAccount::new(key, env.program_id, VoteRecord::LEN).writable()
+    }
+
+    fn proposal_state(account: &TestAccount) -> Proposal {
+        Proposal::unpack(&account.data).unwrap()
+    }
+
+    #[test]
+    fn test_proposal_outcome() {
+        let mut proposal = Proposal {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            index: 0,
+            proposer: Pubkey::new_unique(),
+            created_at: 0,
+            snapshot_slot: 0,
+            voting_ends_at: 0,
+            quorum_votes: 100,
+            approval_threshold_bps: 6_000,
+            for_votes: 60,
+            against_votes: 40,
+            abstain_votes: 0,
+            executed: false,
+            executed_at: 0,
+            description: String::new(),
+            instructions: vec![],
+        };
+        assert!(proposal.is_passed());
+
+        // Threshold ignores abstentions, quorum counts them
+        proposal.for_votes = 59;
+        proposal.abstain_votes = 1;
+        assert!(!proposal.is_passed());
+        proposal.for_votes = 60;
+        proposal.against_votes = 30;
+        proposal.abstain_votes = 5;
+        assert!(!proposal.is_passed());
+        proposal.abstain_votes = 10;
+        assert!(proposal.is_passed());
+    }
+
+    #[test]
+    fn test_state_sizes() {
+        assert_eq!(
+            ACCOUNT_HEADER_LEN + VoterRecord::default().try_to_vec().unwrap().len(),
+            VoterRecord::LEN
+        );
+        let record = VoteRecord {
+            is_initialized: true,
+            proposal: Pubkey::new_unique(),
+            voter: Pubkey::new_unique(),
+            cast_by: Pubkey::new_unique(),
+            vote: Vote::Abstain,
+            weight: 1,
+        };
+        assert_eq!(ACCOUNT_HEADER_LEN + record.try_to_vec().unwrap().len(), VoteRecord::LEN);
+    }
+
+    #[test]
+    fn test_configure_governance() {
+        let program_id = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut authority = TestAccount::wallet().signer();
+        let mut config = config_account(&program_id, &mint, &authority.key);
+        let (governance_key, _) = find_governance_address(&program_id, &mint);
+        let mut governance =
+            TestAccount::new(governance_key, program_id, GovernanceConfig::LEN).writable();
+        let mut rent = rent_account();
+        let mut system_program = system_program_account();
+
+        let accounts = vec![
+            authority.info(),
+            config.info(),
+            governance.info(),
+            rent.info(),
+            system_program.info(),
+        ];
+        let result = process_configure_governance(&program_id, &accounts, VOTING_PERIOD, 400, 0, 0);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::InvalidGovernanceConfig.into()
+        );
+
+        process_configure_governance(&program_id, &accounts, VOTING_PERIOD, 400, 5_000, 1).unwrap();
+        drop(accounts);
+        let state = GovernanceConfig::unpack(&governance.data).unwrap();
+        assert_eq!(state.voting_period, VOTING_PERIOD);
+        assert_eq!(state.quorum_bps, 400);
+        assert_eq!(state.approval_threshold_bps, 5_000);
+    }
+
+    #[test]
+    fn test_create_proposal_requires_voting_power() {
+        let mut env = GovernanceEnv::new();
+        let mut proposer = env.voter(9);
+        let result = env.create_proposal(&mut proposer);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::InsufficientVotingPower.into()
+        );
+    }
+
+    #[test]
+    fn test_proposal_lifecycle() {
+        let mut env = GovernanceEnv::new();
+        let mut proposer = env.voter(60);
+        let mut opponent = env.voter(40);
+        let mut proposal = env.create_proposal(&mut proposer).unwrap();
+
+        let state = proposal_state(&proposal);
+        assert_eq!(state.quorum_votes, 100);
+        assert_eq!(state.voting_ends_at, NOW + VOTING_PERIOD);
+        assert_eq!(state.snapshot_slot, SLOT);
+
+        env.vote(&mut proposer, &mut proposal, Vote::For).unwrap();
+        env.vote(&mut opponent, &mut proposal, Vote::Against)
+            .unwrap();
+
+        // Voting is still open
+        let result = env.execute(&mut proposal);
+        assert_eq!(result.unwrap_err(), AriaError::VotingInProgress.into());
+
+        env.clock = slot_clock_account(NOW + VOTING_PERIOD, SLOT + 1);
+        let result = env.vote(&mut env.voter(1), &mut proposal, Vote::Against);
+        assert_eq!(result.unwrap_err(), AriaError::VotingClosed.into());
+
+        env.execute(&mut proposal).unwrap();
+        let state = proposal_state(&proposal);
+        assert!(state.executed);
+        assert_eq!(state.for_votes, 60);
+        assert_eq!(state.against_votes, 40);
+
+        let result = env.execute(&mut proposal);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::ProposalAlreadyExecuted.into()
+        );
+    }
+
+    #[test]
+    fn test_execute_proposal_paused() {
+        let mut env = GovernanceEnv::new();
+        let mut proposer = env.voter(60);
+        let mut opponent = env.voter(40);
+        let mut proposal = env.create_proposal(&mut proposer).unwrap();
+        env.vote(&mut proposer, &mut proposal, Vote::For).unwrap();
+        env.vote(&mut opponent, &mut proposal, Vote::Against)
+            .unwrap();
+        env.clock = slot_clock_account(NOW + VOTING_PERIOD, SLOT + 1);
+
+        let mut config = ProgramConfig::unpack(&env.program_config.data).unwrap();
+        config.paused = true;
+        config.pack_into(&mut env.program_config.data).unwrap();
+        let result = env.execute(&mut proposal);
+        assert_eq!(result.unwrap_err(), AriaError::ProgramPaused.into());
+        assert!(!proposal_state(&proposal).executed);
+
+        config.paused = false;
+        config.pack_into(&mut env.program_config.data).unwrap();
+        env.execute(&mut proposal).unwrap();
+    }
+
+    #[test]
+    fn test_defeated_proposal() {
+        let mut env = GovernanceEnv::new();
+        let mut proposer = env.voter(50);
+        let mut proposal = env.create_proposal(&mut proposer).unwrap();
+
+        // Below quorum
+        env.vote(&mut proposer, &mut proposal, Vote::For).unwrap();
+        env.clock = slot_clock_account(NOW + VOTING_PERIOD, SLOT + 1);
+        let result = env.execute(&mut proposal);
+        assert_eq!(result.unwrap_err(), AriaError::ProposalNotPassed.into());
+    }
+
+    #[test]
+    fn test_vote_once() {
+        let mut env = GovernanceEnv::new();
+        let mut proposer = env.voter(60);
+        let mut proposal = env.create_proposal(&mut proposer).unwrap();
+
+        let mut vote_record = vote_record_account(&env, &proposal.key, &proposer.wallet.key);
+        env.vote_with_record(&mut proposer, &mut proposal, &mut vote_record, Vote::For)
+            .unwrap();
+        let result =
+            env.vote_with_record(&mut proposer, &mut proposal, &mut vote_record, Vote::For);
+        assert_eq!(result.unwrap_err(), AriaError::AlreadyVoted.into());
+        assert_eq!(proposal_state(&proposal).for_votes, 60);
+    }
+
+    #[test]
+    fn test_voting_power_from_snapshot() {
+        let mut env = GovernanceEnv::new();
+        let mut proposer = env.voter(60);
+        let mut proposal = env.create_proposal(&mut proposer).unwrap();
+
+        // Tokens deposited in the creation slot or later carry no power on this proposal
+        let mut late = env.voter_at(500, SLOT);
+        let result = env.vote(&mut late, &mut proposal, Vote::Against);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::InsufficientVotingPower.into()
+        );
+
+        // Withdrawing after the snapshot keeps the snapshot power
+        env.clock = slot_clock_account(NOW + 60, SLOT + 5);
+        let (vault_key, _) = find_governance_vault_address(&env.program_id, &env.mint.key);
+        let mut vault = TestAccount::new(vault_key, Pubkey::default(), 0);
+        let mut vault_token = token_account(&env.mint.key, &vault_key, 60);
+        let mut destination = token_account(&env.mint.key, &proposer.wallet.key, 0);
+        let mut token_program = token_program_account();
+        let accounts = vec![
+            proposer.wallet.info(),
+            destination.info(),
+            vault_token.info(),
+            proposer.record.info(),
+            vault.info(),
+            env.mint.info(),
+            env.clock.info(),
+            token_program.info(),
+            proposer.checkpoints.info(),
+            env.program_config.info(),
+        ];
+        process_withdraw_governing_tokens(&env.program_id, &accounts, 60).unwrap();
+        drop(accounts);
+        let voter = VoterRecord::unpack(&proposer.record.data).unwrap();
+        assert_eq!(voter.deposited_amount, 0);
+        let history = CheckpointRecord::unpack(&proposer.checkpoints.data).unwrap();
+        assert_eq!(history.amount_at(SLOT + 6).unwrap(), 0);
+
+        env.vote(&mut proposer, &mut proposal, Vote::For).unwrap();
+        assert_eq!(proposal_state(&proposal).for_votes, 60);
+    }
+
+    #[test]
+    fn test_deposit_governing_tokens() {
+        let mut env = GovernanceEnv::new();
+        let mut owner = TestAccount::wallet().signer();
+        let (voter_key, _) = find_voter_record_address(&env.program_id, &env.mint.key, &owner.key);
+        let mut record = TestAccount::new(voter_key, env.program_id, VoterRecord::LEN).writable();
+        let (vault_key, _) = find_governance_vault_address(&env.program_id, &env.mint.key);
+        let mut source = token_account(&env.mint.key, &owner.key, 100);
+        let mut vault_token = token_account(&env.mint.key, &vault_key, 0);
+        let mut wrong_vault = token_account(&env.mint.key, &owner.key, 0);
+        let mut token_program = token_program_account();
+        let (checkpoint_key, _) =
+            find_checkpoint_address(&env.program_id, &env.mint.key, &owner.key);
+        let mut checkpoints =
+            TestAccount::new(checkpoint_key, env.program_id, CheckpointRecord::LEN).writable();
+
+        let accounts = vec![
+            owner.info(),
+            source.info(),
+            wrong_vault.info(),
+            record.info(),
+            env.mint.info(),
+            env.rent.info(),
+            token_program.info(),
+            env.system_program.info(),
+            checkpoints.info(),
+            env.clock.info(),
+            env.program_config.info(),
+        ];
+        let result = process_deposit_governing_tokens(&env.program_id, &accounts, 70);
+        assert_eq!(result.unwrap_err(), AriaError::InvalidProgramAddress.into());
+        drop(accounts);
+
+        for slot in [SLOT, SLOT + 10] {
+            env.clock = slot_clock_account(NOW, slot);
+            let accounts = vec![
+                owner.info(),
+                source.info(),
+                vault_token.info(),
+                record.info(),
+                env.mint.info(),
+                env.rent.info(),
+                token_program.info(),
+                env.system_program.info(),
+                checkpoints.info(),
+                env.clock.info(),
+                env.program_config.info(),
+            ];
+            process_deposit_governing_tokens(&env.program_id, &accounts, 35).unwrap();
+        }
+        let voter = VoterRecord::unpack(&record.data).unwrap();
+        assert_eq!(voter.owner, owner.key);
+        assert_eq!(voter.deposited_amount, 70);
+
+        let history = CheckpointRecord::unpack(&checkpoints.data).unwrap();
+        assert_eq!(history.owner, owner.key);
+        assert_eq!(history.amount_at(SLOT).unwrap(), 0);
+        assert_eq!(history.amount_at(SLOT + 1).unwrap(), 35);
+        assert_eq!(history.amount_at(SLOT + 11).unwrap(), 70);
+    }
+
+    #[test]
+    fn test_set_and_revoke_delegate() {
+        let mut env = GovernanceEnv::new();
+        let mut delegator = TestAccount::wallet().signer();
+        let delegate = Pubkey::new_unique();
+        let (record_key, _) =
+            find_delegate_record_address(&env.program_id, &env.mint.key, &delegator.key);
+        let mut record =
+            TestAccount::new(record_key, env.program_id, DelegateRecord::LEN).writable();
+        let mut mint = mint_account(None, 0);
+        mint.key = env.mint.key;
+        let mut clock = clock_account(NOW);
+        let mut rent = rent_account();
+        let mut system_program = system_program_account();
+
+        let self_key = delegator.key;
+        let accounts = vec![
+            delegator.info(),
+            mint.info(),
+            record.info(),
+            clock.info(),
+            rent.info(),
+            system_program.info(),
+            env.program_config.info(),
+        ];
+        let result = process_set_delegate(&env.program_id, &accounts, self_key);
+        assert_eq!(result.unwrap_err(), AriaError::SelfDelegation.into());
+        process_set_delegate(&env.program_id, &accounts, delegate).unwrap();
+        drop(accounts);
+        let state = DelegateRecord::unpack(&record.data).unwrap();
+        assert_eq!(state.delegator, delegator.key);
+        assert_eq!(state.delegate, delegate);
+
+        let accounts = vec![
+            delegator.info(),
+            mint.info(),
+            record.info(),
+            env.program_config.info(),
+        ];
+        process_revoke_delegate(&env.program_id, &accounts).unwrap();
+        drop(accounts);
+        assert_eq!(record.lamports, 0);
+        assert_eq!(delegator.lamports, 2_000_000);
+        assert!(record.data.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_delegate_rejects_foreign_mint() {
+        let mut env = GovernanceEnv::new();
+        let mut delegator = TestAccount::wallet().signer();
+        let mut mint = mint_account(None, 0);
+        let (record_key, _) =
+            find_delegate_record_address(&env.program_id, &mint.key, &delegator.key);
+        let mut record =
+            TestAccount::new(record_key, env.program_id, DelegateRecord::LEN).writable();
+        let mut clock = clock_account(NOW);
+        let mut rent = rent_account();
+        let mut system_program = system_program_account();
+
+        let accounts = vec![
+            delegator.info(),
+            mint.info(),
+            record.info(),
+            clock.info(),
+            rent.info(),
+            system_program.info(),
+            env.program_config.info(),
+        ];
+        let result = process_set_delegate(&env.program_id, &accounts, Pubkey::new_unique());
+        assert_eq!(result.unwrap_err(), AriaError::TokenMintMismatch.into());
+        drop(accounts);
+        assert!(record.data.iter().all(|byte| *byte == 0));
+
+        let accounts = vec![
+            delegator.info(),
+            mint.info(),
+            record.info(),
+            env.program_config.info(),
+        ];
+        let result = process_revoke_delegate(&env.program_id, &accounts);
+        assert_eq!(result.unwrap_err(), AriaError::TokenMintMismatch.into());
+    }
+
+    #[test]
+    fn test_delegate_votes_with_summed_power() {
+        let mut env = GovernanceEnv::new();
+        let mut delegate = env.voter(20);
+        let mut delegator = env.voter(50);
+        let mut proposal = env.create_proposal(&mut delegate).unwrap();
+
+        let mut delegation = delegate_record(&env, &delegator.wallet.key, &delegate.wallet.key);
+        let mut own_vote = vote_record_account(&env, &proposal.key, &delegate.wallet.key);
+        let mut delegated_vote = vote_record_account(&env, &proposal.key, &delegator.wallet.key);
+        let accounts = vec![
+            delegate.wallet.info(),
+            proposal.info(),
+            delegate.checkpoints.info(),
+            own_vote.info(),
+            env.clock.info(),
+            env.rent.info(),
+            env.system_program.info(),
+            env.program_config.info(),
+            delegation.info(),
+            delegator.checkpoints.info(),
+            delegated_vote.info(),
+        ];
+        process_cast_vote(&env.program_id, &accounts, Vote::For).unwrap();
+        drop(accounts);
+
+        assert_eq!(proposal_state(&proposal).for_votes, 70);
+        let record = VoteRecord::unpack(&delegated_vote.data).unwrap();
+        assert_eq!(record.voter, delegator.wallet.key);
+        assert_eq!(record.cast_by, delegate.wallet.key);
+        assert_eq!(record.weight, 50);
+
+        // The delegator's power was already used
+        let result = env.vote_with_record(
+            &mut delegator,
+            &mut proposal,
+            &mut delegated_vote,
+            Vote::Against,
+        );
+        assert_eq!(result.unwrap_err(), AriaError::AlreadyVoted.into());
+        assert_eq!(proposal_state(&proposal).against_votes, 0);
+    }
+
+    #[test]
+    fn test_delegate_cannot_recount_direct_vote() {
+        let mut env = GovernanceEnv::new();
+        let mut delegate = env.voter(20);
+        let mut delegator = env.voter(50);
+        let mut proposal = env.create_proposal(&mut delegate).unwrap();
+
+        let mut delegated_vote = vote_record_account(&env, &proposal.key, &delegator.wallet.key);
+        env.vote_with_record(
+            &mut delegator,
+            &mut proposal,
+            &mut delegated_vote,
+            Vote::Against,
+        )
+        .unwrap();
+
+        let mut delegation = delegate_record(&env, &delegator.wallet.key, &delegate.wallet.key);
+        let mut own_vote = vote_record_account(&env, &proposal.key, &delegate.wallet.key);
+        let accounts = vec![
+            delegate.wallet.info(),
+            proposal.info(),
+            delegate.checkpoints.info(),
+            own_vote.info(),
+            env.clock.info(),
+            env.rent.info(),
+            env.system_program.info(),
+            env.program_config.info(),
+            delegation.info(),
+            delegator.checkpoints.info(),
+            delegated_vote.info(),
+        ];
+        let result = process_cast_vote(&env.program_id, &accounts, Vote::For);
+        assert_eq!(result.unwrap_err(), AriaError::AlreadyVoted.into());
+        drop(accounts);
+
+        // Only the delegate of record can use the power
+        let mut outsider = env.voter(5);
+        let mut outsider_vote = vote_record_account(&env, &proposal.key, &outsider.wallet.key);
+        let mut other_delegator = env.voter(30);
+        let mut other_delegation =
+            delegate_record(&env, &other_delegator.wallet.key, &delegate.wallet.key);
+        let mut other_vote = vote_record_account(&env, &proposal.key, &other_delegator.wallet.key);
+        let accounts = vec![
+            outsider.wallet.info(),
+            proposal.info(),
+            outsider.checkpoints.info(),
+            outsider_vote.info(),
+            env.clock.info(),
+            env.rent.info(),
+            env.system_program.info(),
+            env.program_config.info(),
+            other_delegation.info(),
+            other_delegator.checkpoints.info(),
+            other_vote.info(),
+        ];
+        let result = process_cast_vote(&env.program_id, &accounts, Vote::For);
+        assert_eq!(result.unwrap_err(), AriaError::NotDelegate.into());
+    }
+}
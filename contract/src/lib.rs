@@ -3,114 +3,143 @@
 
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    entrypoint,
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
-    program_pack::Pack,
     pubkey::Pubkey,
-    sysvar::{rent::Rent, clock::Clock, Sysvar},
     program::invoke_signed,
 };
-use spl_token::{
-    instruction::{initialize_mint, mint_to},
-    state::{Mint, Account},
-    error::TokenError,
+#[cfg(not(feature = "bootstrap-authority"))]
+use solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use spl_token_2022::instruction::{initialize_mint, mint_to};
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
+
+use events::emit;
+
+pub mod checkpoint;
+pub mod fees;
+pub mod governance;
+pub mod metadata;
+pub mod migration;
+pub mod pause;
+pub mod referral;
+pub mod roles;
+mod token;
+pub mod transfer_hook;
+pub mod treasury;
+mod utils;
+mod validation;
+pub mod view;
+
+#[cfg(test)]
+mod test_utils;
+
+// Program entry point, left out when the crate is used as a library with `no-entrypoint`
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+// Instructions, state, errors and PDA helpers live in the aria-token-interface crate
+pub use aria_token_interface::error::AriaError;
+pub use aria_token_interface::events;
+pub use aria_token_interface::instruction::ARIAInstruction;
+use aria_token_interface::instruction::{
+    ApproveSpendArgs, BlockAddressArgs, CastVoteArgs, ConfigureGovernanceArgs,
+    ConfigureTreasuryArgs, CreateProposalArgs, DepositGoverningTokensArgs, ExecuteSpendArgs,
+    GetNextMintTimeV2Args, GetRemainingMintableArgs, GrantRoleArgs, InitializeMintArgs,
+    InitializeTransferHookArgs, MigrateAccountArgs, MintTokensArgs, PayWithReferralArgs,
+    ProposeAuthorityTransferV2Args, ProposeSpendArgs, RegisterReferrerArgs, RevokeRoleArgs,
+    SetComplianceAuthorityArgs, SetDelegateArgs, SetReferralShareArgs, UnblockAddressArgs,
+    UpdateMetadataArgs, UpdateMinterAllowanceArgs, UpdateTransferFeeArgs,
+    WithdrawGoverningTokensArgs,
 };
-use borsh::{BorshDeserialize, BorshSerialize};
-use thiserror::Error;
-
-// Program entry point
-entrypoint!(process_instruction);
-
-// Token configuration
-const TOKEN_DECIMALS: u8 = 9;
-const TOTAL_SUPPLY: u64 = 100_000_000_000_000_000; // 100 million tokens with 9 decimals
-const MINT_COOLDOWN: i64 = 3600; // Minting cooldown period in seconds (1 hour)
-const AUTHORITY_TRANSFER_EXPIRY: i64 = 86400; // Authority transfer validity period in seconds (24 hours)
-
-// Define error types
-#[derive(Error, Debug, Copy, Clone)]
-pub enum AriaError {
-    #[error("Invalid instruction data")]
-    InvalidInstructionData,
-    
-    #[error("Insufficient authority")]
-    InsufficientAuthority,
-    
-    #[error("Invalid account count")]
-    InvalidAccountCount,
-    
-    #[error("Token mint mismatch")]
-    TokenMintMismatch,
-    
-    #[error("Exceeds supply cap")]
-    ExceedsSupplyCap,
-    
-    #[error("Mint cooldown active")]
-    MintCooldownActive,
-    
-    #[error("Authority transfer request not found")]
-    AuthorityTransferNotFound,
-    
-    #[error("Authority transfer request expired")]
-    AuthorityTransferExpired,
+pub use aria_token_interface::{
+    find_config_address, find_mint_authority_address, find_program_data_address,
+    AuthorityTransferState, ProgramConfig, AUTHORITY_TRANSFER_EXPIRY, CONFIG_SEED,
+    MINT_AUTHORITY_SEED, MINT_COOLDOWN, TOKEN_DECIMALS, TOKEN_NAME, TOKEN_SYMBOL, TOTAL_SUPPLY,
+};
+
+// Load the initialized program config after checking its address
+pub(crate) fn load_config(
+    program_id: &Pubkey,
+    config_info: &AccountInfo,
+) -> Result<ProgramConfig, ProgramError> {
+    let (config_address, _) = find_config_address(program_id);
+    utils::check_pda(config_info, &config_address)?;
+    let config: ProgramConfig = utils::load_state(config_info, program_id)?;
+    if !config.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+    Ok(config)
 }
 
-impl From<AriaError> for ProgramError {
-    fn from(e: AriaError) -> Self {
-        ProgramError::Custom(e as u32)
+// Load the program config and verify that the program is not paused
+pub(crate) fn load_active_config(
+    program_id: &Pubkey,
+    config_info: &AccountInfo,
+) -> Result<ProgramConfig, ProgramError> {
+    let config = load_config(program_id, config_info)?;
+    config.check_not_paused()?;
+    Ok(config)
+}
+
+// Load the program config and verify that `authority_info` is its signing authority
+pub(crate) fn load_config_with_authority(
+    program_id: &Pubkey,
+    config_info: &AccountInfo,
+    authority_info: &AccountInfo,
+) -> Result<ProgramConfig, ProgramError> {
+    validation::check_signer(authority_info)?;
+    let config = load_config(program_id, config_info)?;
+    if config.authority != *authority_info.key {
+        return Err(AriaError::InsufficientAuthority.into());
     }
+    Ok(config)
 }
 
-// Authority transfer state, stored in mint account's data extension
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
-pub struct AuthorityTransferState {
-    pub proposed_authority: Option<Pubkey>,
-    pub proposal_time: i64,
+// Verify that `authority_info` may initialize the program: the upgrade authority recorded in the
+// program's program-data account, so nobody can front-run the deployment
+#[cfg(not(feature = "bootstrap-authority"))]
+pub(crate) fn check_initialize_authority(
+    program_id: &Pubkey,
+    authority_info: &AccountInfo,
+    program_data_info: &AccountInfo,
+) -> ProgramResult {
+    let (program_data_address, _) = find_program_data_address(program_id);
+    utils::check_pda(program_data_info, &program_data_address)?;
+    if program_data_info.owner != &bpf_loader_upgradeable::id() {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let data = program_data_info.data.borrow();
+    let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata().min(data.len());
+    let upgrade_authority = match bincode::deserialize(&data[..metadata_len]) {
+        Ok(UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address,
+            ..
+        }) => upgrade_authority_address,
+        _ => return Err(ProgramError::InvalidAccountData),
+    };
+    if upgrade_authority != Some(*authority_info.key) {
+        msg!("InitializeMint must be signed by the program's upgrade authority");
+        return Err(AriaError::InsufficientAuthority.into());
+    }
+    Ok(())
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub enum ARIAInstruction {
-    /// Initialize a new ARIA token
-    /// Accounts required:
-    /// 0. `[signer]` Authority account that will mint tokens
-    /// 1. `[writable]` The mint account to initialize
-    /// 2. `[]` The rent sysvar
-    /// 3. `[]` The token program
-    InitializeMint,
-
-    /// Mint tokens to an account
-    /// Accounts required:
-    /// 0. `[signer]` Mint authority account
-    /// 1. `[writable]` The mint account
-    /// 2. `[writable]` The destination account
-    /// 3. `[]` The token program
-    /// 4. `[]` The clock sysvar
-    MintTokens { amount: u64 },
-    
-    /// Propose transfer of mint authority
-    /// Accounts required:
-    /// 0. `[signer]` Current mint authority account
-    /// 1. `[writable]` The mint account
-    /// 2. `[]` New mint authority account
-    /// 3. `[]` The clock sysvar
-    ProposeAuthorityTransfer { new_authority: Pubkey },
-    
-    /// Accept mint authority transfer
-    /// Accounts required:
-    /// 0. `[signer]` New mint authority account
-    /// 1. `[writable]` The mint account
-    /// 2. `[]` Current mint authority account
-    /// 3. `[]` The token program
-    /// 4. `[]` The clock sysvar
-    AcceptAuthorityTransfer,
-    
-    /// Cancel proposed authority transfer
-    /// Accounts required:
-    /// 0. `[signer]` Current mint authority account
-    /// 1. `[writable]` The mint account
-    CancelAuthorityTransfer,
+// Verify that `authority_info` is the bootstrap key the program was built with
+#[cfg(feature = "bootstrap-authority")]
+pub(crate) fn check_initialize_authority(
+    _program_id: &Pubkey,
+    authority_info: &AccountInfo,
+    _program_data_info: &AccountInfo,
+) -> ProgramResult {
+    let bootstrap_authority = env!("ARIA_BOOTSTRAP_AUTHORITY")
+        .parse::<Pubkey>()
+        .map_err(|_| ProgramError::InvalidArgument)?;
+    if *authority_info.key != bootstrap_authority {
+        msg!("InitializeMint must be signed by the bootstrap authority");
+        return Err(AriaError::InsufficientAuthority.into());
+    }
+    Ok(())
 }
 
 pub fn process_instruction(
@@ -118,34 +147,251 @@ pub fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let instruction = ARIAInstruction::try_from_slice(instruction_data)
-        .map_err(|_| AriaError::InvalidInstructionData)?;
+    // Token-2022 calls the transfer hook interface's Execute, which is not an ARIAInstruction
+    match transfer_hook::unpack_interface(instruction_data) {
+        Some(TransferHookInstruction::Execute { amount }) => {
+            msg!("Instruction: Transfer Hook Execute");
+            return transfer_hook::process_execute(program_id, accounts, amount);
+        }
+        Some(TransferHookInstruction::InitializeExtraAccountMetaList {
+            extra_account_metas,
+        }) => {
+            msg!("Instruction: Initialize Extra Account Meta List");
+            return transfer_hook::process_initialize_extra_account_meta_list(
+                program_id,
+                accounts,
+                &extra_account_metas,
+            );
+        }
+        Some(TransferHookInstruction::UpdateExtraAccountMetaList {
+            extra_account_metas,
+        }) => {
+            msg!("Instruction: Update Extra Account Meta List");
+            return transfer_hook::process_update_extra_account_meta_list(
+                program_id,
+                accounts,
+                &extra_account_metas,
+            );
+        }
+        None => {}
+    }
+
+    let instruction = ARIAInstruction::unpack(instruction_data)?;
 
     match instruction {
-        ARIAInstruction::InitializeMint => {
+        ARIAInstruction::InitializeMint(InitializeMintArgs {
+            transfer_fee,
+            transfer_hook_program,
+            metadata_uri,
+        }) => {
             msg!("Instruction: Initialize ARIA Mint");
-            process_initialize_mint(program_id, accounts)
+            process_initialize_mint(
+                program_id,
+                accounts,
+                transfer_fee,
+                transfer_hook_program,
+                metadata_uri,
+            )
         }
-        ARIAInstruction::MintTokens { amount } => {
+        ARIAInstruction::MintTokens(MintTokensArgs { amount }) => {
             msg!("Instruction: Mint Tokens");
-            process_mint_tokens(accounts, amount)
+            process_mint_tokens(program_id, accounts, amount)
+        }
+        // The original authority transfer instructions took the mint instead of the config
+        ARIAInstruction::ProposeAuthorityTransfer(..)
+        | ARIAInstruction::AcceptAuthorityTransfer
+        | ARIAInstruction::CancelAuthorityTransfer => {
+            msg!("Authority transfers moved to the V2 instructions, which take the program config");
+            Err(AriaError::InstructionDeprecated.into())
         }
-        ARIAInstruction::ProposeAuthorityTransfer { new_authority } => {
+        ARIAInstruction::ProposeAuthorityTransferV2(ProposeAuthorityTransferV2Args {
+            new_authority,
+        }) => {
             msg!("Instruction: Propose Authority Transfer");
-            process_propose_authority_transfer(accounts, new_authority)
+            process_propose_authority_transfer(program_id, accounts, new_authority)
         }
-        ARIAInstruction::AcceptAuthorityTransfer => {
+        ARIAInstruction::AcceptAuthorityTransferV2 => {
             msg!("Instruction: Accept Authority Transfer");
-            process_accept_authority_transfer(accounts)
+            process_accept_authority_transfer(program_id, accounts)
         }
-        ARIAInstruction::CancelAuthorityTransfer => {
+        ARIAInstruction::CancelAuthorityTransferV2 => {
             msg!("Instruction: Cancel Authority Transfer");
-            process_cancel_authority_transfer(accounts)
+            process_cancel_authority_transfer(program_id, accounts)
+        }
+        ARIAInstruction::SetReferralShare(SetReferralShareArgs { share_bps }) => {
+            msg!("Instruction: Set Referral Share");
+            referral::process_set_referral_share(program_id, accounts, share_bps)
+        }
+        ARIAInstruction::RegisterReferrer(RegisterReferrerArgs { referrer }) => {
+            msg!("Instruction: Register Referrer");
+            referral::process_register_referrer(program_id, accounts, referrer)
+        }
+        ARIAInstruction::PayWithReferral(PayWithReferralArgs { amount }) => {
+            msg!("Instruction: Pay With Referral");
+            referral::process_pay_with_referral(program_id, accounts, amount)
+        }
+        ARIAInstruction::ConfigureTreasury(ConfigureTreasuryArgs { approvers, threshold }) => {
+            msg!("Instruction: Configure Treasury");
+            treasury::process_configure_treasury(program_id, accounts, approvers, threshold)
+        }
+        ARIAInstruction::ProposeSpend(ProposeSpendArgs { asset, recipient, amount }) => {
+            msg!("Instruction: Propose Spend");
+            treasury::process_propose_spend(program_id, accounts, asset, recipient, amount)
+        }
+        ARIAInstruction::ApproveSpend(ApproveSpendArgs { index }) => {
+            msg!("Instruction: Approve Spend");
+            treasury::process_approve_spend(program_id, accounts, index)
+        }
+        ARIAInstruction::ExecuteSpend(ExecuteSpendArgs { index }) => {
+            msg!("Instruction: Execute Spend");
+            treasury::process_execute_spend(program_id, accounts, index)
+        }
+        ARIAInstruction::ConfigureGovernance(ConfigureGovernanceArgs {
+            voting_period,
+            quorum_bps,
+            approval_threshold_bps,
+            min_proposal_power,
+        }) => {
+            msg!("Instruction: Configure Governance");
+            governance::process_configure_governance(
+                program_id,
+                accounts,
+                voting_period,
+                quorum_bps,
+                approval_threshold_bps,
+                min_proposal_power,
+            )
+        }
+        ARIAInstruction::DepositGoverningTokens(DepositGoverningTokensArgs { amount }) => {
+            msg!("Instruction: Deposit Governing Tokens");
+            governance::process_deposit_governing_tokens(program_id, accounts, amount)
+        }
+        ARIAInstruction::WithdrawGoverningTokens(WithdrawGoverningTokensArgs { amount }) => {
+            msg!("Instruction: Withdraw Governing Tokens");
+            governance::process_withdraw_governing_tokens(program_id, accounts, amount)
+        }
+        ARIAInstruction::CreateProposal(CreateProposalArgs { description, instructions }) => {
+            msg!("Instruction: Create Proposal");
+            governance::process_create_proposal(program_id, accounts, description, instructions)
+        }
+        ARIAInstruction::CastVote(CastVoteArgs { vote }) => {
+            msg!("Instruction: Cast Vote");
+            governance::process_cast_vote(program_id, accounts, vote)
+        }
+        ARIAInstruction::ExecuteProposal => {
+            msg!("Instruction: Execute Proposal");
+            governance::process_execute_proposal(program_id, accounts)
+        }
+        ARIAInstruction::SetDelegate(SetDelegateArgs { delegate }) => {
+            msg!("Instruction: Set Delegate");
+            governance::process_set_delegate(program_id, accounts, delegate)
+        }
+        ARIAInstruction::RevokeDelegate => {
+            msg!("Instruction: Revoke Delegate");
+            governance::process_revoke_delegate(program_id, accounts)
+        }
+        ARIAInstruction::HarvestWithheldFees => {
+            msg!("Instruction: Harvest Withheld Fees");
+            fees::process_harvest_withheld_fees(program_id, accounts)
+        }
+        ARIAInstruction::WithdrawWithheldFees => {
+            msg!("Instruction: Withdraw Withheld Fees");
+            fees::process_withdraw_withheld_fees(program_id, accounts)
+        }
+        ARIAInstruction::UpdateTransferFee(UpdateTransferFeeArgs { transfer_fee }) => {
+            msg!("Instruction: Update Transfer Fee");
+            fees::process_update_transfer_fee(program_id, accounts, transfer_fee)
+        }
+        ARIAInstruction::UpdateMetadata(UpdateMetadataArgs { field, value }) => {
+            msg!("Instruction: Update Metadata");
+            metadata::process_update_metadata(program_id, accounts, field, value)
+        }
+        ARIAInstruction::InitializeTransferHook(InitializeTransferHookArgs {
+            compliance_authority,
+        }) => {
+            msg!("Instruction: Initialize Transfer Hook");
+            transfer_hook::process_initialize_transfer_hook(
+                program_id,
+                accounts,
+                compliance_authority,
+            )
+        }
+        ARIAInstruction::SetComplianceAuthority(SetComplianceAuthorityArgs {
+            compliance_authority,
+        }) => {
+            msg!("Instruction: Set Compliance Authority");
+            transfer_hook::process_set_compliance_authority(
+                program_id,
+                accounts,
+                compliance_authority,
+            )
+        }
+        ARIAInstruction::BlockAddress(BlockAddressArgs { address }) => {
+            msg!("Instruction: Block Address");
+            transfer_hook::process_block_address(program_id, accounts, address)
+        }
+        ARIAInstruction::UnblockAddress(UnblockAddressArgs { address }) => {
+            msg!("Instruction: Unblock Address");
+            transfer_hook::process_unblock_address(program_id, accounts, address)
+        }
+        ARIAInstruction::Pause => {
+            msg!("Instruction: Pause");
+            pause::process_pause(program_id, accounts)
+        }
+        ARIAInstruction::Unpause => {
+            msg!("Instruction: Unpause");
+            pause::process_unpause(program_id, accounts)
+        }
+        ARIAInstruction::GrantRole(GrantRoleArgs { role, member }) => {
+            msg!("Instruction: Grant Role");
+            roles::process_grant_role(program_id, accounts, role, member)
+        }
+        ARIAInstruction::RevokeRole(RevokeRoleArgs { role, member }) => {
+            msg!("Instruction: Revoke Role");
+            roles::process_revoke_role(program_id, accounts, role, member)
+        }
+        ARIAInstruction::UpdateMinterAllowance(UpdateMinterAllowanceArgs { minter, change }) => {
+            msg!("Instruction: Update Minter Allowance");
+            roles::process_update_minter_allowance(program_id, accounts, minter, change)
+        }
+        ARIAInstruction::MigrateAccount(MigrateAccountArgs { account_type }) => {
+            msg!("Instruction: Migrate Account");
+            migration::process_migrate_account(program_id, accounts, account_type)
+        }
+        ARIAInstruction::GetConfig => {
+            msg!("Instruction: Get Config");
+            view::process_get_config(program_id, accounts)
+        }
+        ARIAInstruction::GetRemainingMintable(GetRemainingMintableArgs { minter }) => {
+            msg!("Instruction: Get Remaining Mintable");
+            view::process_get_remaining_mintable(program_id, accounts, minter)
+        }
+        ARIAInstruction::GetNextMintTime => {
+            msg!("The mint cooldown is per minter, use GetNextMintTimeV2");
+            Err(AriaError::InstructionDeprecated.into())
+        }
+        ARIAInstruction::GetNextMintTimeV2(GetNextMintTimeV2Args { minter }) => {
+            msg!("Instruction: Get Next Mint Time");
+            view::process_get_next_mint_time(program_id, accounts, minter)
+        }
+        ARIAInstruction::GetPendingAuthorityTransfer => {
+            msg!("Instruction: Get Pending Authority Transfer");
+            view::process_get_pending_authority_transfer(program_id, accounts)
+        }
+        ARIAInstruction::GetProgramVersion => {
+            msg!("Instruction: Get Program Version");
+            view::process_get_program_version()
         }
     }
 }
 
-pub fn process_initialize_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+pub fn process_initialize_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    transfer_fee: Option<fees::TransferFeeParams>,
+    transfer_hook_program: Option<Pubkey>,
+    metadata_uri: String,
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
     // Get required accounts
@@ -153,31 +399,66 @@ pub fn process_initialize_mint(program_id: &Pubkey, accounts: &[AccountInfo]) ->
     let mint_info = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
     let token_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_authority_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let role_registry_info = next_account_info(account_info_iter)?;
+    let program_data_info = next_account_info(account_info_iter)?;
 
     // Verify authority
-    if !authority_info.is_signer {
-        return Err(AriaError::InsufficientAuthority.into());
-    }
+    validation::check_signer(authority_info)?;
+    check_initialize_authority(program_id, authority_info, program_data_info)?;
+    validation::check_writable(mint_info)?;
+
+    // Verify token program, either SPL Token or Token-2022 owning the mint
+    token::check_mint_token_program(mint_info, token_program_info)?;
 
-    // Verify token program
-    if token_program_info.key != &spl_token::id() {
-        return Err(ProgramError::IncorrectProgramId);
+    // Verify program addresses
+    let (config_address, config_bump) = find_config_address(program_id);
+    utils::check_pda(config_info, &config_address)?;
+    let (mint_authority_address, _) = find_mint_authority_address(program_id, mint_info.key);
+    utils::check_pda(mint_authority_info, &mint_authority_address)?;
+    utils::check_system_program(system_program_info)?;
+
+    // The program config can only be created once
+    if config_info.owner == program_id {
+        let config: ProgramConfig = utils::load_state(config_info, program_id)?;
+        if config.is_initialized {
+            return Err(AriaError::AccountAlreadyInitialized.into());
+        }
     }
 
     // Get rent
-    let rent = &Rent::from_account_info(rent_info)?;
+    let rent = &validation::load_rent(rent_info)?;
     
     // Ensure mint account has enough space and rent exemption
-    if !rent.is_exempt(mint_info.lamports(), Mint::LEN) {
+    if !rent.is_exempt(mint_info.lamports(), mint_info.data_len()) {
         msg!("Mint account needs sufficient rent exemption");
         return Err(ProgramError::AccountNotRentExempt);
     }
 
-    // Create initialize mint instruction
+    // Extensions are configured before the mint itself is initialized
+    let token_2022 = token_program_info.key == &spl_token_2022::id();
+    if let Some(params) = transfer_fee {
+        fees::initialize_transfer_fee(program_id, mint_info, token_program_info, &params)?;
+    }
+    if let Some(hook_program_id) = transfer_hook_program {
+        transfer_hook::initialize_transfer_hook_extension(
+            program_id,
+            mint_info,
+            token_program_info,
+            &hook_program_id,
+        )?;
+    }
+    if token_2022 {
+        metadata::initialize_metadata_pointer(program_id, mint_info, token_program_info)?;
+    }
+
+    // Create initialize mint instruction, minting is controlled by the program's PDA
     let initialize_mint_instruction = initialize_mint(
-        &spl_token::id(),
+        token_program_info.key,
         mint_info.key,
-        authority_info.key,
+        &mint_authority_address,
         Some(authority_info.key), // Freeze authority (optional)
         TOKEN_DECIMALS,
     )?;
@@ -188,23 +469,82 @@ pub fn process_initialize_mint(program_id: &Pubkey, accounts: &[AccountInfo]) ->
         &[
             mint_info.clone(),
             rent_info.clone(),
-            authority_info.clone(),
             token_program_info.clone(),
         ],
         &[],
     )?;
 
-    // Initialize authority transfer state as empty
-    let authority_transfer_state = AuthorityTransferState {
-        proposed_authority: None,
-        proposal_time: 0,
+    // Metadata is written once the mint exists; legacy SPL mints get a Metaplex account
+    if token_2022 {
+        metadata::initialize_metadata(
+            program_id,
+            authority_info,
+            mint_info,
+            mint_authority_info,
+            token_program_info,
+            system_program_info,
+            rent,
+            metadata_uri,
+        )?;
+    } else {
+        let metadata_info = next_account_info(account_info_iter)?;
+        let metadata_program_info = next_account_info(account_info_iter)?;
+        metadata::create_metaplex_metadata(
+            program_id,
+            authority_info,
+            mint_info,
+            mint_authority_info,
+            metadata_info,
+            metadata_program_info,
+            system_program_info,
+            metadata_uri,
+        )?;
+    }
+
+    // Create the program config with an empty authority transfer state
+    utils::create_pda_account(
+        authority_info,
+        config_info,
+        system_program_info,
+        rent,
+        ProgramConfig::LEN,
+        program_id,
+        &[CONFIG_SEED, &[config_bump]],
+    )?;
+    let config = ProgramConfig {
+        is_initialized: true,
+        mint: *mint_info.key,
+        authority: *authority_info.key,
+        authority_transfer: AuthorityTransferState::default(),
+        last_mint_time: 0,
+        paused: false,
     };
+    utils::store_state(&config, config_info)?;
+
+    // The initial authority is the admin and holds every role until it hands them out
+    roles::create_role_registry(
+        program_id,
+        authority_info,
+        role_registry_info,
+        system_program_info,
+        rent,
+        authority_info.key,
+    )?;
     
+    emit(&events::MintInitialized {
+        mint: *mint_info.key,
+        authority: *authority_info.key,
+        token_program: *token_program_info.key,
+    });
     msg!("ARIA token mint initialized successfully");
     Ok(())
 }
 
-pub fn process_mint_tokens(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+pub fn process_mint_tokens(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
     // Get required accounts
@@ -213,66 +553,99 @@ pub fn process_mint_tokens(accounts: &[AccountInfo], amount: u64) -> ProgramResu
     let destination_info = next_account_info(account_info_iter)?;
     let token_program_info = next_account_info(account_info_iter)?;
     let clock_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_authority_info = next_account_info(account_info_iter)?;
+    let role_registry_info = next_account_info(account_info_iter)?;
+    let allowance_info = next_account_info(account_info_iter)?;
 
-    // Verify authority
-    if !authority_info.is_signer {
-        return Err(AriaError::InsufficientAuthority.into());
-    }
+    // Verify authority and the accounts the mint CPI writes
+    validation::check_signer(authority_info)?;
+    validation::check_writable(mint_info)?;
+    validation::check_writable(config_info)?;
+
+    // Verify token program and load the mint it owns
+    let mint = validation::MintAccount::load(mint_info, token_program_info)?;
 
-    // Verify token program
-    if token_program_info.key != &spl_token::id() {
-        return Err(ProgramError::IncorrectProgramId);
+    let mut config = roles::load_config_with_role(
+        program_id,
+        config_info,
+        role_registry_info,
+        authority_info,
+        roles::Role::Minter,
+    )?;
+    config.check_not_paused()?;
+    if config.mint != mint.key {
+        return Err(AriaError::TokenMintMismatch.into());
     }
+    let (mint_authority_address, mint_authority_bump) =
+        find_mint_authority_address(program_id, mint_info.key);
+    utils::check_pda(mint_authority_info, &mint_authority_address)?;
+    mint.check_mint_authority(&mint_authority_address)?;
     
-    // Get clock to check cooldown period
-    let clock = Clock::from_account_info(clock_info)?;
-    
-    // Get mint account information
-    let mint_data = Mint::unpack(&mint_info.data.borrow())?;
+    // Get clock for the minter's cooldown
+    let clock = validation::load_clock(clock_info)?;
     
     // Check if current supply has reached the cap
-    let current_supply = mint_data.supply;
-    if current_supply + amount > TOTAL_SUPPLY {
+    let new_supply = mint
+        .state
+        .supply
+        .checked_add(amount)
+        .ok_or(AriaError::ArithmeticOverflow)?;
+    if new_supply > TOTAL_SUPPLY {
         msg!("Mint amount would exceed total supply cap");
         return Err(AriaError::ExceedsSupplyCap.into());
     }
-    
-    // TODO: In a production version, we should store and check the last mint time
-    // This is just an example, real implementation would need custom accounts for metadata
+
+    // Each minter is limited to its own allowance and cooldown
+    roles::spend_minter_allowance(
+        program_id,
+        allowance_info,
+        authority_info.key,
+        amount,
+        clock.unix_timestamp,
+    )?;
     
     // Verify the destination account belongs to the correct mint
-    let dest_account = Account::unpack(&destination_info.data.borrow())?;
-    if dest_account.mint != *mint_info.key {
-        return Err(AriaError::TokenMintMismatch.into());
-    }
+    validation::TokenAccount::load_writable(destination_info, mint_info.key)?;
 
     // Create mint instruction
     let mint_to_instruction = mint_to(
-        &spl_token::id(),
+        token_program_info.key,
         mint_info.key,
         destination_info.key,
-        authority_info.key,
+        &mint_authority_address,
         &[],
         amount,
     )?;
 
-    // Execute mint instruction
+    // Execute mint instruction, signed by the mint authority PDA
     invoke_signed(
         &mint_to_instruction,
         &[
             mint_info.clone(),
             destination_info.clone(),
-            authority_info.clone(),
+            mint_authority_info.clone(),
             token_program_info.clone(),
         ],
-        &[],
+        &[&[MINT_AUTHORITY_SEED, mint_info.key.as_ref(), &[mint_authority_bump]]],
     )?;
 
+    // Record the time of the most recent mint by any minter
+    config.last_mint_time = clock.unix_timestamp;
+    utils::store_state(&config, config_info)?;
+
+    emit(&events::TokensMinted {
+        mint: *mint_info.key,
+        minter: *authority_info.key,
+        destination: *destination_info.key,
+        amount,
+    });
     msg!("Minted {} tokens to account {}", amount, destination_info.key);
     Ok(())
 }
 
 pub fn process_propose_authority_transfer(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     new_authority: Pubkey,
 ) -> ProgramResult {
@@ -280,14 +653,12 @@ pub fn process_propose_authority_transfer(
     
     // Get required accounts
     let current_authority_info = next_account_info(account_info_iter)?;
-    let mint_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
     let new_authority_info = next_account_info(account_info_iter)?;
     let clock_info = next_account_info(account_info_iter)?;
 
     // Verify authority
-    if !current_authority_info.is_signer {
-        return Err(AriaError::InsufficientAuthority.into());
-    }
+    validation::check_signer(current_authority_info)?;
     
     // Verify new authority account
     if new_authority_info.key != &new_authority {
@@ -295,107 +666,99 @@ pub fn process_propose_authority_transfer(
     }
     
     // Get clock
-    let clock = Clock::from_account_info(clock_info)?;
+    let clock = validation::load_clock(clock_info)?;
     
-    // Get mint account information and confirm current authority
-    let mint_data = Mint::unpack(&mint_info.data.borrow())?;
-    if mint_data.mint_authority.unwrap() != *current_authority_info.key {
-        return Err(AriaError::InsufficientAuthority.into());
-    }
+    // Get program config and confirm current authority
+    let mut config =
+        load_config_with_authority(program_id, config_info, current_authority_info)?;
     
-    // Create authority transfer state
-    let authority_transfer_state = AuthorityTransferState {
+    // Store authority transfer state, replacing any earlier proposal
+    config.authority_transfer = AuthorityTransferState {
         proposed_authority: Some(new_authority),
         proposal_time: clock.unix_timestamp,
     };
-    
-    // TODO: In an actual implementation, this state would need to be stored in an on-chain account
-    // In this example, we've simplified the implementation
+    utils::store_state(&config, config_info)?;
 
+    emit(&events::AuthorityTransferProposed {
+        authority: *current_authority_info.key,
+        proposed_authority: new_authority,
+    });
     msg!("Mint authority transfer proposed - from {} to {}", 
         current_authority_info.key, 
         new_authority_info.key);
     Ok(())
 }
 
-pub fn process_accept_authority_transfer(accounts: &[AccountInfo]) -> ProgramResult {
+pub fn process_accept_authority_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
     // Get required accounts
     let new_authority_info = next_account_info(account_info_iter)?;
-    let mint_info = next_account_info(account_info_iter)?;
-    let current_authority_info = next_account_info(account_info_iter)?;
-    let token_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
     let clock_info = next_account_info(account_info_iter)?;
 
     // Verify new authority signature
-    if !new_authority_info.is_signer {
-        return Err(AriaError::InsufficientAuthority.into());
-    }
-    
-    // Verify token program
-    if token_program_info.key != &spl_token::id() {
-        return Err(ProgramError::IncorrectProgramId);
-    }
+    validation::check_signer(new_authority_info)?;
     
     // Get clock
-    let clock = Clock::from_account_info(clock_info)?;
-    
-    // TODO: In a real implementation, get authority transfer state from an on-chain account
-    // Here we assume we have verified:
-    // 1. There is a transfer proposal for new_authority_info.key
-    // 2. The proposal has not expired
+    let clock = validation::load_clock(clock_info)?;
     
-    // Create set authority instruction
-    let set_authority_instruction = spl_token::instruction::set_authority(
-        &spl_token::id(),
-        mint_info.key,
-        Some(new_authority_info.key),
-        spl_token::instruction::AuthorityType::MintTokens,
-        current_authority_info.key,
-        &[],
-    )?;
-
-    // Execute set authority instruction
-    invoke_signed(
-        &set_authority_instruction,
-        &[
-            mint_info.clone(),
-            current_authority_info.clone(),
-            token_program_info.clone(),
-        ],
-        &[],
-    )?;
+    // Verify there is an unexpired transfer proposal for the signer
+    let mut config = load_config(program_id, config_info)?;
+    if config.authority_transfer.proposed_authority != Some(*new_authority_info.key) {
+        return Err(AriaError::AuthorityTransferNotFound.into());
+    }
+    if clock.unix_timestamp.saturating_sub(config.authority_transfer.proposal_time)
+        > AUTHORITY_TRANSFER_EXPIRY
+    {
+        return Err(AriaError::AuthorityTransferExpired.into());
+    }
 
-    // Clear authority transfer state
-    // TODO: In a real implementation, update the on-chain account
+    // Transfer authority and clear authority transfer state
+    let previous_authority = config.authority;
+    config.authority = *new_authority_info.key;
+    config.authority_transfer = AuthorityTransferState::default();
+    utils::store_state(&config, config_info)?;
     
+    emit(&events::AuthorityTransferAccepted {
+        previous_authority,
+        new_authority: *new_authority_info.key,
+    });
     msg!("Mint authority transferred from {} to {}", 
-        current_authority_info.key, 
+        previous_authority, 
         new_authority_info.key);
     Ok(())
 }
 
-pub fn process_cancel_authority_transfer(accounts: &[AccountInfo]) -> ProgramResult {
+pub fn process_cancel_authority_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     
     // Get required accounts
     let authority_info = next_account_info(account_info_iter)?;
-    let mint_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
 
     // Verify authority
-    if !authority_info.is_signer {
-        return Err(AriaError::InsufficientAuthority.into());
-    }
+    validation::check_signer(authority_info)?;
     
-    // Get mint account information and confirm current authority
-    let mint_data = Mint::unpack(&mint_info.data.borrow())?;
-    if mint_data.mint_authority.unwrap() != *authority_info.key {
-        return Err(AriaError::InsufficientAuthority.into());
+    // Get program config and confirm current authority
+    let mut config = load_config_with_authority(program_id, config_info, authority_info)?;
+    if config.authority_transfer.proposed_authority.is_none() {
+        return Err(AriaError::AuthorityTransferNotFound.into());
     }
     
-    // TODO: In a real implementation, get and clear authority transfer state from an on-chain account
+    // Clear authority transfer state
+    config.authority_transfer = AuthorityTransferState::default();
+    utils::store_state(&config, config_info)?;
     
+    emit(&events::AuthorityTransferCancelled {
+        authority: *authority_info.key,
+    });
     msg!("Mint authority transfer request cancelled");
     Ok(())
 }
@@ -403,154 +766,119 @@ pub fn process_cancel_authority_transfer(accounts: &[AccountInfo]) -> ProgramRes
 #[cfg(test)]
 mod tests {
     use super::*;
-    use solana_program::clock::Epoch;
-    use solana_program::program_pack::Pack;
-    use solana_program::pubkey::Pubkey;
-    use std::mem::size_of;
-
-    // Mock account info
-    fn create_account_info<'a>(
-        key: &'a Pubkey,
-        is_signer: bool,
-        is_writable: bool,
-        lamports: &'a mut u64,
-        data: &'a mut [u8],
-        owner: &'a Pubkey,
-    ) -> AccountInfo<'a> {
-        AccountInfo {
-            key,
-            is_signer,
-            is_writable,
-            lamports: lamports,
-            data: data.into(),
-            owner,
-            executable: false,
-            rent_epoch: Epoch::default(),
-        }
-    }
-    
+    use aria_token_interface::instruction::ProposeAuthorityTransferArgs;
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use crate::test_utils::*;
+    use solana_program::{bpf_loader, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+    use spl_token::state::Mint;
+
+    const NOW: i64 = 1_700_000_000;
+
     // Create a basic test environment
     struct TestEnv {
         program_id: Pubkey,
-        token_program_id: Pubkey,
-        authority: (Pubkey, u64, Vec<u8>, bool),
-        mint: (Pubkey, u64, Vec<u8>, bool),
-        destination: (Pubkey, u64, Vec<u8>, bool),
-        new_authority: (Pubkey, u64, Vec<u8>, bool),
-        rent: (Pubkey, u64, Vec<u8>, bool),
-        clock: (Pubkey, u64, Vec<u8>, bool),
+        authority: TestAccount,
+        mint: TestAccount,
+        destination: TestAccount,
+        new_authority: TestAccount,
+        rent: TestAccount,
+        clock: TestAccount,
+        token_program: TestAccount,
+        config: TestAccount,
+        mint_authority: TestAccount,
+        system_program: TestAccount,
+        role_registry: TestAccount,
+        minter_allowance: TestAccount,
+        program_data: TestAccount,
     }
     
     impl TestEnv {
         fn new() -> Self {
+            let program_id = Pubkey::new_unique();
+            let authority = TestAccount::wallet().signer();
+
+            // Mint controlled by the program's mint authority PDA
+            let mut mint = mint_account(None, 0);
+            let (mint_authority_key, _) = find_mint_authority_address(&program_id, &mint.key);
+            let mut mint_data = Mint::unpack(&mint.data).unwrap();
+            mint_data.mint_authority = Some(mint_authority_key).into();
+            Mint::pack(mint_data, &mut mint.data).unwrap();
+
+            // Initialize destination account as empty TOKEN account
+            let destination = token_account(&mint.key, &authority.key, 0);
+            let config = config_account(&program_id, &mint.key, &authority.key);
+            let role_registry = role_registry_account(
+                &program_id,
+                &[
+                    roles::RoleAssignment {
+                        role: roles::Role::Minter,
+                        member: authority.key,
+                    },
+                    roles::RoleAssignment {
+                        role: roles::Role::Pauser,
+                        member: authority.key,
+                    },
+                ],
+            );
+
+            let minter_allowance = minter_allowance_account(&program_id, &authority.key, 1_000_000);
+            let authority_key = authority.key;
+
             TestEnv {
-                program_id: Pubkey::new_unique(),
-                token_program_id: spl_token::id(),
-                authority: (Pubkey::new_unique(), 100000, vec![0; 32], true),
-                mint: (Pubkey::new_unique(), 1000000, vec![0; Mint::LEN], false),
-                destination: (Pubkey::new_unique(), 1000000, vec![0; Account::LEN], false),
-                new_authority: (Pubkey::new_unique(), 100000, vec![0; 32], false),
-                rent: (Pubkey::new_unique(), 100000, vec![0; 32], false),
-                clock: (Pubkey::new_unique(), 100000, vec![0; 32], false),
+                program_id,
+                authority,
+                mint,
+                destination,
+                new_authority: TestAccount::wallet(),
+                rent: rent_account(),
+                clock: clock_account(NOW),
+                token_program: token_program_account(),
+                config,
+                mint_authority: TestAccount::new(mint_authority_key, Pubkey::default(), 0),
+                system_program: system_program_account(),
+                role_registry,
+                minter_allowance,
+                program_data: program_data_account(&program_id, Some(authority_key)),
             }
         }
-        
-        // Get authority account info
-        fn authority_info(&mut self) -> AccountInfo {
-            create_account_info(
-                &self.authority.0,
-                self.authority.3,
-                true,
-                &mut self.authority.1,
-                &mut self.authority.2,
-                &self.program_id,
-            )
-        }
-        
-        // Get mint account info
-        fn mint_info(&mut self) -> AccountInfo {
-            create_account_info(
-                &self.mint.0,
-                self.mint.3,
-                true,
-                &mut self.mint.1,
-                &mut self.mint.2,
-                &self.token_program_id,
-            )
-        }
-        
-        // Get destination account info
-        fn destination_info(&mut self) -> AccountInfo {
-            // Initialize destination account as empty TOKEN account
-            let dst_account = Account {
-                mint: self.mint.0,
-                owner: self.authority.0,
-                amount: 0,
-                delegate: Option::None,
-                state: spl_token::state::AccountState::Initialized,
-                is_native: Option::None,
-                delegated_amount: 0,
-                close_authority: Option::None,
-            };
-            
-            Account::pack(dst_account, &mut self.destination.2).unwrap();
-            
-            create_account_info(
-                &self.destination.0,
-                self.destination.3,
-                true,
-                &mut self.destination.1,
-                &mut self.destination.2,
-                &self.token_program_id,
-            )
-        }
-        
-        // Get new authority account info
-        fn new_authority_info(&mut self) -> AccountInfo {
-            create_account_info(
-                &self.new_authority.0,
-                self.new_authority.3,
-                true,
-                &mut self.new_authority.1,
-                &mut self.new_authority.2,
-                &self.program_id,
-            )
+
+        fn config_state(&self) -> ProgramConfig {
+            ProgramConfig::unpack(&self.config.data).unwrap()
         }
-        
-        // Get rent account info
-        fn rent_info(&mut self) -> AccountInfo {
-            create_account_info(
-                &self.rent.0,
-                self.rent.3,
-                false,
-                &mut self.rent.1,
-                &mut self.rent.2,
-                &solana_program::sysvar::rent::id(),
-            )
+
+        fn mint_tokens(&mut self, amount: u64) -> ProgramResult {
+            let accounts = vec![
+                self.authority.info(),
+                self.mint.info(),
+                self.destination.info(),
+                self.token_program.info(),
+                self.clock.info(),
+                self.config.info(),
+                self.mint_authority.info(),
+                self.role_registry.info(),
+                self.minter_allowance.info(),
+            ];
+            process_mint_tokens(&self.program_id, &accounts, amount)
         }
-        
-        // Get clock account info
-        fn clock_info(&mut self) -> AccountInfo {
-            create_account_info(
-                &self.clock.0,
-                self.clock.3,
-                false,
-                &mut self.clock.1,
-                &mut self.clock.2,
-                &solana_program::sysvar::clock::id(),
-            )
+
+        fn propose(&mut self) -> ProgramResult {
+            let new_authority = self.new_authority.key;
+            let accounts = vec![
+                self.authority.info(),
+                self.config.info(),
+                self.new_authority.info(),
+                self.clock.info(),
+            ];
+            process_propose_authority_transfer(&self.program_id, &accounts, new_authority)
         }
-        
-        // Get token program account info
-        fn token_program_info(&mut self) -> AccountInfo {
-            create_account_info(
-                &self.token_program_id,
-                false,
-                false,
-                &mut 10000000,
-                &mut vec![],
-                &Pubkey::default(),
-            )
+
+        fn accept(&mut self) -> ProgramResult {
+            let accounts = vec![
+                self.new_authority.info(),
+                self.config.info(),
+                self.clock.info(),
+            ];
+            process_accept_authority_transfer(&self.program_id, &accounts)
         }
     }
     
@@ -559,52 +887,196 @@ mod tests {
     fn test_initialize_mint_authority() {
         let mut env = TestEnv::new();
         
+        // Non-authority account, should fail
+        env.authority.is_signer = false;
+        
         // Prepare test accounts
-        let mut accounts = vec![
-            env.authority_info(),
-            env.mint_info(),
-            env.rent_info(),
-            env.token_program_info(),
+        let accounts = vec![
+            env.authority.info(),
+            env.mint.info(),
+            env.rent.info(),
+            env.token_program.info(),
+            env.config.info(),
+            env.mint_authority.info(),
+            env.system_program.info(),
+            env.role_registry.info(),
+            env.program_data.info(),
         ];
         
-        // Non-authority account, should fail
-        accounts[0] = create_account_info(
-            &env.authority.0,
-            false, // Not a signer
-            true,
-            &mut env.authority.1,
-            &mut env.authority.2,
-            &env.program_id,
-        );
-        
-        let result = process_initialize_mint(&env.program_id, &accounts);
+        let result = process_initialize_mint(&env.program_id, &accounts, None, None, String::new());
         assert!(result.is_err());
         if let Err(e) = result {
             assert_eq!(e, AriaError::InsufficientAuthority.into());
         }
     }
+
+    // Test InitializeMint instruction - only the upgrade authority can initialize the program
+    #[cfg(not(feature = "bootstrap-authority"))]
+    #[test]
+    fn test_initialize_mint_requires_upgrade_authority() {
+        let mut env = TestEnv::new();
+        env.config.data.fill(0);
+        env.role_registry.data.fill(0);
+        env.mint.lamports = Rent::default().minimum_balance(Mint::LEN);
+        let program_id = env.program_id;
+        let authority = env.authority.key;
+
+        let mut initialize = |program_data: TestAccount| {
+            env.program_data = program_data;
+            let accounts = vec![
+                env.authority.info(),
+                env.mint.info(),
+                env.rent.info(),
+                env.token_program.info(),
+                env.config.info(),
+                env.mint_authority.info(),
+                env.system_program.info(),
+                env.role_registry.info(),
+                env.program_data.info(),
+            ];
+            process_initialize_mint(&env.program_id, &accounts, None, None, String::new())
+        };
+
+        // Another signer front-running the deployment
+        let result = initialize(program_data_account(&program_id, Some(Pubkey::new_unique())));
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+
+        // An immutable program has no upgrade authority
+        let result = initialize(program_data_account(&program_id, None));
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+
+        // The program-data account of another program
+        let result = initialize(program_data_account(&Pubkey::new_unique(), Some(authority)));
+        assert_eq!(result.unwrap_err(), AriaError::InvalidProgramAddress.into());
+    }
+
+    // Test InitializeMint instruction - config creation
+    #[test]
+    fn test_initialize_mint_creates_config() {
+        let mut env = TestEnv::new();
+        env.config.data.fill(0);
+        env.role_registry.data.fill(0);
+        env.mint.lamports = Rent::default().minimum_balance(Mint::LEN);
+        let (metadata_key, _) = metadata::find_metaplex_metadata_address(&env.mint.key);
+        let mut metadata = TestAccount::new(metadata_key, Pubkey::default(), 0).writable();
+        let mut metadata_program =
+            TestAccount::new(metadata::METAPLEX_PROGRAM_ID, bpf_loader::id(), 0);
+
+        // Legacy SPL Token mints need the Metaplex accounts
+        let mut accounts = vec![
+            env.authority.info(),
+            env.mint.info(),
+            env.rent.info(),
+            env.token_program.info(),
+            env.config.info(),
+            env.mint_authority.info(),
+            env.system_program.info(),
+            env.role_registry.info(),
+            env.program_data.info(),
+        ];
+        let result = process_initialize_mint(&env.program_id, &accounts, None, None, String::new());
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+
+        accounts.push(metadata.info());
+        accounts.push(metadata_program.info());
+        process_initialize_mint(&env.program_id, &accounts, None, None, String::new()).unwrap();
+
+        // The config can only be created once
+        let result = process_initialize_mint(&env.program_id, &accounts, None, None, String::new());
+        assert_eq!(result.unwrap_err(), AriaError::AccountAlreadyInitialized.into());
+        drop(accounts);
+
+        let config = env.config_state();
+        assert_eq!(config.mint, env.mint.key);
+        assert_eq!(config.authority, env.authority.key);
+        assert_eq!(config.authority_transfer, AuthorityTransferState::default());
+
+        // The initial authority holds every role
+        let registry = roles::RoleRegistry::unpack(&env.role_registry.data).unwrap();
+        for role in roles::Role::ALL {
+            assert!(registry.has_role(&env.authority.key, role));
+        }
+    }
     
-    // Test MintTokens instruction - success case
+    // Test InitializeMint instruction - transfer fees need a Token-2022 mint
     #[test]
-    fn test_mint_tokens_success() {
+    fn test_initialize_mint_transfer_fee_requires_token_2022() {
         let mut env = TestEnv::new();
-        
-        // Prepare test accounts
+        env.config.data.fill(0);
+        env.role_registry.data.fill(0);
+        env.mint.lamports = Rent::default().minimum_balance(Mint::LEN);
+
         let accounts = vec![
-            env.authority_info(),
-            env.mint_info(),
-            env.destination_info(),
-            env.token_program_info(),
-            env.clock_info(),
+            env.authority.info(),
+            env.mint.info(),
+            env.rent.info(),
+            env.token_program.info(),
+            env.config.info(),
+            env.mint_authority.info(),
+            env.system_program.info(),
+            env.role_registry.info(),
+            env.program_data.info(),
         ];
+        let transfer_fee = fees::TransferFeeParams {
+            transfer_fee_basis_points: 100,
+            maximum_fee: 1_000,
+        };
+        let result = process_initialize_mint(
+            &env.program_id,
+            &accounts,
+            Some(transfer_fee),
+            None,
+            String::new(),
+        );
+        assert_eq!(result.unwrap_err(), AriaError::Token2022Required.into());
+    }
+
+    // Test InitializeMint instruction - Token-2022 mints get metadata and the extensions
+    #[test]
+    fn test_initialize_token_2022_mint_with_metadata() {
+        let mut env = TestEnv::new();
+        env.config.data.fill(0);
+        env.role_registry.data.fill(0);
+        env.mint.owner = spl_token_2022::id();
+        env.mint.lamports = Rent::default().minimum_balance(Mint::LEN);
+        env.token_program.key = spl_token_2022::id();
+
+        let accounts = vec![
+            env.authority.info(),
+            env.mint.info(),
+            env.rent.info(),
+            env.token_program.info(),
+            env.config.info(),
+            env.mint_authority.info(),
+            env.system_program.info(),
+            env.role_registry.info(),
+            env.program_data.info(),
+        ];
+        let transfer_fee = fees::TransferFeeParams {
+            transfer_fee_basis_points: 100,
+            maximum_fee: 1_000,
+        };
+        process_initialize_mint(
+            &env.program_id,
+            &accounts,
+            Some(transfer_fee),
+            Some(Pubkey::new_unique()),
+            "https://aria.example/ari.json".to_string(),
+        )
+        .unwrap();
+        drop(accounts);
+
+        assert_eq!(env.config_state().mint, env.mint.key);
+    }
+
+    // Test MintTokens instruction - success case
+    #[test]
+    fn test_mint_tokens_success() {
+        let mut env = TestEnv::new();
         
-        // In this test, we assume SPL Token program is called, so we only validate input validation
-        // In a real scenario, we'd use mocks to test the actual token minting
-        let result = process_mint_tokens(&accounts, 1000);
-        
-        // This will fail since we're not actually mocking the SPL Token program
-        // But we're still testing if the function can be correctly called
-        assert!(result.is_err());
+        // The SPL Token CPI is stubbed off-chain, so this checks validation and state updates
+        env.mint_tokens(1000).unwrap();
+        assert_eq!(env.config_state().last_mint_time, NOW);
     }
     
     // Test MintTokens instruction - authority validation
@@ -612,69 +1084,201 @@ mod tests {
     fn test_mint_tokens_authority() {
         let mut env = TestEnv::new();
         
-        // Prepare test accounts
-        let mut accounts = vec![
-            env.authority_info(),
-            env.mint_info(),
-            env.destination_info(),
-            env.token_program_info(),
-            env.clock_info(),
-        ];
-        
         // Non-authority account, should fail
-        accounts[0] = create_account_info(
-            &env.authority.0,
-            false, // Not a signer
-            true,
-            &mut env.authority.1,
-            &mut env.authority.2,
-            &env.program_id,
-        );
+        env.authority.is_signer = false;
         
-        let result = process_mint_tokens(&accounts, 1000);
+        let result = env.mint_tokens(1000);
         assert!(result.is_err());
         if let Err(e) = result {
             assert_eq!(e, AriaError::InsufficientAuthority.into());
         }
+
+        // A signer without the minter role, should fail
+        env.authority = TestAccount::wallet().signer();
+        let result = env.mint_tokens(1000);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+
+        // A minter that is not the admin can mint, but not manage the program
+        env.role_registry = role_registry_account(
+            &env.program_id,
+            &[roles::RoleAssignment {
+                role: roles::Role::Minter,
+                member: env.authority.key,
+            }],
+        );
+        env.minter_allowance = minter_allowance_account(&env.program_id, &env.authority.key, 1000);
+        env.mint_tokens(1000).unwrap();
+        let result = env.propose();
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
     }
-    
-    // Test ProposeAuthorityTransfer instruction
+
+    // Test MintTokens instruction - cooldown and supply cap
     #[test]
-    fn test_propose_authority_transfer() {
+    fn test_mint_tokens_limits() {
+        let mut env = TestEnv::new();
+        env.mint_tokens(1000).unwrap();
+
+        let result = env.mint_tokens(1000);
+        assert_eq!(result.unwrap_err(), AriaError::MintCooldownActive.into());
+
+        env.clock = clock_account(NOW + MINT_COOLDOWN);
+        let result = env.mint_tokens(TOTAL_SUPPLY + 1);
+        assert_eq!(result.unwrap_err(), AriaError::ExceedsSupplyCap.into());
+        env.mint_tokens(1000).unwrap();
+    }
+
+    // Test MintTokens instruction - each minter has its own cooldown
+    #[test]
+    fn test_mint_tokens_cooldown_per_minter() {
+        let mut env = TestEnv::new();
+        env.mint_tokens(1000).unwrap();
+        let allowance = roles::MinterAllowance::unpack(&env.minter_allowance.data).unwrap();
+        assert_eq!(allowance.last_mint_time, NOW);
+
+        // A second minter is not held up by the first one's cooldown
+        let first_minter = std::mem::replace(&mut env.authority, TestAccount::wallet().signer());
+        env.role_registry = role_registry_account(
+            &env.program_id,
+            &[
+                roles::RoleAssignment {
+                    role: roles::Role::Minter,
+                    member: first_minter.key,
+                },
+                roles::RoleAssignment {
+                    role: roles::Role::Minter,
+                    member: env.authority.key,
+                },
+            ],
+        );
+        let first_allowance = std::mem::replace(
+            &mut env.minter_allowance,
+            minter_allowance_account(&env.program_id, &env.authority.key, 1000),
+        );
+        env.mint_tokens(500).unwrap();
+        let result = env.mint_tokens(500);
+        assert_eq!(result.unwrap_err(), AriaError::MintCooldownActive.into());
+
+        env.authority = first_minter;
+        env.minter_allowance = first_allowance;
+        let result = env.mint_tokens(500);
+        assert_eq!(result.unwrap_err(), AriaError::MintCooldownActive.into());
+        env.clock = clock_account(NOW + MINT_COOLDOWN);
+        env.mint_tokens(500).unwrap();
+    }
+
+    // Test MintTokens instruction - account validation
+    #[test]
+    fn test_mint_tokens_account_validation() {
+        let mut env = TestEnv::new();
+        env.destination.is_writable = false;
+        let result = env.mint_tokens(1000);
+        assert_eq!(result.unwrap_err(), AriaError::AccountNotWritable.into());
+        env.destination.is_writable = true;
+
+        env.clock = rent_account();
+        let result = env.mint_tokens(1000);
+        assert_eq!(result.unwrap_err(), AriaError::InvalidSysvar.into());
+        env.clock = clock_account(NOW);
+
+        // A supply close to u64::MAX must not wrap around the cap check
+        let mut mint_data = Mint::unpack(&env.mint.data).unwrap();
+        mint_data.supply = u64::MAX - 10;
+        Mint::pack(mint_data, &mut env.mint.data).unwrap();
+        let result = env.mint_tokens(1000);
+        assert_eq!(result.unwrap_err(), AriaError::ArithmeticOverflow.into());
+
+        // The mint must still be controlled by the program's mint authority PDA
+        mint_data.supply = 0;
+        mint_data.mint_authority = None.into();
+        Mint::pack(mint_data, &mut env.mint.data).unwrap();
+        let result = env.mint_tokens(1000);
+        assert_eq!(result.unwrap_err(), AriaError::MintAuthorityNotSet.into());
+        mint_data.mint_authority = Some(Pubkey::new_unique()).into();
+        Mint::pack(mint_data, &mut env.mint.data).unwrap();
+        let result = env.mint_tokens(1000);
+        assert_eq!(result.unwrap_err(), AriaError::MintAuthorityMismatch.into());
+    }
+
+    // Test MintTokens instruction - minter allowance
+    #[test]
+    fn test_mint_tokens_allowance() {
+        let mut env = TestEnv::new();
+        env.minter_allowance = minter_allowance_account(&env.program_id, &env.authority.key, 1500);
+        env.mint_tokens(1000).unwrap();
+        let allowance =
+            roles::MinterAllowance::unpack(&env.minter_allowance.data).unwrap();
+        assert_eq!(allowance.remaining, 500);
+
+        env.clock = clock_account(NOW + MINT_COOLDOWN);
+        let result = env.mint_tokens(501);
+        assert_eq!(result.unwrap_err(), AriaError::MinterAllowanceExceeded.into());
+        env.mint_tokens(500).unwrap();
+    }
+
+    // Test MintTokens instruction - global pause
+    #[test]
+    fn test_mint_tokens_paused() {
         let mut env = TestEnv::new();
-        
-        // Prepare test accounts
         let accounts = vec![
-            env.authority_info(),
-            env.mint_info(),
-            env.new_authority_info(),
-            env.clock_info(),
+            env.authority.info(),
+            env.config.info(),
+            env.role_registry.info(),
         ];
-        
-        // This test will fail since we didn't initialize the mint account properly
-        // But we're still testing if the function can be correctly called
-        let result = process_propose_authority_transfer(&accounts, env.new_authority.0);
-        assert!(result.is_err());
+        pause::process_pause(&env.program_id, &accounts).unwrap();
+        drop(accounts);
+
+        let result = env.mint_tokens(1000);
+        assert_eq!(result.unwrap_err(), AriaError::ProgramPaused.into());
+
+        // Authority transfers stay available while paused
+        env.propose().unwrap();
+    }
+
+    // Test ProposeAuthorityTransfer instruction
+    #[test]
+    fn test_propose_authority_transfer() {
+        let mut env = TestEnv::new();
+        env.propose().unwrap();
+
+        let config = env.config_state();
+        assert_eq!(config.authority_transfer.proposed_authority, Some(env.new_authority.key));
+        assert_eq!(config.authority_transfer.proposal_time, NOW);
+
+        // Only the current authority can propose
+        env.authority = TestAccount::wallet().signer();
+        let result = env.propose();
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
     }
     
     // Test AcceptAuthorityTransfer instruction
     #[test]
     fn test_accept_authority_transfer() {
         let mut env = TestEnv::new();
-        
-        // Prepare test accounts
-        let accounts = vec![
-            env.new_authority_info(),
-            env.mint_info(),
-            env.authority_info(),
-            env.token_program_info(),
-            env.clock_info(),
-        ];
-        
-        // This test will fail since we didn't initialize mint account and authority transfer
-        // But we're still testing if the function can be correctly called
-        let result = process_accept_authority_transfer(&accounts);
-        assert!(result.is_err());
+        env.new_authority.is_signer = true;
+
+        // Nothing has been proposed yet
+        let result = env.accept();
+        assert_eq!(result.unwrap_err(), AriaError::AuthorityTransferNotFound.into());
+
+        env.propose().unwrap();
+        env.accept().unwrap();
+
+        let config = env.config_state();
+        assert_eq!(config.authority, env.new_authority.key);
+        assert_eq!(config.authority_transfer, AuthorityTransferState::default());
+    }
+
+    // Test AcceptAuthorityTransfer instruction - expiry
+    #[test]
+    fn test_accept_authority_transfer_expired() {
+        let mut env = TestEnv::new();
+        env.new_authority.is_signer = true;
+        env.propose().unwrap();
+
+        env.clock = clock_account(NOW + AUTHORITY_TRANSFER_EXPIRY + 1);
+        let result = env.accept();
+        assert_eq!(result.unwrap_err(), AriaError::AuthorityTransferExpired.into());
+        assert_eq!(env.config_state().authority, env.authority.key);
     }
     
     // Test CancelAuthorityTransfer instruction
@@ -684,33 +1288,76 @@ mod tests {
         
         // Prepare test accounts
         let accounts = vec![
-            env.authority_info(),
-            env.mint_info(),
+            env.authority.info(),
+            env.config.info(),
         ];
         
-        // This test will fail since we didn't initialize mint account and authority transfer
-        // But we're still testing if the function can be correctly called
-        let result = process_cancel_authority_transfer(&accounts);
-        assert!(result.is_err());
+        // Nothing has been proposed yet
+        let result = process_cancel_authority_transfer(&env.program_id, &accounts);
+        assert_eq!(result.unwrap_err(), AriaError::AuthorityTransferNotFound.into());
+        drop(accounts);
+
+        env.propose().unwrap();
+        let accounts = vec![env.authority.info(), env.config.info()];
+        process_cancel_authority_transfer(&env.program_id, &accounts).unwrap();
+        drop(accounts);
+        assert_eq!(env.config_state().authority_transfer, AuthorityTransferState::default());
     }
     
+    // The original authority transfer instructions are rejected in favour of the V2 ones
+    #[test]
+    fn test_retired_authority_transfer_instructions() {
+        let mut env = TestEnv::new();
+        let retired = [
+            ARIAInstruction::ProposeAuthorityTransfer(ProposeAuthorityTransferArgs {
+                new_authority: env.new_authority.key,
+            }),
+            ARIAInstruction::AcceptAuthorityTransfer,
+            ARIAInstruction::CancelAuthorityTransfer,
+        ];
+        for instruction in retired {
+            let accounts = vec![
+                env.authority.info(),
+                env.mint.info(),
+                env.new_authority.info(),
+                env.clock.info(),
+            ];
+            let data = instruction.try_to_vec().unwrap();
+            let result = process_instruction(&env.program_id, &accounts, &data);
+            assert_eq!(result.unwrap_err(), AriaError::InstructionDeprecated.into());
+        }
+        assert_eq!(env.config_state().authority_transfer, AuthorityTransferState::default());
+    }
+
     // Test instruction parsing
     #[test]
     fn test_instruction_parsing() {
         // Test InitializeMint instruction parsing
-        let initialize_data = ARIAInstruction::InitializeMint.try_to_vec().unwrap();
+        let initialize_data = ARIAInstruction::InitializeMint(InitializeMintArgs {
+            transfer_fee: None,
+            transfer_hook_program: None,
+            metadata_uri: "https://aria.example/ari.json".to_string(),
+        })
+        .try_to_vec()
+        .unwrap();
         let parsed = ARIAInstruction::try_from_slice(&initialize_data).unwrap();
         match parsed {
-            ARIAInstruction::InitializeMint => {},
+            ARIAInstruction::InitializeMint(InitializeMintArgs {
+                transfer_fee: None,
+                transfer_hook_program: None,
+                metadata_uri,
+            }) => {
+                assert_eq!(metadata_uri, "https://aria.example/ari.json");
+            }
             _ => panic!("Incorrect parsing"),
         }
         
         // Test MintTokens instruction parsing
         let amount = 1000u64;
-        let mint_data = ARIAInstruction::MintTokens { amount }.try_to_vec().unwrap();
+        let mint_data = ARIAInstruction::MintTokens(MintTokensArgs { amount }).pack();
         let parsed = ARIAInstruction::try_from_slice(&mint_data).unwrap();
         match parsed {
-            ARIAInstruction::MintTokens { amount: parsed_amount } => {
+            ARIAInstruction::MintTokens(MintTokensArgs { amount: parsed_amount }) => {
                 assert_eq!(parsed_amount, amount);
             },
             _ => panic!("Incorrect parsing"),
@@ -718,10 +1365,13 @@ mod tests {
         
         // Test ProposeAuthorityTransfer instruction parsing
         let new_authority = Pubkey::new_unique();
-        let transfer_data = ARIAInstruction::ProposeAuthorityTransfer { new_authority }.try_to_vec().unwrap();
+        let args = ProposeAuthorityTransferArgs { new_authority };
+        let transfer_data = ARIAInstruction::ProposeAuthorityTransfer(args).pack();
         let parsed = ARIAInstruction::try_from_slice(&transfer_data).unwrap();
         match parsed {
-            ARIAInstruction::ProposeAuthorityTransfer { new_authority: parsed_authority } => {
+            ARIAInstruction::ProposeAuthorityTransfer(ProposeAuthorityTransferArgs {
+                new_authority: parsed_authority,
+            }) => {
                 assert_eq!(parsed_authority, new_authority);
             },
             _ => panic!("Incorrect parsing"),
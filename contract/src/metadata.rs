@@ -0,0 +1,512 @@
+// Token metadata
+// Token-2022 mints carry their name, symbol and URI in the metadata extension stored in the
+// mint account itself; legacy SPL Token mints get a Metaplex metadata account. The metadata
+// pointer and the update authority are the program's mint authority PDA, so the metadata can
+// only be changed through UpdateMetadata.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::rent::Rent,
+};
+use mpl_token_metadata::accounts::Metadata;
+use mpl_token_metadata::instructions::{
+    CreateMetadataAccountV3Cpi, CreateMetadataAccountV3CpiAccounts,
+    CreateMetadataAccountV3InstructionArgs, UpdateMetadataAccountV2Cpi,
+    UpdateMetadataAccountV2CpiAccounts, UpdateMetadataAccountV2InstructionArgs,
+};
+use mpl_token_metadata::types::DataV2;
+use spl_token_2022::extension::{metadata_pointer, BaseStateWithExtensions, StateWithExtensions};
+use spl_token_metadata_interface::{
+    instruction as metadata_instruction,
+    state::TokenMetadata,
+};
+
+use crate::events::{self, emit};
+use crate::token::{check_mint_token_program, check_token_2022, Mint};
+use crate::utils::{check_pda, check_system_program};
+use crate::validation::load_rent;
+use crate::{find_mint_authority_address, load_config_with_authority, AriaError};
+use crate::{MINT_AUTHORITY_SEED, TOKEN_NAME, TOKEN_SYMBOL};
+
+pub use aria_token_interface::metadata::*;
+
+// Fund the mint so that it stays rent exempt after Token-2022 reallocates it to `new_len`
+fn fund_mint_rent<'a>(
+    payer_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    rent: &Rent,
+    new_len: usize,
+) -> ProgramResult {
+    let top_up = rent
+        .minimum_balance(new_len)
+        .saturating_sub(mint_info.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer_info.key, mint_info.key, top_up),
+            &[
+                payer_info.clone(),
+                mint_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+// Point the metadata pointer extension at the mint itself.
+// Must run before the mint is initialized; the mint account must be sized for the pointer.
+pub(crate) fn initialize_metadata_pointer<'a>(
+    program_id: &Pubkey,
+    mint_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    check_token_2022(token_program_info, "Token metadata")?;
+    let (mint_authority_address, _) = find_mint_authority_address(program_id, mint_info.key);
+    let instruction = metadata_pointer::instruction::initialize(
+        token_program_info.key,
+        mint_info.key,
+        Some(mint_authority_address),
+        Some(*mint_info.key),
+    )?;
+    invoke(
+        &instruction,
+        &[mint_info.clone(), token_program_info.clone()],
+    )
+}
+
+// Write the ARIA name and symbol and `uri` into the metadata extension of an initialized mint.
+// The payer covers the rent for the larger mint account.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn initialize_metadata<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    mint_authority_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    rent: &Rent,
+    uri: String,
+) -> ProgramResult {
+    check_token_2022(token_program_info, "Token metadata")?;
+    let (mint_authority_address, mint_authority_bump) =
+        find_mint_authority_address(program_id, mint_info.key);
+    check_pda(mint_authority_info, &mint_authority_address)?;
+
+    let metadata = TokenMetadata {
+        name: TOKEN_NAME.to_string(),
+        symbol: TOKEN_SYMBOL.to_string(),
+        uri,
+        ..TokenMetadata::default()
+    };
+    let new_len = mint_info
+        .data_len()
+        .checked_add(metadata.tlv_size_of()?)
+        .ok_or(AriaError::ArithmeticOverflow)?;
+    fund_mint_rent(payer_info, mint_info, system_program_info, rent, new_len)?;
+
+    let instruction = metadata_instruction::initialize(
+        token_program_info.key,
+        mint_info.key,
+        &mint_authority_address,
+        mint_info.key,
+        &mint_authority_address,
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+    );
+    invoke_signed(
+        &instruction,
+        &[
+            mint_info.clone(),
+            mint_authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[
+            MINT_AUTHORITY_SEED,
+            mint_info.key.as_ref(),
+            &[mint_authority_bump],
+        ]],
+    )?;
+
+    msg!("Token metadata created: {} ({})", TOKEN_NAME, TOKEN_SYMBOL);
+    Ok(())
+}
+
+// Verify the Metaplex program and the metadata PDA of `mint_info`
+fn check_metaplex_accounts(
+    mint_info: &AccountInfo,
+    metadata_info: &AccountInfo,
+    metadata_program_info: &AccountInfo,
+) -> ProgramResult {
+    if metadata_program_info.key != &METAPLEX_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (metadata_address, _) = find_metaplex_metadata_address(mint_info.key);
+    check_pda(metadata_info, &metadata_address)
+}
+
+// Metaplex pads name, symbol and URI with NUL bytes to their maximum length
+fn trim_padding(value: &str) -> String {
+    value.trim_end_matches('\0').to_string()
+}
+
+// Fungible token metadata: no royalties, creators, collection or uses
+fn metaplex_data(name: String, symbol: String, uri: String) -> DataV2 {
+    DataV2 {
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    }
+}
+
+// Create the Metaplex metadata account of a legacy SPL Token mint with the ARIA name and symbol
+// and `uri`. The mint authority PDA signs as mint authority and becomes the update authority.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_metaplex_metadata<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    mint_authority_info: &AccountInfo<'a>,
+    metadata_info: &AccountInfo<'a>,
+    metadata_program_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    uri: String,
+) -> ProgramResult {
+    check_metaplex_accounts(mint_info, metadata_info, metadata_program_info)?;
+    let (mint_authority_address, mint_authority_bump) =
+        find_mint_authority_address(program_id, mint_info.key);
+    check_pda(mint_authority_info, &mint_authority_address)?;
+
+    CreateMetadataAccountV3Cpi::new(
+        metadata_program_info,
+        CreateMetadataAccountV3CpiAccounts {
+            metadata: metadata_info,
+            mint: mint_info,
+            mint_authority: mint_authority_info,
+            payer: payer_info,
+            update_authority: (mint_authority_info, true),
+            system_program: system_program_info,
+            rent: None,
+        },
+        CreateMetadataAccountV3InstructionArgs {
+            data: metaplex_data(TOKEN_NAME.to_string(), TOKEN_SYMBOL.to_string(), uri),
+            is_mutable: true,
+            collection_details: None,
+        },
+    )
+    .invoke_signed(&[&[
+        MINT_AUTHORITY_SEED,
+        mint_info.key.as_ref(),
+        &[mint_authority_bump],
+    ]])?;
+
+    msg!("Metaplex metadata created: {} ({})", TOKEN_NAME, TOKEN_SYMBOL);
+    Ok(())
+}
+
+// Read the metadata currently stored in the mint
+fn load_metadata(mint_info: &AccountInfo) -> Result<TokenMetadata, ProgramError> {
+    let data = mint_info.data.borrow();
+    let state = StateWithExtensions::<Mint>::unpack(&data)?;
+    state.get_variable_len_extension::<TokenMetadata>()
+}
+
+pub fn process_update_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    field: MetadataField,
+    value: String,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let mint_authority_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    // Only the program authority may change the metadata
+    let config = load_config_with_authority(program_id, config_info, authority_info)?;
+    config.check_not_paused()?;
+    if config.mint != *mint_info.key {
+        return Err(AriaError::TokenMintMismatch.into());
+    }
+
+    check_mint_token_program(mint_info, token_program_info)?;
+    check_system_program(system_program_info)?;
+    let (mint_authority_address, mint_authority_bump) =
+        find_mint_authority_address(program_id, mint_info.key);
+    check_pda(mint_authority_info, &mint_authority_address)?;
+    let mint_authority_seeds: &[&[u8]] = &[
+        MINT_AUTHORITY_SEED,
+        mint_info.key.as_ref(),
+        &[mint_authority_bump],
+    ];
+
+    if token_program_info.key == &spl_token_2022::id() {
+        // A longer value grows the mint; the authority pays the extra rent
+        let current = load_metadata(mint_info)?;
+        let mut updated = current.clone();
+        updated.update(field.clone().into(), value.clone());
+        let new_len = mint_info
+            .data_len()
+            .saturating_sub(current.tlv_size_of()?)
+            .checked_add(updated.tlv_size_of()?)
+            .ok_or(AriaError::ArithmeticOverflow)?;
+        let rent = &load_rent(rent_info)?;
+        fund_mint_rent(
+            authority_info,
+            mint_info,
+            system_program_info,
+            rent,
+            new_len,
+        )?;
+
+        let instruction = metadata_instruction::update_field(
+            token_program_info.key,
+            mint_info.key,
+            &mint_authority_address,
+            field.clone().into(),
+            value.clone(),
+        );
+        invoke_signed(
+            &instruction,
+            &[
+                mint_info.clone(),
+                mint_authority_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[mint_authority_seeds],
+        )?;
+    } else {
+        // Legacy SPL Token mints keep their metadata in a Metaplex account
+        let metadata_info = next_account_info(account_info_iter)?;
+        let metadata_program_info = next_account_info(account_info_iter)?;
+        check_metaplex_accounts(mint_info, metadata_info, metadata_program_info)?;
+        if metadata_info.owner != &METAPLEX_PROGRAM_ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let current =
+            Metadata::try_from(metadata_info).map_err(|_| ProgramError::InvalidAccountData)?;
+        let mut data = metaplex_data(
+            trim_padding(&current.name),
+            trim_padding(&current.symbol),
+            trim_padding(&current.uri),
+        );
+        match &field {
+            MetadataField::Name => data.name = value.clone(),
+            MetadataField::Symbol => data.symbol = value.clone(),
+            MetadataField::Uri => data.uri = value.clone(),
+            MetadataField::Key(_) => return Err(AriaError::MetadataFieldUnsupported.into()),
+        }
+
+        UpdateMetadataAccountV2Cpi::new(
+            metadata_program_info,
+            UpdateMetadataAccountV2CpiAccounts {
+                metadata: metadata_info,
+                update_authority: mint_authority_info,
+            },
+            UpdateMetadataAccountV2InstructionArgs {
+                data: Some(data),
+                new_update_authority: None,
+                primary_sale_happened: None,
+                is_mutable: None,
+            },
+        )
+        .invoke_signed(&[mint_authority_seeds])?;
+    }
+
+    emit(&events::MetadataUpdated {
+        mint: *mint_info.key,
+        field: field.clone(),
+        value,
+    });
+    msg!("Token metadata updated: {:?}", field);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use borsh::BorshSerialize;
+    use spl_token_2022::extension::{
+        metadata_pointer::MetadataPointer, ExtensionType, StateWithExtensionsMut,
+    };
+
+    fn token_2022_program_account() -> TestAccount {
+        TestAccount::new(spl_token_2022::id(), solana_program::bpf_loader::id(), 0)
+    }
+
+    // Token-2022 mint holding the ARIA metadata, as left behind by InitializeMint
+    fn token_2022_mint_with_metadata(program_id: &Pubkey) -> TestAccount {
+        let mint_key = Pubkey::new_unique();
+        let (mint_authority_key, _) = find_mint_authority_address(program_id, &mint_key);
+        let metadata = TokenMetadata {
+            update_authority: Some(mint_authority_key).try_into().unwrap(),
+            mint: mint_key,
+            name: TOKEN_NAME.to_string(),
+            symbol: TOKEN_SYMBOL.to_string(),
+            uri: "https://aria.example/ari.json".to_string(),
+            additional_metadata: vec![],
+        };
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MetadataPointer])
+                .unwrap()
+                + metadata.tlv_size_of().unwrap();
+        let mut mint = TestAccount::new(mint_key, spl_token_2022::id(), space).writable();
+        {
+            let mut state =
+                StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint.data).unwrap();
+            let pointer = state.init_extension::<MetadataPointer>(true).unwrap();
+            pointer.authority = Some(mint_authority_key).try_into().unwrap();
+            pointer.metadata_address = Some(mint_key).try_into().unwrap();
+            state.base = Mint {
+                mint_authority: Some(mint_authority_key).into(),
+                decimals: crate::TOKEN_DECIMALS,
+                is_initialized: true,
+                ..Mint::default()
+            };
+            state.pack_base();
+            state.init_account_type().unwrap();
+            state.init_variable_len_extension(&metadata, false).unwrap();
+        }
+        mint
+    }
+
+    #[test]
+    fn test_initialize_metadata_requires_token_2022() {
+        let program_id = Pubkey::new_unique();
+        let mut mint = mint_account(None, 0);
+        let mut legacy_program = token_program_account();
+        let result = initialize_metadata_pointer(&program_id, &mint.info(), &legacy_program.info());
+        assert_eq!(result.unwrap_err(), AriaError::Token2022Required.into());
+    }
+
+    #[test]
+    fn test_update_metadata() {
+        let program_id = Pubkey::new_unique();
+        let mut authority = TestAccount::wallet().signer().writable();
+        let mut mint = token_2022_mint_with_metadata(&program_id);
+        assert_eq!(load_metadata(&mint.info()).unwrap().name, TOKEN_NAME);
+        let mut config = config_account(&program_id, &mint.key, &authority.key);
+        let (mint_authority_key, _) = find_mint_authority_address(&program_id, &mint.key);
+        let mut mint_authority = TestAccount::new(mint_authority_key, Pubkey::default(), 0);
+        let mut rent = rent_account();
+        let mut token_program = token_2022_program_account();
+        let mut system_program = system_program_account();
+        let mut intruder = TestAccount::wallet().signer().writable();
+
+        let accounts = vec![
+            intruder.info(),
+            config.info(),
+            mint.info(),
+            mint_authority.info(),
+            rent.info(),
+            token_program.info(),
+            system_program.info(),
+        ];
+        let result = process_update_metadata(
+            &program_id,
+            &accounts,
+            MetadataField::Uri,
+            "https://evil.example".to_string(),
+        );
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop(accounts);
+
+        let accounts = vec![
+            authority.info(),
+            config.info(),
+            mint.info(),
+            mint_authority.info(),
+            rent.info(),
+            token_program.info(),
+            system_program.info(),
+        ];
+        process_update_metadata(
+            &program_id,
+            &accounts,
+            MetadataField::Key("website".to_string()),
+            "https://aria.example".to_string(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_update_metaplex_metadata() {
+        let program_id = Pubkey::new_unique();
+        let mut authority = TestAccount::wallet().signer().writable();
+        let mut mint = mint_account(None, 0);
+        let mut config = config_account(&program_id, &mint.key, &authority.key);
+        let (mint_authority_key, _) = find_mint_authority_address(&program_id, &mint.key);
+        let mut mint_authority = TestAccount::new(mint_authority_key, Pubkey::default(), 0);
+        let mut rent = rent_account();
+        let mut token_program = token_program_account();
+        let mut system_program = system_program_account();
+
+        // Metaplex metadata as created by InitializeMint, strings padded with NUL bytes
+        let (metadata_key, _) = find_metaplex_metadata_address(&mint.key);
+        assert_eq!(metadata_key, Metadata::find_pda(&mint.key).0);
+        let mut metadata = TestAccount::new(metadata_key, METAPLEX_PROGRAM_ID, 0).writable();
+        metadata.data = Metadata {
+            key: mpl_token_metadata::types::Key::MetadataV1,
+            update_authority: mint_authority_key,
+            mint: mint.key,
+            name: format!("{:\0<32}", TOKEN_NAME),
+            symbol: format!("{:\0<10}", TOKEN_SYMBOL),
+            uri: format!("{:\0<200}", ""),
+            seller_fee_basis_points: 0,
+            creators: None,
+            primary_sale_happened: false,
+            is_mutable: true,
+            edition_nonce: None,
+            token_standard: None,
+            collection: None,
+            uses: None,
+            collection_details: None,
+            programmable_config: None,
+        }
+        .try_to_vec()
+        .unwrap();
+        let mut metadata_program =
+            TestAccount::new(METAPLEX_PROGRAM_ID, solana_program::bpf_loader::id(), 0);
+        let mut fake_program = TestAccount::new(Pubkey::new_unique(), Pubkey::default(), 0);
+
+        let mut update = |field: MetadataField, metadata_program: &mut TestAccount| {
+            let accounts = vec![
+                authority.info(),
+                config.info(),
+                mint.info(),
+                mint_authority.info(),
+                rent.info(),
+                token_program.info(),
+                system_program.info(),
+                metadata.info(),
+                metadata_program.info(),
+            ];
+            process_update_metadata(&program_id, &accounts, field, "ARIA".to_string())
+        };
+
+        let result = update(MetadataField::Name, &mut fake_program);
+        assert_eq!(result.unwrap_err(), ProgramError::IncorrectProgramId);
+        let result = update(MetadataField::Key("website".to_string()), &mut metadata_program);
+        assert_eq!(result.unwrap_err(), AriaError::MetadataFieldUnsupported.into());
+        update(MetadataField::Name, &mut metadata_program).unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+// Account migration
+// MigrateAccount upgrades a program account with an older layout in place: the state is decoded
+// with the layout of its version, the account is reallocated to the current size and rewritten
+// with the current account header. Version 0 is the layout from before account headers, the
+// current state without a header; types whose layout changed since decode their older versions
+// themselves.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::rent::Rent,
+};
+
+use crate::checkpoint::CheckpointRecord;
+use crate::events::{self, emit};
+use crate::governance::{DelegateRecord, GovernanceConfig, Proposal, VoteRecord, VoterRecord};
+use crate::referral::{ReferralConfig, ReferralRecord, ReferrerStats};
+use crate::roles::{MinterAllowance, RoleRegistry};
+use crate::transfer_hook::Blocklist;
+use crate::treasury::{SpendRecord, TreasuryConfig};
+use crate::utils::{check_pda, check_system_program, store_state};
+use crate::validation::{check_signer, load_rent};
+use crate::{AriaError, ProgramConfig};
+
+pub use aria_token_interface::account::*;
+
+pub fn process_migrate_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    account_type: AccountType,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    check_signer(payer_info)?;
+    if account_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    check_system_program(system_program_info)?;
+    let rent = &load_rent(rent_info)?;
+
+    let accounts = (payer_info, account_info, system_program_info, rent);
+    match account_type {
+        AccountType::ProgramConfig => migrate::<ProgramConfig>(program_id, accounts),
+        AccountType::RoleRegistry => migrate::<RoleRegistry>(program_id, accounts),
+        AccountType::MinterAllowance => migrate::<MinterAllowance>(program_id, accounts),
+        AccountType::ReferralConfig => migrate::<ReferralConfig>(program_id, accounts),
+        AccountType::ReferralRecord => migrate::<ReferralRecord>(program_id, accounts),
+        AccountType::ReferrerStats => migrate::<ReferrerStats>(program_id, accounts),
+        AccountType::TreasuryConfig => migrate::<TreasuryConfig>(program_id, accounts),
+        AccountType::SpendRecord => migrate::<SpendRecord>(program_id, accounts),
+        AccountType::GovernanceConfig => migrate::<GovernanceConfig>(program_id, accounts),
+        AccountType::VoterRecord => migrate::<VoterRecord>(program_id, accounts),
+        AccountType::Proposal => migrate::<Proposal>(program_id, accounts),
+        AccountType::VoteRecord => migrate::<VoteRecord>(program_id, accounts),
+        AccountType::DelegateRecord => migrate::<DelegateRecord>(program_id, accounts),
+        AccountType::Blocklist => migrate::<Blocklist>(program_id, accounts),
+        AccountType::CheckpointRecord => migrate::<CheckpointRecord>(program_id, accounts),
+        AccountType::Uninitialized | AccountType::Unversioned => {
+            Err(AriaError::InvalidAccountType.into())
+        }
+    }
+}
+
+// Payer, migrated account, system program and rent
+type MigrationAccounts<'a, 'b> = (
+    &'b AccountInfo<'a>,
+    &'b AccountInfo<'a>,
+    &'b AccountInfo<'a>,
+    &'b Rent,
+);
+
+fn migrate<T: VersionedAccount>(program_id: &Pubkey, accounts: MigrationAccounts) -> ProgramResult {
+    let (payer_info, account_info, system_program_info, rent) = accounts;
+
+    let header = AccountHeader::unpack(&account_info.data.borrow())?;
+    let from_version = match header.account_type {
+        AccountType::Unversioned => 0,
+        account_type if account_type == T::ACCOUNT_TYPE => header.version,
+        _ => return Err(AriaError::InvalidAccountType.into()),
+    };
+    if from_version == T::VERSION {
+        msg!("Account is already at version {}", T::VERSION);
+        return Ok(());
+    }
+
+    // Decode the state with the layout of its version
+    let (state, space) = T::unpack_outdated(from_version, &account_info.data.borrow())?;
+    // The state must belong at this address, so one account type cannot be migrated as another
+    check_pda(account_info, &state.address(program_id))?;
+
+    let top_up = rent
+        .minimum_balance(space)
+        .saturating_sub(account_info.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer_info.key, account_info.key, top_up),
+            &[
+                payer_info.clone(),
+                account_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+    account_info.realloc(space, false)?;
+    account_info.data.borrow_mut().fill(0);
+    store_state(&state, account_info)?;
+
+    emit(&events::AccountMigrated {
+        account: *account_info.key,
+        account_type: T::ACCOUNT_TYPE,
+        from_version,
+        to_version: T::VERSION,
+    });
+    msg!(
+        "Migrated account {} from version {} to {}",
+        account_info.key,
+        from_version,
+        T::VERSION
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roles::MinterAllowanceV1;
+    use crate::test_utils::*;
+    use borsh::BorshSerialize;
+
+    // Minter allowance written with the layout from before account headers
+    fn unversioned_allowance(program_id: &Pubkey, minter: &Pubkey) -> TestAccount {
+        let (key, _) = crate::roles::find_minter_allowance_address(program_id, minter);
+        let state = MinterAllowanceV1 {
+            is_initialized: true,
+            minter: *minter,
+            remaining: 7,
+        };
+        let mut account = TestAccount::new(key, *program_id, MinterAllowance::LEN).writable();
+        account.data = state.try_to_vec().unwrap();
+        account
+    }
+
+    fn migrate_account(
+        program_id: &Pubkey,
+        account: &mut TestAccount,
+        account_type: AccountType,
+    ) -> ProgramResult {
+        let mut payer = TestAccount::wallet().signer();
+        let mut rent = rent_account();
+        let mut system_program = system_program_account();
+        let accounts = vec![
+            payer.info(),
+            account.info(),
+            rent.info(),
+            system_program.info(),
+        ];
+        process_migrate_account(program_id, &accounts, account_type)
+    }
+
+    #[test]
+    fn test_handlers_reject_outdated_accounts() {
+        let program_id = Pubkey::new_unique();
+        let minter = Pubkey::new_unique();
+        let mut account = unversioned_allowance(&program_id, &minter);
+        assert_eq!(
+            crate::utils::load_state::<MinterAllowance>(&account.info(), &program_id).unwrap_err(),
+            AriaError::AccountVersionMismatch.into()
+        );
+
+        // Creating the account again does not overwrite the old layout
+        let mut payer = TestAccount::wallet().signer();
+        let mut system_program = system_program_account();
+        let result = crate::utils::create_pda_account(
+            &payer.info(),
+            &account.info(),
+            &system_program.info(),
+            &Rent::default(),
+            MinterAllowance::LEN,
+            &program_id,
+            &[],
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::AccountVersionMismatch.into()
+        );
+    }
+
+    #[test]
+    fn test_migrate_account_checks() {
+        let program_id = Pubkey::new_unique();
+        let minter = Pubkey::new_unique();
+
+        // The state must live at its own address, so it cannot be migrated as another type
+        let mut account = unversioned_allowance(&program_id, &minter);
+        assert_eq!(
+            migrate_account(&program_id, &mut account, AccountType::ReferralConfig).unwrap_err(),
+            AriaError::InvalidProgramAddress.into()
+        );
+        assert_eq!(
+            migrate_account(&program_id, &mut account, AccountType::Unversioned).unwrap_err(),
+            AriaError::InvalidAccountType.into()
+        );
+        account.key = Pubkey::new_unique();
+        assert_eq!(
+            migrate_account(&program_id, &mut account, AccountType::MinterAllowance).unwrap_err(),
+            AriaError::InvalidProgramAddress.into()
+        );
+
+        // Current accounts are left alone
+        let mut account = minter_allowance_account(&program_id, &minter, 7);
+        let data = account.data.clone();
+        migrate_account(&program_id, &mut account, AccountType::MinterAllowance).unwrap();
+        assert_eq!(account.data, data);
+        assert_eq!(
+            migrate_account(&program_id, &mut account, AccountType::RoleRegistry).unwrap_err(),
+            AriaError::InvalidAccountType.into()
+        );
+
+        account.owner = Pubkey::new_unique();
+        assert_eq!(
+            migrate_account(&program_id, &mut account, AccountType::MinterAllowance).unwrap_err(),
+            ProgramError::IllegalOwner
+        );
+    }
+}
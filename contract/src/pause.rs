@@ -0,0 +1,123 @@
+// Emergency pause
+// Holders of the pauser role can stop every state-changing instruction, including
+// ExecuteProposal. While paused, only these instructions are accepted:
+// - Unpause, GrantRole and RevokeRole, so the admin can appoint a pauser to resume
+// - The authority transfer instructions, so a compromised admin key can still be replaced
+// - UpdateMinterAllowance, so the admin can cut the allowance of a compromised minter before
+//   resuming; MintTokens itself stays paused, so a raised allowance has no effect until then
+// - MigrateAccount, which only rewrites an account into the current layout
+// - The view instructions, which do not change state
+// - The transfer hook instructions (Execute, InitializeTransferHook, the extra-account-metas
+//   instructions, SetComplianceAuthority, BlockAddress and UnblockAddress). They run on the
+//   separate hook deployment, which has no program config to pause, and blocking an address is
+//   itself an incident response.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::events::{self, emit};
+use crate::roles::{load_config_with_role, Role};
+use crate::utils::store_state;
+
+pub fn process_pause(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let pauser_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let role_registry_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_with_role(
+        program_id,
+        config_info,
+        role_registry_info,
+        pauser_info,
+        Role::Pauser,
+    )?;
+    config.paused = true;
+    store_state(&config, config_info)?;
+
+    emit(&events::Paused {
+        pauser: *pauser_info.key,
+    });
+    msg!("Program paused by {}", pauser_info.key);
+    Ok(())
+}
+
+pub fn process_unpause(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let pauser_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let role_registry_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_with_role(
+        program_id,
+        config_info,
+        role_registry_info,
+        pauser_info,
+        Role::Pauser,
+    )?;
+    config.paused = false;
+    store_state(&config, config_info)?;
+
+    emit(&events::Unpaused {
+        pauser: *pauser_info.key,
+    });
+    msg!("Program unpaused by {}", pauser_info.key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roles::{process_grant_role, RoleAssignment};
+    use crate::test_utils::*;
+    use crate::{load_active_config, load_config, AriaError};
+
+    #[test]
+    fn test_pause_and_unpause() {
+        let program_id = Pubkey::new_unique();
+        let mut admin = TestAccount::wallet().signer();
+        let mut config = config_account(&program_id, &Pubkey::new_unique(), &admin.key);
+        let mut pauser = TestAccount::wallet().signer();
+        let mut registry = role_registry_account(
+            &program_id,
+            &[RoleAssignment {
+                role: Role::Pauser,
+                member: pauser.key,
+            }],
+        );
+
+        // The admin does not hold the pauser role
+        let accounts = vec![admin.info(), config.info(), registry.info()];
+        let result = process_pause(&program_id, &accounts);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop(accounts);
+
+        let accounts = vec![pauser.info(), config.info(), registry.info()];
+        process_pause(&program_id, &accounts).unwrap();
+        assert_eq!(
+            load_active_config(&program_id, &accounts[1]).unwrap_err(),
+            AriaError::ProgramPaused.into()
+        );
+        drop(accounts);
+
+        // The admin can appoint another pauser while paused
+        let mut new_pauser = TestAccount::wallet().signer();
+        let new_pauser_key = new_pauser.key;
+        let accounts = vec![admin.info(), config.info(), registry.info()];
+        process_grant_role(&program_id, &accounts, Role::Pauser, new_pauser_key).unwrap();
+        drop(accounts);
+
+        let accounts = vec![new_pauser.info(), config.info(), registry.info()];
+        process_unpause(&program_id, &accounts).unwrap();
+        assert!(!load_config(&program_id, &accounts[1]).unwrap().paused);
+        assert!(load_active_config(&program_id, &accounts[1]).is_ok());
+    }
+}
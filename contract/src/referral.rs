@@ -0,0 +1,552 @@
+// Referral registry
+// Users register a referrer once; payments routed through the program then send a
+// configurable basis-point share to that referrer.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::events::{self, emit};
+use crate::roles::{load_config_with_role, Role};
+use crate::token::transfer_checked;
+
+use crate::utils::{
+    check_pda, check_system_program, create_pda_account, load_state,
+    store_state, MAX_BASIS_POINTS,
+};
+use crate::validation::{check_signer, load_clock, load_rent, MintAccount, TokenAccount};
+use crate::{load_active_config, AriaError};
+
+pub use aria_token_interface::referral::*;
+
+pub fn process_set_referral_share(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    share_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let program_config_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let role_registry_info = next_account_info(account_info_iter)?;
+
+    if share_bps > MAX_BASIS_POINTS {
+        return Err(AriaError::InvalidBasisPoints.into());
+    }
+
+    // Only rewarders may configure referral shares
+    let program_config = load_config_with_role(
+        program_id,
+        program_config_info,
+        role_registry_info,
+        authority_info,
+        Role::Rewarder,
+    )?;
+    program_config.check_not_paused()?;
+    let mint = program_config.mint;
+
+    let (config_address, bump) = find_referral_config_address(program_id, &mint);
+    check_pda(config_info, &config_address)?;
+    check_system_program(system_program_info)?;
+
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        authority_info,
+        config_info,
+        system_program_info,
+        rent,
+        ReferralConfig::LEN,
+        program_id,
+        &[REFERRAL_CONFIG_SEED, mint.as_ref(), &[bump]],
+    )?;
+
+    let config = ReferralConfig {
+        is_initialized: true,
+        mint,
+        share_bps,
+    };
+    store_state(&config, config_info)?;
+
+    emit(&events::ReferralShareSet { mint, share_bps });
+    msg!("Referral share set to {} bps", share_bps);
+    Ok(())
+}
+
+pub fn process_register_referrer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    referrer: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let user_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let record_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let config = load_active_config(program_id, config_info)?;
+    if config.mint != *mint_info.key {
+        return Err(AriaError::TokenMintMismatch.into());
+    }
+
+    // Verify user
+    check_signer(user_info)?;
+
+    if referrer == *user_info.key {
+        return Err(AriaError::SelfReferral.into());
+    }
+
+    let (record_address, record_bump) =
+        find_referral_record_address(program_id, mint_info.key, user_info.key);
+    check_pda(record_info, &record_address)?;
+    let (stats_address, stats_bump) =
+        find_referrer_stats_address(program_id, mint_info.key, &referrer);
+    check_pda(stats_info, &stats_address)?;
+    check_system_program(system_program_info)?;
+
+    // A referrer can only be registered once
+    if record_info.owner == program_id {
+        let record: ReferralRecord = load_state(record_info, program_id)?;
+        if record.is_initialized {
+            return Err(AriaError::AccountAlreadyInitialized.into());
+        }
+    }
+
+    let clock = load_clock(clock_info)?;
+    let rent = &load_rent(rent_info)?;
+
+    create_pda_account(
+        user_info,
+        record_info,
+        system_program_info,
+        rent,
+        ReferralRecord::LEN,
+        program_id,
+        &[
+            REFERRAL_RECORD_SEED,
+            mint_info.key.as_ref(),
+            user_info.key.as_ref(),
+            &[record_bump],
+        ],
+    )?;
+    let record = ReferralRecord {
+        is_initialized: true,
+        mint: *mint_info.key,
+        user: *user_info.key,
+        referrer,
+        registered_at: clock.unix_timestamp,
+    };
+    store_state(&record, record_info)?;
+
+    // Create the referrer's stats on their first referral
+    create_pda_account(
+        user_info,
+        stats_info,
+        system_program_info,
+        rent,
+        ReferrerStats::LEN,
+        program_id,
+        &[
+            REFERRER_STATS_SEED,
+            mint_info.key.as_ref(),
+            referrer.as_ref(),
+            &[stats_bump],
+        ],
+    )?;
+    let mut stats: ReferrerStats = load_state(stats_info, program_id)?;
+    if !stats.is_initialized {
+        stats = ReferrerStats {
+            is_initialized: true,
+            mint: *mint_info.key,
+            referrer,
+            ..ReferrerStats::default()
+        };
+    }
+    stats.referral_count = stats
+        .referral_count
+        .checked_add(1)
+        .ok_or(AriaError::ArithmeticOverflow)?;
+    store_state(&stats, stats_info)?;
+
+    emit(&events::ReferrerRegistered {
+        mint: *mint_info.key,
+        user: *user_info.key,
+        referrer,
+    });
+    msg!("Registered referrer {} for {}", referrer, user_info.key);
+    Ok(())
+}
+
+pub fn process_pay_with_referral(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let payer_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+    let referrer_token_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let record_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let program_config_info = next_account_info(account_info_iter)?;
+
+    let program_config = load_active_config(program_id, program_config_info)?;
+    if program_config.mint != *mint_info.key {
+        return Err(AriaError::TokenMintMismatch.into());
+    }
+
+    // Verify payer
+    check_signer(payer_info)?;
+
+    // Verify token program and load the mint it owns
+    let mint = MintAccount::load(mint_info, token_program_info)?;
+
+    let (config_address, _) = find_referral_config_address(program_id, mint_info.key);
+    check_pda(config_info, &config_address)?;
+    let config: ReferralConfig = load_state(config_info, program_id)?;
+    if !config.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+
+    let (record_address, _) =
+        find_referral_record_address(program_id, mint_info.key, payer_info.key);
+    check_pda(record_info, &record_address)?;
+    let record: ReferralRecord = load_state(record_info, program_id)?;
+    if !record.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+
+    let (stats_address, _) =
+        find_referrer_stats_address(program_id, mint_info.key, &record.referrer);
+    check_pda(stats_info, &stats_address)?;
+    let mut stats: ReferrerStats = load_state(stats_info, program_id)?;
+
+    // The referrer's share must land in a token account they own
+    let referrer_account = TokenAccount::load_writable(referrer_token_info, mint_info.key)?;
+    if referrer_account.state.owner != record.referrer {
+        return Err(AriaError::ReferrerMismatch.into());
+    }
+
+    let share = referral_share(amount, config.share_bps)?;
+    let remainder = amount
+        .checked_sub(share)
+        .ok_or(AriaError::ArithmeticOverflow)?;
+
+    if remainder > 0 {
+        transfer_checked(
+            token_program_info,
+            source_info,
+            mint_info,
+            recipient_info,
+            payer_info,
+            account_info_iter.as_slice(),
+            remainder,
+            mint.state.decimals,
+            &[],
+        )?;
+    }
+
+    if share > 0 {
+        transfer_checked(
+            token_program_info,
+            source_info,
+            mint_info,
+            referrer_token_info,
+            payer_info,
+            account_info_iter.as_slice(),
+            share,
+            mint.state.decimals,
+            &[],
+        )?;
+
+        stats.total_rewards = stats
+            .total_rewards
+            .checked_add(share)
+            .ok_or(AriaError::ArithmeticOverflow)?;
+        store_state(&stats, stats_info)?;
+    }
+
+    emit(&events::ReferralPayment {
+        mint: *mint_info.key,
+        payer: *payer_info.key,
+        referrer: record.referrer,
+        amount,
+        referral_amount: share,
+    });
+    msg!(
+        "Paid {} tokens, {} routed to referrer {}",
+        amount,
+        share,
+        record.referrer
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use crate::roles::RoleAssignment;
+    use crate::test_utils::*;
+
+    struct ReferralEnv {
+        program_id: Pubkey,
+        user: TestAccount,
+        referrer: Pubkey,
+        mint: TestAccount,
+        record: TestAccount,
+        stats: TestAccount,
+        clock: TestAccount,
+        rent: TestAccount,
+        system_program: TestAccount,
+        program_config: TestAccount,
+    }
+
+    impl ReferralEnv {
+        fn new() -> Self {
+            let program_id = Pubkey::new_unique();
+            let user = TestAccount::wallet().signer();
+            let referrer = Pubkey::new_unique();
+            let mint = mint_account(Some(Pubkey::new_unique()), 0);
+            let (record_key, _) = find_referral_record_address(&program_id, &mint.key, &user.key);
+            let (stats_key, _) = find_referrer_stats_address(&program_id, &mint.key, &referrer);
+            let program_config = config_account(&program_id, &mint.key, &Pubkey::new_unique());
+            ReferralEnv {
+                program_id,
+                user,
+                referrer,
+                mint,
+                record: TestAccount::new(record_key, program_id, ReferralRecord::LEN).writable(),
+                stats: TestAccount::new(stats_key, program_id, ReferrerStats::LEN).writable(),
+                clock: clock_account(1_700_000_000),
+                rent: rent_account(),
+                system_program: system_program_account(),
+                program_config,
+            }
+        }
+
+        fn register(&mut self, referrer: Pubkey) -> ProgramResult {
+            let accounts = vec![
+                self.user.info(),
+                self.mint.info(),
+                self.record.info(),
+                self.stats.info(),
+                self.clock.info(),
+                self.rent.info(),
+                self.system_program.info(),
+                self.program_config.info(),
+            ];
+            process_register_referrer(&self.program_id, &accounts, referrer)
+        }
+    }
+
+    #[test]
+    fn test_referral_share() {
+        assert_eq!(referral_share(10_000, 250).unwrap(), 250);
+        assert_eq!(referral_share(399, 250).unwrap(), 9);
+        assert_eq!(referral_share(1_000, 0).unwrap(), 0);
+        assert_eq!(
+            referral_share(u64::MAX, MAX_BASIS_POINTS).unwrap(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn test_state_sizes() {
+        let config = ReferralConfig {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            share_bps: 500,
+        };
+        assert_eq!(ACCOUNT_HEADER_LEN + config.try_to_vec().unwrap().len(), ReferralConfig::LEN);
+
+        let record = ReferralRecord {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            referrer: Pubkey::new_unique(),
+            registered_at: 1,
+        };
+        assert_eq!(ACCOUNT_HEADER_LEN + record.try_to_vec().unwrap().len(), ReferralRecord::LEN);
+        assert_eq!(
+            ACCOUNT_HEADER_LEN + ReferrerStats::default().try_to_vec().unwrap().len(),
+            ReferrerStats::LEN
+        );
+    }
+
+    #[test]
+    fn test_register_referrer() {
+        let mut env = ReferralEnv::new();
+        let referrer = env.referrer;
+        env.register(referrer).unwrap();
+
+        let record = ReferralRecord::unpack(&env.record.data).unwrap();
+        assert!(record.is_initialized);
+        assert_eq!(record.user, env.user.key);
+        assert_eq!(record.referrer, referrer);
+        assert_eq!(record.registered_at, 1_700_000_000);
+
+        let stats = ReferrerStats::unpack(&env.stats.data).unwrap();
+        assert_eq!(stats.referrer, referrer);
+        assert_eq!(stats.referral_count, 1);
+
+        // The record is immutable once written
+        let result = env.register(referrer);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::AccountAlreadyInitialized.into()
+        );
+    }
+
+    #[test]
+    fn test_register_referrer_rejects_self_referral() {
+        let mut env = ReferralEnv::new();
+        let user = env.user.key;
+        let result = env.register(user);
+        assert_eq!(result.unwrap_err(), AriaError::SelfReferral.into());
+    }
+
+    #[test]
+    fn test_register_referrer_requires_signer() {
+        let mut env = ReferralEnv::new();
+        env.user.is_signer = false;
+        let referrer = env.referrer;
+        let result = env.register(referrer);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+    }
+
+    #[test]
+    fn test_register_referrer_rejects_foreign_mint() {
+        let mut env = ReferralEnv::new();
+        let other_mint = Pubkey::new_unique();
+        env.program_config = config_account(&env.program_id, &other_mint, &Pubkey::new_unique());
+        let referrer = env.referrer;
+        let result = env.register(referrer);
+        assert_eq!(result.unwrap_err(), AriaError::TokenMintMismatch.into());
+    }
+
+    #[test]
+    fn test_set_referral_share() {
+        let program_id = Pubkey::new_unique();
+        let mut rewarder = TestAccount::wallet().signer();
+        let mint = Pubkey::new_unique();
+        let mut program_config = config_account(&program_id, &mint, &Pubkey::new_unique());
+        let (config_key, _) = find_referral_config_address(&program_id, &mint);
+        let mut config = TestAccount::new(config_key, program_id, ReferralConfig::LEN).writable();
+        let mut rent = rent_account();
+        let mut system_program = system_program_account();
+        let mut role_registry = role_registry_account(
+            &program_id,
+            &[RoleAssignment {
+                role: Role::Rewarder,
+                member: rewarder.key,
+            }],
+        );
+
+        let accounts = vec![
+            rewarder.info(),
+            program_config.info(),
+            config.info(),
+            rent.info(),
+            system_program.info(),
+            role_registry.info(),
+        ];
+        let result = process_set_referral_share(&program_id, &accounts, MAX_BASIS_POINTS + 1);
+        assert_eq!(result.unwrap_err(), AriaError::InvalidBasisPoints.into());
+
+        process_set_referral_share(&program_id, &accounts, 500).unwrap();
+        drop(accounts);
+        let state = ReferralConfig::unpack(&config.data).unwrap();
+        assert_eq!(state.share_bps, 500);
+        assert_eq!(state.mint, mint);
+
+        // Anyone without the rewarder role is rejected
+        let mut other = TestAccount::wallet().signer();
+        let accounts = vec![
+            other.info(),
+            program_config.info(),
+            config.info(),
+            rent.info(),
+            system_program.info(),
+            role_registry.info(),
+        ];
+        let result = process_set_referral_share(&program_id, &accounts, 100);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+    }
+
+    #[test]
+    fn test_pay_with_referral() {
+        let mut env = ReferralEnv::new();
+        let referrer = env.referrer;
+        env.register(referrer).unwrap();
+
+        let program_id = env.program_id;
+        let (config_key, _) = find_referral_config_address(&program_id, &env.mint.key);
+        let mut config = TestAccount::new(config_key, program_id, ReferralConfig::LEN);
+        ReferralConfig {
+            is_initialized: true,
+            mint: env.mint.key,
+            share_bps: 1_000,
+        }
+        .pack_into(&mut config.data)
+        .unwrap();
+
+        let mut source = token_account(&env.mint.key, &env.user.key, 10_000);
+        let mut recipient = token_account(&env.mint.key, &Pubkey::new_unique(), 0);
+        let mut referrer_token = token_account(&env.mint.key, &referrer, 0);
+        let mut wrong_token = token_account(&env.mint.key, &Pubkey::new_unique(), 0);
+        let mut token_program = token_program_account();
+
+        let accounts = vec![
+            env.user.info(),
+            source.info(),
+            recipient.info(),
+            wrong_token.info(),
+            env.mint.info(),
+            config.info(),
+            env.record.info(),
+            env.stats.info(),
+            token_program.info(),
+            env.program_config.info(),
+        ];
+        let result = process_pay_with_referral(&program_id, &accounts, 5_000);
+        assert_eq!(result.unwrap_err(), AriaError::ReferrerMismatch.into());
+        drop(accounts);
+
+        let accounts = vec![
+            env.user.info(),
+            source.info(),
+            recipient.info(),
+            referrer_token.info(),
+            env.mint.info(),
+            config.info(),
+            env.record.info(),
+            env.stats.info(),
+            token_program.info(),
+            env.program_config.info(),
+        ];
+        process_pay_with_referral(&program_id, &accounts, 5_000).unwrap();
+        drop(accounts);
+
+        let stats = ReferrerStats::unpack(&env.stats.data).unwrap();
+        assert_eq!(stats.total_rewards, 500);
+    }
+}
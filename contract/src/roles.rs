@@ -0,0 +1,443 @@
+// Role-based access control
+// The admin, which is the program authority in the config, grants and revokes roles in the
+// role registry PDA. Minters mint through MintTokens, pausers pause and unpause the program
+// and rewarders configure referral rewards. InitializeMint grants every role to the initial
+// authority; role changes stay possible while the program is paused.
+//
+// Each minter also has its own allowance PDA. MintTokens spends from the caller's allowance,
+// so a minter can never mint more than the admin has budgeted for it, and records the mint
+// time there, so the cooldown applies to each minter separately.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::rent::Rent,
+};
+
+use crate::events::{self, emit};
+use crate::utils::{check_pda, check_system_program, create_pda_account, load_state, store_state};
+use crate::validation::{check_signer, load_rent};
+use crate::{load_config, load_config_with_authority, AriaError, ProgramConfig, MINT_COOLDOWN};
+
+pub use aria_token_interface::roles::*;
+
+// Create the role registry with every role granted to `admin`
+pub(crate) fn create_role_registry<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    registry_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    rent: &Rent,
+    admin: &Pubkey,
+) -> ProgramResult {
+    let (registry_address, bump) = find_role_registry_address(program_id);
+    check_pda(registry_info, &registry_address)?;
+    create_pda_account(
+        payer_info,
+        registry_info,
+        system_program_info,
+        rent,
+        RoleRegistry::LEN,
+        program_id,
+        &[ROLES_SEED, &[bump]],
+    )?;
+
+    let registry = RoleRegistry {
+        is_initialized: true,
+        assignments: Role::ALL
+            .iter()
+            .map(|role| RoleAssignment {
+                role: *role,
+                member: *admin,
+            })
+            .collect(),
+    };
+    store_state(&registry, registry_info)?;
+
+    for role in Role::ALL {
+        emit(&events::RoleGranted {
+            role,
+            member: *admin,
+            admin: *admin,
+        });
+        msg!("Role {:?} granted to {}", role, admin);
+    }
+    Ok(())
+}
+
+// Load the initialized role registry after checking its address
+fn load_role_registry(
+    program_id: &Pubkey,
+    registry_info: &AccountInfo,
+) -> Result<RoleRegistry, ProgramError> {
+    let (registry_address, _) = find_role_registry_address(program_id);
+    check_pda(registry_info, &registry_address)?;
+    let registry: RoleRegistry = load_state(registry_info, program_id)?;
+    if !registry.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+    Ok(registry)
+}
+
+// Load the program config and verify that `member_info` signed and holds `role`
+pub(crate) fn load_config_with_role(
+    program_id: &Pubkey,
+    config_info: &AccountInfo,
+    registry_info: &AccountInfo,
+    member_info: &AccountInfo,
+    role: Role,
+) -> Result<ProgramConfig, ProgramError> {
+    check_signer(member_info)?;
+    let config = load_config(program_id, config_info)?;
+    let registry = load_role_registry(program_id, registry_info)?;
+    if !registry.has_role(member_info.key, role) {
+        msg!("{} does not hold the {:?} role", member_info.key, role);
+        return Err(AriaError::InsufficientAuthority.into());
+    }
+    Ok(config)
+}
+
+// Deduct `amount` from the allowance of `minter` and restart its cooldown at `now`, failing
+// while the minter is on cooldown or when the allowance does not cover the amount
+pub(crate) fn spend_minter_allowance(
+    program_id: &Pubkey,
+    allowance_info: &AccountInfo,
+    minter: &Pubkey,
+    amount: u64,
+    now: i64,
+) -> ProgramResult {
+    let (allowance_address, _) = find_minter_allowance_address(program_id, minter);
+    check_pda(allowance_info, &allowance_address)?;
+    if allowance_info.owner != program_id {
+        msg!("{} has no minter allowance", minter);
+        return Err(AriaError::MinterAllowanceExceeded.into());
+    }
+    let mut allowance: MinterAllowance = load_state(allowance_info, program_id)?;
+    if let Some(next_mint_time) = next_mint_time(&allowance) {
+        if now < next_mint_time {
+            msg!("{} is on cooldown until {}", minter, next_mint_time);
+            return Err(AriaError::MintCooldownActive.into());
+        }
+    }
+    allowance.remaining = match allowance.remaining.checked_sub(amount) {
+        Some(remaining) if allowance.is_initialized => remaining,
+        _ => {
+            msg!(
+                "Mint amount exceeds the remaining allowance of {}",
+                allowance.remaining
+            );
+            return Err(AriaError::MinterAllowanceExceeded.into());
+        }
+    };
+    allowance.last_mint_time = now;
+    store_state(&allowance, allowance_info)
+}
+
+// Time from which the cooldown lets the minter mint again, None before its first mint
+pub(crate) fn next_mint_time(allowance: &MinterAllowance) -> Option<i64> {
+    (allowance.last_mint_time != 0).then(|| allowance.last_mint_time.saturating_add(MINT_COOLDOWN))
+}
+
+pub fn process_grant_role(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: Role,
+    member: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let registry_info = next_account_info(account_info_iter)?;
+
+    load_config_with_authority(program_id, config_info, admin_info)?;
+    let mut registry = load_role_registry(program_id, registry_info)?;
+
+    // Granting a role that is already held is a no-op
+    if !registry.has_role(&member, role) {
+        if registry.assignments.len() >= MAX_ROLE_ASSIGNMENTS {
+            return Err(AriaError::RoleRegistryFull.into());
+        }
+        registry.assignments.push(RoleAssignment { role, member });
+        store_state(&registry, registry_info)?;
+        emit(&events::RoleGranted {
+            role,
+            member,
+            admin: *admin_info.key,
+        });
+    }
+
+    msg!(
+        "Role {:?} granted to {} by {}",
+        role,
+        member,
+        admin_info.key
+    );
+    Ok(())
+}
+
+pub fn process_revoke_role(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: Role,
+    member: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let registry_info = next_account_info(account_info_iter)?;
+
+    load_config_with_authority(program_id, config_info, admin_info)?;
+    let mut registry = load_role_registry(program_id, registry_info)?;
+    if registry.has_role(&member, role) {
+        registry
+            .assignments
+            .retain(|assignment| !(assignment.role == role && assignment.member == member));
+        store_state(&registry, registry_info)?;
+        emit(&events::RoleRevoked {
+            role,
+            member,
+            admin: *admin_info.key,
+        });
+    }
+
+    msg!(
+        "Role {:?} revoked from {} by {}",
+        role,
+        member,
+        admin_info.key
+    );
+    Ok(())
+}
+
+pub fn process_update_minter_allowance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    minter: Pubkey,
+    change: AllowanceChange,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let allowance_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    load_config_with_authority(program_id, config_info, admin_info)?;
+    let (allowance_address, bump) = find_minter_allowance_address(program_id, &minter);
+    check_pda(allowance_info, &allowance_address)?;
+    check_system_program(system_program_info)?;
+
+    // The allowance account is created by the first update
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        admin_info,
+        allowance_info,
+        system_program_info,
+        rent,
+        MinterAllowance::LEN,
+        program_id,
+        &[MINTER_ALLOWANCE_SEED, minter.as_ref(), &[bump]],
+    )?;
+    let mut allowance: MinterAllowance = load_state(allowance_info, program_id)?;
+    allowance.remaining = match change {
+        AllowanceChange::Set(amount) => amount,
+        AllowanceChange::Increase(amount) => allowance
+            .remaining
+            .checked_add(amount)
+            .ok_or(AriaError::ArithmeticOverflow)?,
+        AllowanceChange::Decrease(amount) => allowance
+            .remaining
+            .checked_sub(amount)
+            .ok_or(AriaError::ArithmeticOverflow)?,
+    };
+    allowance.is_initialized = true;
+    allowance.minter = minter;
+    store_state(&allowance, allowance_info)?;
+
+    emit(&events::MinterAllowanceUpdated {
+        minter,
+        remaining: allowance.remaining,
+    });
+    msg!(
+        "Minter allowance of {} set to {} by {}",
+        minter,
+        allowance.remaining,
+        admin_info.key
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn test_grant_and_revoke_role() {
+        let program_id = Pubkey::new_unique();
+        let mut admin = TestAccount::wallet().signer();
+        let mut config = config_account(&program_id, &Pubkey::new_unique(), &admin.key);
+        let mut registry = role_registry_account(&program_id, &[]);
+        let mut minter = TestAccount::wallet().signer();
+        let minter_key = minter.key;
+
+        // Only the admin changes roles
+        let accounts = vec![minter.info(), config.info(), registry.info()];
+        let result = process_grant_role(&program_id, &accounts, Role::Minter, minter_key);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop(accounts);
+
+        let accounts = vec![admin.info(), config.info(), registry.info()];
+        process_grant_role(&program_id, &accounts, Role::Minter, minter_key).unwrap();
+        process_grant_role(&program_id, &accounts, Role::Minter, minter_key).unwrap();
+        drop(accounts);
+        let state = RoleRegistry::unpack(&registry.data).unwrap();
+        assert_eq!(state.assignments.len(), 1);
+
+        // Roles are checked individually
+        let (config_info, registry_info, minter_info) =
+            (config.info(), registry.info(), minter.info());
+        load_config_with_role(
+            &program_id,
+            &config_info,
+            &registry_info,
+            &minter_info,
+            Role::Minter,
+        )
+        .unwrap();
+        let result = load_config_with_role(
+            &program_id,
+            &config_info,
+            &registry_info,
+            &minter_info,
+            Role::Pauser,
+        );
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop((config_info, registry_info, minter_info));
+
+        let accounts = vec![admin.info(), config.info(), registry.info()];
+        process_revoke_role(&program_id, &accounts, Role::Minter, minter_key).unwrap();
+        drop(accounts);
+        let result = load_config_with_role(
+            &program_id,
+            &config.info(),
+            &registry.info(),
+            &minter.info(),
+            Role::Minter,
+        );
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+    }
+
+    #[test]
+    fn test_role_registry_full() {
+        let program_id = Pubkey::new_unique();
+        let mut admin = TestAccount::wallet().signer();
+        let mut config = config_account(&program_id, &Pubkey::new_unique(), &admin.key);
+        let members: Vec<Pubkey> = (0..MAX_ROLE_ASSIGNMENTS)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        let assignments: Vec<RoleAssignment> = members
+            .iter()
+            .map(|member| RoleAssignment {
+                role: Role::Rewarder,
+                member: *member,
+            })
+            .collect();
+        let mut registry = role_registry_account(&program_id, &assignments);
+
+        let accounts = vec![admin.info(), config.info(), registry.info()];
+        let result =
+            process_grant_role(&program_id, &accounts, Role::Rewarder, Pubkey::new_unique());
+        assert_eq!(result.unwrap_err(), AriaError::RoleRegistryFull.into());
+
+        // Existing assignments can still be granted again
+        process_grant_role(&program_id, &accounts, Role::Rewarder, members[0]).unwrap();
+    }
+
+    #[test]
+    fn test_minter_allowance() {
+        let program_id = Pubkey::new_unique();
+        let mut admin = TestAccount::wallet().signer();
+        let mut config = config_account(&program_id, &Pubkey::new_unique(), &admin.key);
+        let minter = Pubkey::new_unique();
+        let (allowance_key, _) = find_minter_allowance_address(&program_id, &minter);
+        let mut allowance =
+            TestAccount::new(allowance_key, program_id, MinterAllowance::LEN).writable();
+        let mut rent = rent_account();
+        let mut system_program = system_program_account();
+
+        // Nothing can be minted before the admin sets an allowance
+        let result = spend_minter_allowance(&program_id, &allowance.info(), &minter, 1, NOW);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::MinterAllowanceExceeded.into()
+        );
+
+        let accounts = vec![
+            admin.info(),
+            config.info(),
+            allowance.info(),
+            rent.info(),
+            system_program.info(),
+        ];
+        process_update_minter_allowance(&program_id, &accounts, minter, AllowanceChange::Set(100))
+            .unwrap();
+        process_update_minter_allowance(
+            &program_id,
+            &accounts,
+            minter,
+            AllowanceChange::Increase(50),
+        )
+        .unwrap();
+        process_update_minter_allowance(
+            &program_id,
+            &accounts,
+            minter,
+            AllowanceChange::Decrease(30),
+        )
+        .unwrap();
+        let result = process_update_minter_allowance(
+            &program_id,
+            &accounts,
+            minter,
+            AllowanceChange::Decrease(121),
+        );
+        assert_eq!(result.unwrap_err(), AriaError::ArithmeticOverflow.into());
+
+        spend_minter_allowance(&program_id, &accounts[2], &minter, 20, NOW).unwrap();
+
+        // The cooldown runs from this minter's last mint
+        let result = spend_minter_allowance(&program_id, &accounts[2], &minter, 1, NOW + 1);
+        assert_eq!(result.unwrap_err(), AriaError::MintCooldownActive.into());
+        let later = NOW + MINT_COOLDOWN;
+        spend_minter_allowance(&program_id, &accounts[2], &minter, 100, later).unwrap();
+        let result =
+            spend_minter_allowance(&program_id, &accounts[2], &minter, 1, later + MINT_COOLDOWN);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::MinterAllowanceExceeded.into()
+        );
+        drop(accounts);
+        let state = MinterAllowance::unpack(&allowance.data).unwrap();
+        assert_eq!(state.minter, minter);
+        assert_eq!(state.remaining, 0);
+        assert_eq!(state.last_mint_time, later);
+        assert_eq!(next_mint_time(&state), Some(later + MINT_COOLDOWN));
+
+        // Allowances belong to one minter
+        let other = Pubkey::new_unique();
+        let result = spend_minter_allowance(&program_id, &allowance.info(), &other, 0, NOW);
+        assert_eq!(result.unwrap_err(), AriaError::InvalidProgramAddress.into());
+    }
+}
@@ -0,0 +1,201 @@
+// Mock accounts for unit tests
+
+use solana_program::{
+    account_info::AccountInfo,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::{Clock, Epoch},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::{self, Sysvar},
+};
+use spl_token::state::{Account, AccountState, Mint};
+
+// State accounts are packed with their account header
+pub(crate) use crate::migration::{VersionedAccount, ACCOUNT_HEADER_LEN};
+
+// Owned backing storage for a mock account info
+#[derive(Debug)]
+pub(crate) struct TestAccount {
+    pub key: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl TestAccount {
+    pub fn new(key: Pubkey, owner: Pubkey, data_len: usize) -> Self {
+        TestAccount {
+            key,
+            lamports: 1_000_000,
+            data: vec![0; data_len],
+            owner,
+            is_signer: false,
+            is_writable: false,
+        }
+    }
+
+    // Wallet account owned by the system program
+    pub fn wallet() -> Self {
+        Self::new(Pubkey::new_unique(), solana_program::system_program::id(), 0)
+    }
+
+    pub fn signer(mut self) -> Self {
+        self.is_signer = true;
+        self.is_writable = true;
+        self
+    }
+
+    pub fn writable(mut self) -> Self {
+        self.is_writable = true;
+        self
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            self.is_writable,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            Epoch::default(),
+        )
+    }
+}
+
+// Clock sysvar account at the given unix timestamp
+pub(crate) fn clock_account(unix_timestamp: i64) -> TestAccount {
+    slot_clock_account(unix_timestamp, 0)
+}
+
+// Clock sysvar account at the given unix timestamp and slot
+pub(crate) fn slot_clock_account(unix_timestamp: i64, slot: u64) -> TestAccount {
+    let mut account = TestAccount::new(sysvar::clock::id(), sysvar::id(), Clock::size_of());
+    let clock = Clock {
+        slot,
+        unix_timestamp,
+        ..Clock::default()
+    };
+    clock.to_account_info(&mut account.info()).unwrap();
+    account
+}
+
+// Rent sysvar account with default rent parameters
+pub(crate) fn rent_account() -> TestAccount {
+    let mut account = TestAccount::new(sysvar::rent::id(), sysvar::id(), Rent::size_of());
+    Rent::default().to_account_info(&mut account.info()).unwrap();
+    account
+}
+
+// System program account
+pub(crate) fn system_program_account() -> TestAccount {
+    TestAccount::new(solana_program::system_program::id(), Pubkey::default(), 0)
+}
+
+// Program-data account of an upgradeable program
+pub(crate) fn program_data_account(
+    program_id: &Pubkey,
+    upgrade_authority_address: Option<Pubkey>,
+) -> TestAccount {
+    let mut account = TestAccount::new(
+        crate::find_program_data_address(program_id).0,
+        bpf_loader_upgradeable::id(),
+        0,
+    );
+    account.data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address,
+    })
+    .unwrap();
+    account
+}
+
+// SPL Token program account
+pub(crate) fn token_program_account() -> TestAccount {
+    TestAccount::new(spl_token::id(), solana_program::bpf_loader::id(), 0)
+}
+
+// Initialized SPL Token mint
+pub(crate) fn mint_account(mint_authority: Option<Pubkey>, supply: u64) -> TestAccount {
+    let mut account = TestAccount::new(Pubkey::new_unique(), spl_token::id(), Mint::LEN).writable();
+    let mint = Mint {
+        mint_authority: mint_authority.into(),
+        supply,
+        decimals: crate::TOKEN_DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    Mint::pack(mint, &mut account.data).unwrap();
+    account
+}
+
+// Initialized SPL Token account
+pub(crate) fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> TestAccount {
+    let mut account =
+        TestAccount::new(Pubkey::new_unique(), spl_token::id(), Account::LEN).writable();
+    let token_account = Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: AccountState::Initialized,
+        ..Account::default()
+    };
+    Account::pack(token_account, &mut account.data).unwrap();
+    account
+}
+
+// Initialized program config PDA
+pub(crate) fn config_account(program_id: &Pubkey, mint: &Pubkey, authority: &Pubkey) -> TestAccount {
+    let (key, _) = crate::find_config_address(program_id);
+    let mut account = TestAccount::new(key, *program_id, crate::ProgramConfig::LEN).writable();
+    let config = crate::ProgramConfig {
+        is_initialized: true,
+        mint: *mint,
+        authority: *authority,
+        authority_transfer: crate::AuthorityTransferState::default(),
+        last_mint_time: 0,
+        paused: false,
+    };
+    config.pack_into(&mut account.data).unwrap();
+    account
+}
+
+// Initialized role registry PDA holding `assignments`
+pub(crate) fn role_registry_account(
+    program_id: &Pubkey,
+    assignments: &[crate::roles::RoleAssignment],
+) -> TestAccount {
+    let (key, _) = crate::roles::find_role_registry_address(program_id);
+    let mut account =
+        TestAccount::new(key, *program_id, crate::roles::RoleRegistry::LEN).writable();
+    let registry = crate::roles::RoleRegistry {
+        is_initialized: true,
+        assignments: assignments.to_vec(),
+    };
+    registry.pack_into(&mut account.data).unwrap();
+    account
+}
+
+// Initialized minter allowance PDA
+pub(crate) fn minter_allowance_account(
+    program_id: &Pubkey,
+    minter: &Pubkey,
+    remaining: u64,
+) -> TestAccount {
+    let (key, _) = crate::roles::find_minter_allowance_address(program_id, minter);
+    let mut account =
+        TestAccount::new(key, *program_id, crate::roles::MinterAllowance::LEN).writable();
+    let allowance = crate::roles::MinterAllowance {
+        is_initialized: true,
+        minter: *minter,
+        remaining,
+        last_mint_time: 0,
+    };
+    allowance.pack_into(&mut account.data).unwrap();
+    account
+}
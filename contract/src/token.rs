@@ -0,0 +1,186 @@
+// Token program helpers
+// ARIA accepts mints owned by either SPL Token or Token-2022. State is parsed with the
+// extension-aware Token-2022 unpacking, which also reads legacy accounts, and CPIs are sent
+// to the program that owns the mint.
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token_2022::{extension::StateWithExtensions, onchain::invoke_transfer_checked};
+
+use crate::AriaError;
+
+pub(crate) use spl_token_2022::state::{Account, Mint};
+
+// Whether `program_id` is SPL Token or Token-2022
+pub(crate) fn is_token_program(program_id: &Pubkey) -> bool {
+    program_id == &spl_token::id() || program_id == &spl_token_2022::id()
+}
+
+// Verify that `token_program_info` is a supported token program
+pub(crate) fn check_token_program(token_program_info: &AccountInfo) -> ProgramResult {
+    if !is_token_program(token_program_info.key) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+// Verify that `token_program_info` is the token program owning the mint
+pub(crate) fn check_mint_token_program(
+    mint_info: &AccountInfo,
+    token_program_info: &AccountInfo,
+) -> ProgramResult {
+    check_token_program(token_program_info)?;
+    if mint_info.owner != token_program_info.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+// Verify that `token_program_info` is Token-2022, for features that only exist as extensions
+pub(crate) fn check_token_2022(token_program_info: &AccountInfo, feature: &str) -> ProgramResult {
+    if token_program_info.key != &spl_token_2022::id() {
+        msg!("{}: Token-2022 mint required", feature);
+        return Err(AriaError::Token2022Required.into());
+    }
+    Ok(())
+}
+
+// Unpack a mint owned by either token program, ignoring any extensions
+pub(crate) fn unpack_mint(mint_info: &AccountInfo) -> Result<Mint, ProgramError> {
+    if !is_token_program(mint_info.owner) {
+        return Err(AriaError::InvalidTokenAccountOwner.into());
+    }
+    let data = mint_info.data.borrow();
+    Ok(StateWithExtensions::<Mint>::unpack(&data)?.base)
+}
+
+// Unpack a token account owned by either token program, ignoring any extensions
+pub(crate) fn unpack_token_account(account_info: &AccountInfo) -> Result<Account, ProgramError> {
+    if !is_token_program(account_info.owner) {
+        return Err(AriaError::InvalidTokenAccountOwner.into());
+    }
+    let data = account_info.data.borrow();
+    Ok(StateWithExtensions::<Account>::unpack(&data)?.base)
+}
+
+// Transfer `amount` with a decimals check through the mint's token program.
+// `signer_seeds` is empty when `authority_info` signed the transaction. For mints with a
+// transfer hook, `hook_accounts` must hold the hook program, its extra-account-metas PDA and
+// the extra accounts it lists.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_checked<'a>(
+    token_program_info: &AccountInfo<'a>,
+    source_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    authority_info: &AccountInfo<'a>,
+    hook_accounts: &[AccountInfo<'a>],
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    check_mint_token_program(mint_info, token_program_info)?;
+    invoke_transfer_checked(
+        token_program_info.key,
+        source_info.clone(),
+        mint_info.clone(),
+        destination_info.clone(),
+        authority_info.clone(),
+        hook_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use solana_program::program_pack::Pack;
+    use spl_token_2022::{
+        extension::{
+            mint_close_authority::MintCloseAuthority, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensionsMut,
+        },
+        state::AccountState,
+    };
+
+    #[test]
+    fn test_unpack_legacy_state() {
+        let owner = Pubkey::new_unique();
+        let mut mint = mint_account(None, 42);
+        let mut account = token_account(&mint.key, &owner, 7);
+
+        assert_eq!(unpack_mint(&mint.info()).unwrap().supply, 42);
+        let state = unpack_token_account(&account.info()).unwrap();
+        assert_eq!(state.owner, owner);
+        assert_eq!(state.amount, 7);
+    }
+
+    #[test]
+    fn test_unpack_token_2022_state_with_extensions() {
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MintCloseAuthority])
+                .unwrap();
+        let mut mint = TestAccount::new(Pubkey::new_unique(), spl_token_2022::id(), space);
+        {
+            let mut state =
+                StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint.data).unwrap();
+            state.init_extension::<MintCloseAuthority>(true).unwrap();
+            state.base = Mint {
+                supply: 1_000,
+                decimals: crate::TOKEN_DECIMALS,
+                is_initialized: true,
+                ..Mint::default()
+            };
+            state.pack_base();
+            state.init_account_type().unwrap();
+            assert!(state.get_extension::<MintCloseAuthority>().is_ok());
+        }
+        assert_eq!(unpack_mint(&mint.info()).unwrap().supply, 1_000);
+
+        let mut account =
+            TestAccount::new(Pubkey::new_unique(), spl_token_2022::id(), Account::LEN);
+        Account::pack(
+            Account {
+                mint: mint.key,
+                state: AccountState::Initialized,
+                amount: 5,
+                ..Account::default()
+            },
+            &mut account.data,
+        )
+        .unwrap();
+        assert_eq!(unpack_token_account(&account.info()).unwrap().amount, 5);
+    }
+
+    #[test]
+    fn test_token_program_checks() {
+        let mut legacy_program = token_program_account();
+        let mut token_2022_program =
+            TestAccount::new(spl_token_2022::id(), solana_program::bpf_loader::id(), 0);
+        let mut mint = mint_account(None, 0);
+        let mut other = TestAccount::wallet();
+
+        assert!(check_token_program(&legacy_program.info()).is_ok());
+        assert!(check_token_program(&token_2022_program.info()).is_ok());
+        assert_eq!(
+            check_token_program(&other.info()).unwrap_err(),
+            ProgramError::IncorrectProgramId
+        );
+
+        // CPIs must go to the program that owns the mint
+        assert!(check_mint_token_program(&mint.info(), &legacy_program.info()).is_ok());
+        assert_eq!(
+            check_mint_token_program(&mint.info(), &token_2022_program.info()).unwrap_err(),
+            ProgramError::IncorrectProgramId
+        );
+        assert_eq!(
+            unpack_mint(&other.info()).unwrap_err(),
+            AriaError::InvalidTokenAccountOwner.into()
+        );
+    }
+}
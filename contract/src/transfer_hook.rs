@@ -0,0 +1,856 @@
+// Token-2022 transfer hook
+// The program implements the transfer hook interface: Token-2022 calls Execute on every
+// transfer of a mint whose transfer-hook extension names this program, and the transfer is
+// rejected when the source or destination owner is on the mint's blocklist. The blocklist PDA
+// is listed in the extra-account-metas PDA, so wallets resolve it automatically.
+//
+// The extra-account-metas PDA is created by the interface's InitializeExtraAccountMetaList, or
+// by InitializeTransferHook together with the blocklist. The interface instructions must be
+// signed by the mint authority or, since ARIA mints hand their mint authority to a PDA, by the
+// freeze authority. The blocklist must stay the first extra account.
+//
+// Token-2022 cannot call back into a program that is already on the call stack, so the hook
+// must be a separate deployment of this crate; otherwise transfers made by the program itself
+// (treasury, referral and governance) would fail.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_token_2022::extension::{
+    transfer_hook::{self, TransferHookAccount},
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use spl_transfer_hook_interface::{
+    collect_extra_account_metas_signer_seeds,
+    instruction::{ExecuteInstruction, TransferHookInstruction},
+};
+
+use crate::events::{self, emit};
+use crate::token::{check_token_2022, Account, Mint};
+use crate::utils::{
+    allocate_pda_account, check_pda, check_system_program, create_pda_account, load_state,
+    store_state,
+};
+use crate::validation::{check_signer, load_rent};
+use crate::{find_mint_authority_address, AriaError};
+
+pub use aria_token_interface::transfer_hook::*;
+
+// Transfer hook interface instruction, None for any other instruction data
+pub(crate) fn unpack_interface(instruction_data: &[u8]) -> Option<TransferHookInstruction> {
+    TransferHookInstruction::unpack(instruction_data).ok()
+}
+
+// Enable the transfer-hook extension on an uninitialized mint, naming `hook_program_id`.
+// Must run before the mint itself is initialized.
+pub(crate) fn initialize_transfer_hook_extension<'a>(
+    program_id: &Pubkey,
+    mint_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    hook_program_id: &Pubkey,
+) -> ProgramResult {
+    check_token_2022(token_program_info, "Transfer hooks")?;
+    let (mint_authority_address, _) = find_mint_authority_address(program_id, mint_info.key);
+    let instruction = transfer_hook::instruction::initialize(
+        token_program_info.key,
+        mint_info.key,
+        Some(mint_authority_address),
+        Some(*hook_program_id),
+    )?;
+    invoke(
+        &instruction,
+        &[mint_info.clone(), token_program_info.clone()],
+    )?;
+
+    msg!("Transfer hook enabled: {}", hook_program_id);
+    Ok(())
+}
+
+// Load the initialized blocklist of `mint` after checking its address
+fn load_blocklist(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    blocklist_info: &AccountInfo,
+) -> Result<Blocklist, ProgramError> {
+    let (blocklist_address, _) = find_blocklist_address(program_id, mint);
+    check_pda(blocklist_info, &blocklist_address)?;
+    let blocklist: Blocklist = load_state(blocklist_info, program_id)?;
+    if !blocklist.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+    Ok(blocklist)
+}
+
+// Load the blocklist and verify that `authority_info` is its signing compliance authority
+fn load_blocklist_with_authority(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    blocklist_info: &AccountInfo,
+    authority_info: &AccountInfo,
+) -> Result<Blocklist, ProgramError> {
+    check_signer(authority_info)?;
+    let blocklist = load_blocklist(program_id, mint, blocklist_info)?;
+    if blocklist.compliance_authority != *authority_info.key {
+        return Err(AriaError::InsufficientAuthority.into());
+    }
+    Ok(blocklist)
+}
+
+// Load a Token-2022 mint that uses this deployment as its transfer hook
+fn load_hook_mint(program_id: &Pubkey, mint_info: &AccountInfo) -> Result<Mint, ProgramError> {
+    if mint_info.owner != &spl_token_2022::id() {
+        return Err(AriaError::Token2022Required.into());
+    }
+    let data = mint_info.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    if transfer_hook::get_program_id(&mint) != Some(*program_id) {
+        msg!("Mint does not use this program as its transfer hook");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(mint.base)
+}
+
+// Verify the signer of an interface instruction: the mint authority or the freeze authority
+fn check_hook_authority(mint: &Mint, authority_info: &AccountInfo) -> ProgramResult {
+    check_signer(authority_info)?;
+    let authority = Some(*authority_info.key).into();
+    if mint.mint_authority != authority && mint.freeze_authority != authority {
+        return Err(AriaError::InsufficientAuthority.into());
+    }
+    Ok(())
+}
+
+// Execute reads the blocklist from the first extra account
+fn check_extra_account_metas(metas: &[ExtraAccountMeta]) -> ProgramResult {
+    if metas.first() != extra_account_metas()?.first() {
+        return Err(AriaError::InvalidExtraAccountMetas.into());
+    }
+    Ok(())
+}
+
+// Verify that Token-2022 marked the account as being in the middle of a transfer
+fn check_transferring(account_info: &AccountInfo) -> Result<Account, ProgramError> {
+    if account_info.owner != &spl_token_2022::id() {
+        return Err(AriaError::InvalidTokenAccountOwner.into());
+    }
+    let data = account_info.data.borrow();
+    let account = StateWithExtensions::<Account>::unpack(&data)?;
+    let extension = account.get_extension::<TransferHookAccount>()?;
+    if !bool::from(extension.transferring) {
+        msg!("Execute can only be called by Token-2022 during a transfer");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(account.base)
+}
+
+pub fn process_initialize_transfer_hook(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    compliance_authority: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let extra_metas_info = next_account_info(account_info_iter)?;
+    let blocklist_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    check_signer(authority_info)?;
+    check_system_program(system_program_info)?;
+
+    // The mint must use this deployment as its hook, and its freeze authority must sign
+    let mint = load_hook_mint(program_id, mint_info)?;
+    if mint.freeze_authority != Some(*authority_info.key).into() {
+        return Err(AriaError::InsufficientAuthority.into());
+    }
+
+    let (blocklist_address, blocklist_bump) = find_blocklist_address(program_id, mint_info.key);
+    check_pda(blocklist_info, &blocklist_address)?;
+    let (extra_metas_address, extra_metas_bump) =
+        find_extra_account_metas_address(program_id, mint_info.key);
+    check_pda(extra_metas_info, &extra_metas_address)?;
+
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        authority_info,
+        blocklist_info,
+        system_program_info,
+        rent,
+        Blocklist::LEN,
+        program_id,
+        &[BLOCKLIST_SEED, mint_info.key.as_ref(), &[blocklist_bump]],
+    )?;
+    let blocklist: Blocklist = load_state(blocklist_info, program_id)?;
+    if blocklist.is_initialized {
+        return Err(AriaError::AccountAlreadyInitialized.into());
+    }
+    let blocklist = Blocklist {
+        is_initialized: true,
+        mint: *mint_info.key,
+        compliance_authority,
+        blocked: Vec::new(),
+    };
+    store_state(&blocklist, blocklist_info)?;
+
+    // Left as is when InitializeExtraAccountMetaList already created it
+    if extra_metas_info.owner != program_id {
+        let metas = extra_account_metas()?;
+        let bump_seed = [extra_metas_bump];
+        create_pda_account(
+            authority_info,
+            extra_metas_info,
+            system_program_info,
+            rent,
+            ExtraAccountMetaList::size_of(metas.len())?,
+            program_id,
+            &collect_extra_account_metas_signer_seeds(mint_info.key, &bump_seed),
+        )?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut extra_metas_info.data.borrow_mut(),
+            &metas,
+        )?;
+    }
+
+    emit(&events::TransferHookInitialized {
+        mint: *mint_info.key,
+        compliance_authority,
+    });
+    msg!(
+        "Transfer hook initialized for mint {}, compliance authority {}",
+        mint_info.key,
+        compliance_authority
+    );
+    Ok(())
+}
+
+// Interface InitializeExtraAccountMetaList. The PDA must be funded by the caller beforehand, as
+// the interface passes no payer.
+pub fn process_initialize_extra_account_meta_list(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    metas: &[ExtraAccountMeta],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let extra_metas_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    check_system_program(system_program_info)?;
+    let mint = load_hook_mint(program_id, mint_info)?;
+    check_hook_authority(&mint, authority_info)?;
+    check_extra_account_metas(metas)?;
+
+    let (extra_metas_address, extra_metas_bump) =
+        find_extra_account_metas_address(program_id, mint_info.key);
+    check_pda(extra_metas_info, &extra_metas_address)?;
+    if extra_metas_info.owner == program_id {
+        return Err(AriaError::AccountAlreadyInitialized.into());
+    }
+
+    let bump_seed = [extra_metas_bump];
+    allocate_pda_account(
+        extra_metas_info,
+        system_program_info,
+        ExtraAccountMetaList::size_of(metas.len())?,
+        program_id,
+        &collect_extra_account_metas_signer_seeds(mint_info.key, &bump_seed),
+    )?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut extra_metas_info.data.borrow_mut(),
+        metas,
+    )?;
+
+    emit(&events::ExtraAccountMetasInitialized {
+        mint: *mint_info.key,
+        meta_count: metas.len() as u32,
+    });
+    msg!("Extra account metas initialized for mint {}", mint_info.key);
+    Ok(())
+}
+
+// Interface UpdateExtraAccountMetaList. A longer list needs the PDA funded for its new size.
+pub fn process_update_extra_account_meta_list(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    metas: &[ExtraAccountMeta],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let extra_metas_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+
+    let mint = load_hook_mint(program_id, mint_info)?;
+    check_hook_authority(&mint, authority_info)?;
+    check_extra_account_metas(metas)?;
+
+    let (extra_metas_address, _) = find_extra_account_metas_address(program_id, mint_info.key);
+    check_pda(extra_metas_info, &extra_metas_address)?;
+    if extra_metas_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let space = ExtraAccountMetaList::size_of(metas.len())?;
+    if space > extra_metas_info.data_len() {
+        extra_metas_info.realloc(space, false)?;
+    }
+    ExtraAccountMetaList::update::<ExecuteInstruction>(
+        &mut extra_metas_info.data.borrow_mut(),
+        metas,
+    )?;
+
+    emit(&events::ExtraAccountMetasUpdated {
+        mint: *mint_info.key,
+        meta_count: metas.len() as u32,
+    });
+    msg!("Extra account metas updated for mint {}", mint_info.key);
+    Ok(())
+}
+
+pub fn process_set_compliance_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    compliance_authority: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let blocklist_info = next_account_info(account_info_iter)?;
+
+    let mut blocklist =
+        load_blocklist_with_authority(program_id, mint_info.key, blocklist_info, authority_info)?;
+    blocklist.compliance_authority = compliance_authority;
+    store_state(&blocklist, blocklist_info)?;
+
+    emit(&events::ComplianceAuthoritySet {
+        mint: *mint_info.key,
+        compliance_authority,
+    });
+    msg!("Compliance authority set to {}", compliance_authority);
+    Ok(())
+}
+
+pub fn process_block_address(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    address: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let blocklist_info = next_account_info(account_info_iter)?;
+
+    let mut blocklist =
+        load_blocklist_with_authority(program_id, mint_info.key, blocklist_info, authority_info)?;
+    if blocklist.is_blocked(&address) {
+        msg!("{} is already blocked", address);
+        return Ok(());
+    }
+    if blocklist.blocked.len() >= MAX_BLOCKED {
+        return Err(AriaError::BlocklistFull.into());
+    }
+    blocklist.blocked.push(address);
+    store_state(&blocklist, blocklist_info)?;
+
+    emit(&events::AddressBlocked {
+        mint: *mint_info.key,
+        address,
+    });
+    msg!("Blocked {}", address);
+    Ok(())
+}
+
+pub fn process_unblock_address(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    address: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let blocklist_info = next_account_info(account_info_iter)?;
+
+    let mut blocklist =
+        load_blocklist_with_authority(program_id, mint_info.key, blocklist_info, authority_info)?;
+    if blocklist.is_blocked(&address) {
+        blocklist.blocked.retain(|blocked| blocked != &address);
+        store_state(&blocklist, blocklist_info)?;
+        emit(&events::AddressUnblocked {
+            mint: *mint_info.key,
+            address,
+        });
+    }
+
+    msg!("Unblocked {}", address);
+    Ok(())
+}
+
+pub fn process_execute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let source_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let _authority_info = next_account_info(account_info_iter)?;
+    let extra_metas_info = next_account_info(account_info_iter)?;
+    let blocklist_info = next_account_info(account_info_iter)?;
+
+    // The extra accounts must be the ones listed in the validation account
+    let (extra_metas_address, _) = find_extra_account_metas_address(program_id, mint_info.key);
+    check_pda(extra_metas_info, &extra_metas_address)?;
+    if extra_metas_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    ExtraAccountMetaList::check_account_infos::<ExecuteInstruction>(
+        accounts,
+        &TransferHookInstruction::Execute { amount }.pack(),
+        program_id,
+        &extra_metas_info.data.borrow(),
+    )?;
+
+    let source = check_transferring(source_info)?;
+    let destination = check_transferring(destination_info)?;
+
+    let blocklist = load_blocklist(program_id, mint_info.key, blocklist_info)?;
+    for owner in [&source.owner, &destination.owner] {
+        if blocklist.is_blocked(owner) {
+            msg!("Transfer rejected: {} is blocked", owner);
+            return Err(AriaError::AddressBlocked.into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use crate::test_utils::*;
+    use spl_token_2022::{
+        extension::{transfer_hook::TransferHook, ExtensionType, StateWithExtensionsMut},
+        state::AccountState,
+    };
+    use spl_type_length_value::state::TlvStateBorrowed;
+
+    struct HookEnv {
+        program_id: Pubkey,
+        compliance: TestAccount,
+        mint: TestAccount,
+        extra_metas: TestAccount,
+        blocklist: TestAccount,
+    }
+
+    impl HookEnv {
+        fn new() -> Self {
+            let program_id = Pubkey::new_unique();
+            let compliance = TestAccount::wallet().signer();
+            let mint_key = Pubkey::new_unique();
+
+            let (extra_metas_key, _) = find_extra_account_metas_address(&program_id, &mint_key);
+            let metas = extra_account_metas().unwrap();
+            let mut extra_metas = TestAccount::new(
+                extra_metas_key,
+                program_id,
+                ExtraAccountMetaList::size_of(metas.len()).unwrap(),
+            );
+            ExtraAccountMetaList::init::<ExecuteInstruction>(&mut extra_metas.data, &metas)
+                .unwrap();
+
+            let (blocklist_key, _) = find_blocklist_address(&program_id, &mint_key);
+            // Read-only, as Token-2022 passes it to Execute
+            let mut blocklist = TestAccount::new(blocklist_key, program_id, Blocklist::LEN);
+            store_test_state(
+                &Blocklist {
+                    is_initialized: true,
+                    mint: mint_key,
+                    compliance_authority: compliance.key,
+                    blocked: Vec::new(),
+                },
+                &mut blocklist,
+            );
+
+            HookEnv {
+                program_id,
+                compliance,
+                mint: hook_mint(mint_key, &program_id),
+                extra_metas,
+                blocklist,
+            }
+        }
+
+        fn blocklist_state(&self) -> Blocklist {
+            Blocklist::unpack(&self.blocklist.data).unwrap()
+        }
+
+        // Blocking writes the blocklist, which Execute receives read-only
+        fn block(&mut self, address: Pubkey) -> ProgramResult {
+            self.blocklist.is_writable = true;
+            let accounts = vec![
+                self.compliance.info(),
+                self.mint.info(),
+                self.blocklist.info(),
+            ];
+            let result = process_block_address(&self.program_id, &accounts, address);
+            drop(accounts);
+            self.blocklist.is_writable = false;
+            result
+        }
+
+        fn execute(
+            &mut self,
+            source: &mut TestAccount,
+            destination: &mut TestAccount,
+        ) -> ProgramResult {
+            let mut owner = TestAccount::wallet();
+            let accounts = vec![
+                source.info(),
+                self.mint.info(),
+                destination.info(),
+                owner.info(),
+                self.extra_metas.info(),
+                self.blocklist.info(),
+            ];
+            process_execute(&self.program_id, &accounts, 10)
+        }
+    }
+
+    fn stored_metas(extra_metas: &TestAccount) -> Vec<ExtraAccountMeta> {
+        let state = TlvStateBorrowed::unpack(&extra_metas.data).unwrap();
+        ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&state)
+            .unwrap()
+            .data()
+            .to_vec()
+    }
+
+    fn store_test_state(blocklist: &Blocklist, account: &mut TestAccount) {
+        blocklist.pack_into(&mut account.data).unwrap();
+    }
+
+    // Token-2022 mint naming `program_id` as its transfer hook
+    fn hook_mint(key: Pubkey, program_id: &Pubkey) -> TestAccount {
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook])
+                .unwrap();
+        let mut mint = TestAccount::new(key, spl_token_2022::id(), space);
+        let mut state =
+            StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint.data).unwrap();
+        let extension = state.init_extension::<TransferHook>(true).unwrap();
+        extension.program_id = Some(*program_id).try_into().unwrap();
+        state.base = Mint {
+            decimals: crate::TOKEN_DECIMALS,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        mint
+    }
+
+    // Token-2022 account in the middle of a transfer, or not
+    fn hook_account(mint: &Pubkey, owner: &Pubkey, transferring: bool) -> TestAccount {
+        let space = ExtensionType::try_calculate_account_len::<Account>(&[
+            ExtensionType::TransferHookAccount,
+        ])
+        .unwrap();
+        let mut account = TestAccount::new(Pubkey::new_unique(), spl_token_2022::id(), space);
+        let mut state =
+            StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut account.data).unwrap();
+        let extension = state.init_extension::<TransferHookAccount>(true).unwrap();
+        extension.transferring = transferring.into();
+        state.base = Account {
+            mint: *mint,
+            owner: *owner,
+            state: AccountState::Initialized,
+            ..Account::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        account
+    }
+
+    #[test]
+    fn test_execute_rejects_blocked_owners() {
+        let mut env = HookEnv::new();
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let mut alice_account = hook_account(&env.mint.key, &alice, true);
+        let mut bob_account = hook_account(&env.mint.key, &bob, true);
+
+        env.execute(&mut alice_account, &mut bob_account).unwrap();
+
+        env.block(bob).unwrap();
+        assert_eq!(
+            env.execute(&mut alice_account, &mut bob_account)
+                .unwrap_err(),
+            AriaError::AddressBlocked.into()
+        );
+        assert_eq!(
+            env.execute(&mut bob_account, &mut alice_account)
+                .unwrap_err(),
+            AriaError::AddressBlocked.into()
+        );
+
+        env.blocklist.is_writable = true;
+        let accounts = vec![env.compliance.info(), env.mint.info(), env.blocklist.info()];
+        process_unblock_address(&env.program_id, &accounts, bob).unwrap();
+        drop(accounts);
+        env.blocklist.is_writable = false;
+        env.execute(&mut alice_account, &mut bob_account).unwrap();
+    }
+
+    #[test]
+    fn test_execute_outside_transfer() {
+        let mut env = HookEnv::new();
+        let mut source = hook_account(&env.mint.key, &Pubkey::new_unique(), false);
+        let mut destination = hook_account(&env.mint.key, &Pubkey::new_unique(), true);
+        assert_eq!(
+            env.execute(&mut source, &mut destination).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+
+        // Only Token-2022 accounts are unpacked
+        let mut source = hook_account(&env.mint.key, &Pubkey::new_unique(), true);
+        source.owner = Pubkey::new_unique();
+        assert_eq!(
+            env.execute(&mut source, &mut destination).unwrap_err(),
+            AriaError::InvalidTokenAccountOwner.into()
+        );
+
+        // The blocklist must be the account listed in the extra-account-metas PDA
+        source.owner = spl_token_2022::id();
+        env.blocklist.key = Pubkey::new_unique();
+        assert!(env.execute(&mut source, &mut destination).is_err());
+    }
+
+    #[test]
+    fn test_blocklist_management() {
+        let mut env = HookEnv::new();
+        let mut intruder = TestAccount::wallet().signer();
+        let accounts = vec![intruder.info(), env.mint.info(), env.blocklist.info()];
+        let result = process_block_address(&env.program_id, &accounts, Pubkey::new_unique());
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop(accounts);
+
+        let blocked = Pubkey::new_unique();
+        env.block(blocked).unwrap();
+        env.block(blocked).unwrap();
+        assert_eq!(env.blocklist_state().blocked, vec![blocked]);
+
+        // A new compliance authority takes over
+        env.blocklist.is_writable = true;
+        let accounts = vec![env.compliance.info(), env.mint.info(), env.blocklist.info()];
+        process_set_compliance_authority(&env.program_id, &accounts, intruder.key).unwrap();
+        drop(accounts);
+        assert_eq!(
+            env.block(Pubkey::new_unique()).unwrap_err(),
+            AriaError::InsufficientAuthority.into()
+        );
+
+        let mut full = Blocklist {
+            blocked: vec![Pubkey::default(); MAX_BLOCKED],
+            ..env.blocklist_state()
+        };
+        assert!(ACCOUNT_HEADER_LEN + full.try_to_vec().unwrap().len() <= Blocklist::LEN);
+        full.compliance_authority = env.compliance.key;
+        store_test_state(&full, &mut env.blocklist);
+        assert_eq!(
+            env.block(Pubkey::new_unique()).unwrap_err(),
+            AriaError::BlocklistFull.into()
+        );
+    }
+
+    #[test]
+    fn test_initialize_transfer_hook() {
+        let program_id = Pubkey::new_unique();
+        let mut freeze_authority = TestAccount::wallet().signer().writable();
+        let mint_key = Pubkey::new_unique();
+        let mut mint = hook_mint(mint_key, &program_id);
+        {
+            let mut state = StateWithExtensionsMut::<Mint>::unpack(&mut mint.data).unwrap();
+            state.base.freeze_authority = Some(freeze_authority.key).into();
+            state.pack_base();
+        }
+        let (extra_metas_key, _) = find_extra_account_metas_address(&program_id, &mint_key);
+        // The off-chain CPI stubs leave the owner as is, so the list is written in place
+        let mut extra_metas = TestAccount::new(
+            extra_metas_key,
+            solana_program::system_program::id(),
+            ExtraAccountMetaList::size_of(1).unwrap(),
+        )
+        .writable();
+        let (blocklist_key, _) = find_blocklist_address(&program_id, &mint_key);
+        let mut blocklist = TestAccount::new(blocklist_key, program_id, Blocklist::LEN).writable();
+        let mut rent = rent_account();
+        let mut system_program = system_program_account();
+        let mut intruder = TestAccount::wallet().signer();
+        let compliance = Pubkey::new_unique();
+
+        let accounts = vec![
+            intruder.info(),
+            mint.info(),
+            extra_metas.info(),
+            blocklist.info(),
+            rent.info(),
+            system_program.info(),
+        ];
+        let result = process_initialize_transfer_hook(&program_id, &accounts, compliance);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop(accounts);
+
+        let accounts = vec![
+            freeze_authority.info(),
+            mint.info(),
+            extra_metas.info(),
+            blocklist.info(),
+            rent.info(),
+            system_program.info(),
+        ];
+        process_initialize_transfer_hook(&program_id, &accounts, compliance).unwrap();
+        let result = process_initialize_transfer_hook(&program_id, &accounts, compliance);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::AccountAlreadyInitialized.into()
+        );
+        drop(accounts);
+
+        let state = Blocklist::unpack(&blocklist.data).unwrap();
+        assert_eq!(state.compliance_authority, compliance);
+        assert_eq!(state.mint, mint_key);
+        assert_eq!(stored_metas(&extra_metas), extra_account_metas().unwrap());
+
+        // Another deployment's mint cannot be configured here
+        let mut other_mint = hook_mint(mint_key, &Pubkey::new_unique());
+        let accounts = vec![
+            freeze_authority.info(),
+            other_mint.info(),
+            extra_metas.info(),
+            blocklist.info(),
+            rent.info(),
+            system_program.info(),
+        ];
+        let result = process_initialize_transfer_hook(&program_id, &accounts, compliance);
+        assert_eq!(result.unwrap_err(), ProgramError::InvalidAccountData);
+    }
+
+    #[test]
+    fn test_extra_account_meta_list_instructions() {
+        let program_id = Pubkey::new_unique();
+        let mut freeze_authority = TestAccount::wallet().signer();
+        let mint_key = Pubkey::new_unique();
+        let mut mint = hook_mint(mint_key, &program_id);
+        {
+            let mut state = StateWithExtensionsMut::<Mint>::unpack(&mut mint.data).unwrap();
+            state.base.freeze_authority = Some(freeze_authority.key).into();
+            state.pack_base();
+        }
+        let (extra_metas_key, _) = find_extra_account_metas_address(&program_id, &mint_key);
+        // Pre-funded and large enough for the updated list, as realloc needs a runtime buffer
+        let mut extra_metas = TestAccount::new(
+            extra_metas_key,
+            solana_program::system_program::id(),
+            ExtraAccountMetaList::size_of(2).unwrap(),
+        )
+        .writable();
+        let mut system_program = system_program_account();
+        let mut intruder = TestAccount::wallet().signer();
+
+        let blocklist_meta = extra_account_metas().unwrap()[0];
+        let other_meta =
+            ExtraAccountMeta::new_with_pubkey(&Pubkey::new_unique(), false, false).unwrap();
+
+        let accounts = vec![
+            extra_metas.info(),
+            mint.info(),
+            intruder.info(),
+            system_program.info(),
+        ];
+        let result =
+            process_initialize_extra_account_meta_list(&program_id, &accounts, &[blocklist_meta]);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop(accounts);
+
+        let accounts = vec![
+            extra_metas.info(),
+            mint.info(),
+            freeze_authority.info(),
+            system_program.info(),
+        ];
+        let result = process_initialize_extra_account_meta_list(
+            &program_id,
+            &accounts,
+            &[other_meta, blocklist_meta],
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::InvalidExtraAccountMetas.into()
+        );
+        process_initialize_extra_account_meta_list(&program_id, &accounts, &[blocklist_meta])
+            .unwrap();
+        drop(accounts);
+        assert_eq!(stored_metas(&extra_metas), vec![blocklist_meta]);
+
+        // Assigned by the system program on-chain
+        extra_metas.owner = program_id;
+        let accounts = vec![
+            extra_metas.info(),
+            mint.info(),
+            freeze_authority.info(),
+            system_program.info(),
+        ];
+        let result =
+            process_initialize_extra_account_meta_list(&program_id, &accounts, &[blocklist_meta]);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::AccountAlreadyInitialized.into()
+        );
+        drop(accounts);
+
+        let accounts = vec![extra_metas.info(), mint.info(), intruder.info()];
+        let result = process_update_extra_account_meta_list(
+            &program_id,
+            &accounts,
+            &[blocklist_meta, other_meta],
+        );
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+        drop(accounts);
+
+        let accounts = vec![extra_metas.info(), mint.info(), freeze_authority.info()];
+        let result = process_update_extra_account_meta_list(&program_id, &accounts, &[]);
+        assert_eq!(
+            result.unwrap_err(),
+            AriaError::InvalidExtraAccountMetas.into()
+        );
+        process_update_extra_account_meta_list(
+            &program_id,
+            &accounts,
+            &[blocklist_meta, other_meta],
+        )
+        .unwrap();
+        drop(accounts);
+        assert_eq!(stored_metas(&extra_metas), vec![blocklist_meta, other_meta]);
+    }
+}
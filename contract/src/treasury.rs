@@ -0,0 +1,688 @@
+// Program-owned treasury
+// ARI and SOL are held by a vault PDA and can only leave through spend proposals
+// approved by a threshold of the configured approvers.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::events::{self, emit};
+use crate::token::transfer_checked;
+
+use crate::utils::{check_pda, check_system_program, create_pda_account, load_state, store_state};
+use crate::validation::{
+    check_signer, check_writable, load_clock, load_rent, MintAccount, TokenAccount,
+};
+use crate::{load_active_config, load_config_with_authority, AriaError};
+
+pub use aria_token_interface::treasury::*;
+
+// Validate an approver set and threshold
+fn check_approvers(approvers: &[Pubkey], threshold: u8) -> ProgramResult {
+    if approvers.is_empty() || approvers.len() > MAX_TREASURY_APPROVERS {
+        return Err(AriaError::InvalidApproverSet.into());
+    }
+    for (i, approver) in approvers.iter().enumerate() {
+        if approvers[..i].contains(approver) {
+            return Err(AriaError::InvalidApproverSet.into());
+        }
+    }
+    if threshold == 0 || threshold as usize > approvers.len() {
+        return Err(AriaError::InvalidThreshold.into());
+    }
+    Ok(())
+}
+
+// Load an initialized treasury config after checking its address
+fn load_treasury(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    treasury_info: &AccountInfo,
+) -> Result<TreasuryConfig, ProgramError> {
+    let (treasury_address, _) = find_treasury_address(program_id, mint);
+    check_pda(treasury_info, &treasury_address)?;
+    let treasury: TreasuryConfig = load_state(treasury_info, program_id)?;
+    if !treasury.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+    Ok(treasury)
+}
+
+// Load an initialized spend record after checking its address
+fn load_spend(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    spend_info: &AccountInfo,
+    index: u64,
+) -> Result<SpendRecord, ProgramError> {
+    let (spend_address, _) = find_spend_address(program_id, mint, index);
+    check_pda(spend_info, &spend_address)?;
+    let spend: SpendRecord = load_state(spend_info, program_id)?;
+    if !spend.is_initialized {
+        return Err(AriaError::AccountNotInitialized.into());
+    }
+    Ok(spend)
+}
+
+pub fn process_configure_treasury(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    approvers: Vec<Pubkey>,
+    threshold: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    check_approvers(&approvers, threshold)?;
+
+    // Only the program authority may change the approver set
+    let config = load_config_with_authority(program_id, config_info, authority_info)?;
+    config.check_not_paused()?;
+    let mint = config.mint;
+
+    let (treasury_address, bump) = find_treasury_address(program_id, &mint);
+    check_pda(treasury_info, &treasury_address)?;
+    check_system_program(system_program_info)?;
+
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        authority_info,
+        treasury_info,
+        system_program_info,
+        rent,
+        TreasuryConfig::LEN,
+        program_id,
+        &[TREASURY_SEED, mint.as_ref(), &[bump]],
+    )?;
+
+    // Keep the spend counter when updating an existing treasury
+    let existing: TreasuryConfig = load_state(treasury_info, program_id)?;
+    let spend_count = if existing.is_initialized {
+        existing.spend_count
+    } else {
+        0
+    };
+
+    let treasury = TreasuryConfig {
+        is_initialized: true,
+        mint,
+        approvers,
+        threshold,
+        spend_count,
+    };
+    store_state(&treasury, treasury_info)?;
+
+    emit(&events::TreasuryConfigured {
+        mint,
+        approvers: treasury.approvers.clone(),
+        threshold,
+    });
+    msg!(
+        "Treasury configured with {} approvers, threshold {}",
+        treasury.approvers.len(),
+        threshold
+    );
+    Ok(())
+}
+
+pub fn process_propose_spend(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    asset: TreasuryAsset,
+    recipient: Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let proposer_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let spend_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    load_active_config(program_id, config_info)?;
+
+    // Verify proposer
+    check_signer(proposer_info)?;
+
+    let mut treasury = load_treasury(program_id, mint_info.key, treasury_info)?;
+    if !treasury.is_approver(proposer_info.key) {
+        return Err(AriaError::NotAnApprover.into());
+    }
+
+    if amount == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let index = treasury.spend_count;
+    let (spend_address, bump) = find_spend_address(program_id, mint_info.key, index);
+    check_pda(spend_info, &spend_address)?;
+    check_system_program(system_program_info)?;
+
+    let clock = load_clock(clock_info)?;
+    let rent = &load_rent(rent_info)?;
+    create_pda_account(
+        proposer_info,
+        spend_info,
+        system_program_info,
+        rent,
+        SpendRecord::LEN,
+        program_id,
+        &[
+            SPEND_SEED,
+            mint_info.key.as_ref(),
+            &index.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+
+    // The proposer's approval is implied
+    let spend = SpendRecord {
+        is_initialized: true,
+        mint: *mint_info.key,
+        index,
+        proposer: *proposer_info.key,
+        asset,
+        recipient,
+        amount,
+        approvals: vec![*proposer_info.key],
+        status: SpendStatus::Pending,
+        created_at: clock.unix_timestamp,
+        executed_at: 0,
+    };
+    store_state(&spend, spend_info)?;
+
+    treasury.spend_count = index
+        .checked_add(1)
+        .ok_or(AriaError::ArithmeticOverflow)?;
+    store_state(&treasury, treasury_info)?;
+
+    emit(&events::SpendProposed {
+        mint: *mint_info.key,
+        index,
+        proposer: *proposer_info.key,
+        asset,
+        recipient,
+        amount,
+    });
+    msg!(
+        "Spend {} proposed: {} {:?} to {}",
+        index,
+        amount,
+        asset,
+        recipient
+    );
+    Ok(())
+}
+
+pub fn process_approve_spend(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let approver_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let spend_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    load_active_config(program_id, config_info)?;
+
+    // Verify approver
+    check_signer(approver_info)?;
+
+    let treasury = load_treasury(program_id, mint_info.key, treasury_info)?;
+    if !treasury.is_approver(approver_info.key) {
+        return Err(AriaError::NotAnApprover.into());
+    }
+
+    let mut spend = load_spend(program_id, mint_info.key, spend_info, index)?;
+    if spend.status != SpendStatus::Pending {
+        return Err(AriaError::SpendAlreadyExecuted.into());
+    }
+    if spend.approvals.contains(approver_info.key) {
+        return Err(AriaError::AlreadyApproved.into());
+    }
+    // Approvals from removed approvers no longer count and can be replaced
+    spend.approvals.retain(|approver| treasury.is_approver(approver));
+    spend.approvals.push(*approver_info.key);
+    store_state(&spend, spend_info)?;
+
+    emit(&events::SpendApproved {
+        mint: *mint_info.key,
+        index,
+        approver: *approver_info.key,
+        approvals: spend.approvals.len() as u8,
+    });
+    msg!(
+        "Spend {} approved by {} ({}/{})",
+        index,
+        approver_info.key,
+        spend.approvals.len(),
+        treasury.threshold
+    );
+    Ok(())
+}
+
+pub fn process_execute_spend(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let executor_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let spend_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    load_active_config(program_id, config_info)?;
+
+    // Verify executor
+    check_signer(executor_info)?;
+
+    let treasury = load_treasury(program_id, mint_info.key, treasury_info)?;
+    if !treasury.is_approver(executor_info.key) {
+        return Err(AriaError::NotAnApprover.into());
+    }
+
+    let mut spend = load_spend(program_id, mint_info.key, spend_info, index)?;
+    if spend.status != SpendStatus::Pending {
+        return Err(AriaError::SpendAlreadyExecuted.into());
+    }
+    if spend.approval_count(&treasury) < treasury.threshold as usize {
+        return Err(AriaError::ThresholdNotMet.into());
+    }
+    if recipient_info.key != &spend.recipient {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let (vault_address, vault_bump) = find_treasury_vault_address(program_id, mint_info.key);
+    check_pda(vault_info, &vault_address)?;
+    let vault_seeds: &[&[u8]] = &[TREASURY_VAULT_SEED, mint_info.key.as_ref(), &[vault_bump]];
+
+    match spend.asset {
+        TreasuryAsset::Sol => {
+            check_system_program(system_program_info)?;
+
+            // The vault must stay rent exempt
+            let rent = &load_rent(rent_info)?;
+            let remaining = vault_info
+                .lamports()
+                .checked_sub(spend.amount)
+                .ok_or(ProgramError::InsufficientFunds)?;
+            if remaining < rent.minimum_balance(0) {
+                return Err(ProgramError::InsufficientFunds);
+            }
+
+            invoke_signed(
+                &system_instruction::transfer(vault_info.key, recipient_info.key, spend.amount),
+                &[
+                    vault_info.clone(),
+                    recipient_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[vault_seeds],
+            )?;
+        }
+        TreasuryAsset::Token => {
+            let mint = MintAccount::load(mint_info, token_program_info)?;
+            let source_account = TokenAccount::load_writable(source_info, mint_info.key)?;
+            if source_account.state.owner != vault_address {
+                return Err(AriaError::InsufficientAuthority.into());
+            }
+            check_writable(recipient_info)?;
+
+            transfer_checked(
+                token_program_info,
+                source_info,
+                mint_info,
+                recipient_info,
+                vault_info,
+                account_info_iter.as_slice(),
+                spend.amount,
+                mint.state.decimals,
+                &[vault_seeds],
+            )?;
+        }
+    }
+
+    let clock = load_clock(clock_info)?;
+    spend.status = SpendStatus::Executed;
+    spend.executed_at = clock.unix_timestamp;
+    store_state(&spend, spend_info)?;
+
+    emit(&events::SpendExecuted {
+        mint: *mint_info.key,
+        index,
+        asset: spend.asset,
+        recipient: spend.recipient,
+        amount: spend.amount,
+    });
+    msg!(
+        "Spend {} executed: {} {:?} to {}",
+        index,
+        spend.amount,
+        spend.asset,
+        spend.recipient
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use crate::test_utils::*;
+    use solana_program::rent::Rent;
+
+    struct TreasuryEnv {
+        program_id: Pubkey,
+        approvers: Vec<TestAccount>,
+        mint: TestAccount,
+        treasury: TestAccount,
+        clock: TestAccount,
+        rent: TestAccount,
+        system_program: TestAccount,
+        token_program: TestAccount,
+        program_config: TestAccount,
+    }
+
+    impl TreasuryEnv {
+        // Treasury with three approvers and a threshold of two
+        fn new() -> Self {
+            let program_id = Pubkey::new_unique();
+            let approvers: Vec<TestAccount> =
+                (0..3).map(|_| TestAccount::wallet().signer()).collect();
+            let mint = mint_account(Some(Pubkey::new_unique()), 0);
+            let (treasury_key, _) = find_treasury_address(&program_id, &mint.key);
+            let mut treasury =
+                TestAccount::new(treasury_key, program_id, TreasuryConfig::LEN).writable();
+            TreasuryConfig {
+                is_initialized: true,
+                mint: mint.key,
+                approvers: approvers.iter().map(|a| a.key).collect(),
+                threshold: 2,
+                spend_count: 0,
+            }
+            .pack_into(&mut treasury.data)
+            .unwrap();
+            let program_config = config_account(&program_id, &mint.key, &Pubkey::new_unique());
+            TreasuryEnv {
+                program_id,
+                approvers,
+                mint,
+                treasury,
+                clock: clock_account(1_700_000_000),
+                rent: rent_account(),
+                system_program: system_program_account(),
+                token_program: token_program_account(),
+                program_config,
+            }
+        }
+
+        fn spend_account(&self, index: u64) -> TestAccount {
+            let (key, _) = find_spend_address(&self.program_id, &self.mint.key, index);
+            TestAccount::new(key, self.program_id, SpendRecord::LEN).writable()
+        }
+
+        fn propose(
+            &mut self,
+            approver: usize,
+            spend: &mut TestAccount,
+            asset: TreasuryAsset,
+            recipient: Pubkey,
+            amount: u64,
+        ) -> ProgramResult {
+            let accounts = vec![
+                self.approvers[approver].info(),
+                self.mint.info(),
+                self.treasury.info(),
+                spend.info(),
+                self.clock.info(),
+                self.rent.info(),
+                self.system_program.info(),
+                self.program_config.info(),
+            ];
+            process_propose_spend(&self.program_id, &accounts, asset, recipient, amount)
+        }
+
+        fn approve(&mut self, approver: usize, spend: &mut TestAccount, index: u64) -> ProgramResult {
+            let accounts = vec![
+                self.approvers[approver].info(),
+                self.mint.info(),
+                self.treasury.info(),
+                spend.info(),
+                self.program_config.info(),
+            ];
+            process_approve_spend(&self.program_id, &accounts, index)
+        }
+
+        fn execute(
+            &mut self,
+            spend: &mut TestAccount,
+            vault: &mut TestAccount,
+            source: &mut TestAccount,
+            recipient: &mut TestAccount,
+            index: u64,
+        ) -> ProgramResult {
+            let accounts = vec![
+                self.approvers[0].info(),
+                self.mint.info(),
+                self.treasury.info(),
+                spend.info(),
+                vault.info(),
+                source.info(),
+                recipient.info(),
+                self.clock.info(),
+                self.rent.info(),
+                self.token_program.info(),
+                self.system_program.info(),
+                self.program_config.info(),
+            ];
+            process_execute_spend(&self.program_id, &accounts, index)
+        }
+    }
+
+    #[test]
+    fn test_check_approvers() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        assert!(check_approvers(&[a, b], 2).is_ok());
+        assert_eq!(
+            check_approvers(&[a, b], 3).unwrap_err(),
+            AriaError::InvalidThreshold.into()
+        );
+        assert_eq!(
+            check_approvers(&[a, b], 0).unwrap_err(),
+            AriaError::InvalidThreshold.into()
+        );
+        assert_eq!(
+            check_approvers(&[a, a], 1).unwrap_err(),
+            AriaError::InvalidApproverSet.into()
+        );
+        assert_eq!(
+            check_approvers(&[], 1).unwrap_err(),
+            AriaError::InvalidApproverSet.into()
+        );
+        let too_many: Vec<Pubkey> = (0..=MAX_TREASURY_APPROVERS)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        assert_eq!(
+            check_approvers(&too_many, 1).unwrap_err(),
+            AriaError::InvalidApproverSet.into()
+        );
+    }
+
+    #[test]
+    fn test_state_sizes() {
+        let config = TreasuryConfig {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            approvers: vec![Pubkey::new_unique(); MAX_TREASURY_APPROVERS],
+            threshold: 1,
+            spend_count: 0,
+        };
+        assert_eq!(ACCOUNT_HEADER_LEN + config.try_to_vec().unwrap().len(), TreasuryConfig::LEN);
+
+        let spend = SpendRecord {
+            is_initialized: true,
+            mint: Pubkey::new_unique(),
+            index: 0,
+            proposer: Pubkey::new_unique(),
+            asset: TreasuryAsset::Sol,
+            recipient: Pubkey::new_unique(),
+            amount: 1,
+            approvals: vec![Pubkey::new_unique(); MAX_TREASURY_APPROVERS],
+            status: SpendStatus::Pending,
+            created_at: 0,
+            executed_at: 0,
+        };
+        assert_eq!(ACCOUNT_HEADER_LEN + spend.try_to_vec().unwrap().len(), SpendRecord::LEN);
+    }
+
+    #[test]
+    fn test_configure_treasury() {
+        let program_id = Pubkey::new_unique();
+        let mut authority = TestAccount::wallet().signer();
+        let mint = Pubkey::new_unique();
+        let mut config = config_account(&program_id, &mint, &authority.key);
+        let (treasury_key, _) = find_treasury_address(&program_id, &mint);
+        let mut treasury = TestAccount::new(treasury_key, program_id, TreasuryConfig::LEN).writable();
+        let mut rent = rent_account();
+        let mut system_program = system_program_account();
+        let approvers = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let accounts = vec![
+            authority.info(),
+            config.info(),
+            treasury.info(),
+            rent.info(),
+            system_program.info(),
+        ];
+        process_configure_treasury(&program_id, &accounts, approvers.clone(), 2).unwrap();
+        drop(accounts);
+
+        let state: TreasuryConfig =
+            VersionedAccount::unpack(&treasury.data).unwrap();
+        assert!(state.is_initialized);
+        assert_eq!(state.approvers, approvers);
+        assert_eq!(state.threshold, 2);
+
+        // Only the program authority can change the approver set
+        let mut other = TestAccount::wallet().signer();
+        let accounts = vec![
+            other.info(),
+            config.info(),
+            treasury.info(),
+            rent.info(),
+            system_program.info(),
+        ];
+        let result = process_configure_treasury(&program_id, &accounts, approvers, 1);
+        assert_eq!(result.unwrap_err(), AriaError::InsufficientAuthority.into());
+    }
+
+    #[test]
+    fn test_token_spend_lifecycle() {
+        let mut env = TreasuryEnv::new();
+        let recipient_owner = Pubkey::new_unique();
+        let (vault_key, _) = find_treasury_vault_address(&env.program_id, &env.mint.key);
+        let mut vault = TestAccount::new(vault_key, solana_program::system_program::id(), 0);
+        let mut source = token_account(&env.mint.key, &vault_key, 1_000_000);
+        let mut recipient = token_account(&env.mint.key, &recipient_owner, 0);
+        let mut spend = env.spend_account(0);
+
+        env.propose(0, &mut spend, TreasuryAsset::Token, recipient.key, 5_000)
+            .unwrap();
+
+        // One approval is below the threshold
+        let result = env.execute(&mut spend, &mut vault, &mut source, &mut recipient, 0);
+        assert_eq!(result.unwrap_err(), AriaError::ThresholdNotMet.into());
+
+        let result = env.approve(0, &mut spend, 0);
+        assert_eq!(result.unwrap_err(), AriaError::AlreadyApproved.into());
+        env.approve(1, &mut spend, 0).unwrap();
+
+        env.execute(&mut spend, &mut vault, &mut source, &mut recipient, 0)
+            .unwrap();
+        let record: SpendRecord = VersionedAccount::unpack(&spend.data).unwrap();
+        assert_eq!(record.status, SpendStatus::Executed);
+        assert_eq!(record.executed_at, 1_700_000_000);
+        assert_eq!(record.amount, 5_000);
+
+        // A spend only executes once
+        let result = env.execute(&mut spend, &mut vault, &mut source, &mut recipient, 0);
+        assert_eq!(result.unwrap_err(), AriaError::SpendAlreadyExecuted.into());
+
+        let config: TreasuryConfig =
+            VersionedAccount::unpack(&env.treasury.data).unwrap();
+        assert_eq!(config.spend_count, 1);
+    }
+
+    #[test]
+    fn test_sol_spend_keeps_vault_rent_exempt() {
+        let mut env = TreasuryEnv::new();
+        let (vault_key, _) = find_treasury_vault_address(&env.program_id, &env.mint.key);
+        let mut vault = TestAccount::new(vault_key, solana_program::system_program::id(), 0);
+        vault.lamports = Rent::default().minimum_balance(0) + 1_000;
+        let mut source = TestAccount::wallet();
+        let mut recipient = TestAccount::wallet().writable();
+
+        let mut spend = env.spend_account(0);
+        env.propose(0, &mut spend, TreasuryAsset::Sol, recipient.key, 1_001)
+            .unwrap();
+        env.approve(2, &mut spend, 0).unwrap();
+        let result = env.execute(&mut spend, &mut vault, &mut source, &mut recipient, 0);
+        assert_eq!(result.unwrap_err(), ProgramError::InsufficientFunds);
+
+        let mut spend = env.spend_account(1);
+        env.propose(0, &mut spend, TreasuryAsset::Sol, recipient.key, 1_000)
+            .unwrap();
+        env.approve(2, &mut spend, 1).unwrap();
+        env.execute(&mut spend, &mut vault, &mut source, &mut recipient, 1)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_spend_requires_approver() {
+        let mut env = TreasuryEnv::new();
+        let mut spend = env.spend_account(0);
+        env.approvers.push(TestAccount::wallet().signer());
+
+        let result = env.propose(3, &mut spend, TreasuryAsset::Sol, Pubkey::new_unique(), 1);
+        assert_eq!(result.unwrap_err(), AriaError::NotAnApprover.into());
+
+        env.propose(0, &mut spend, TreasuryAsset::Sol, Pubkey::new_unique(), 1)
+            .unwrap();
+        let result = env.approve(3, &mut spend, 0);
+        assert_eq!(result.unwrap_err(), AriaError::NotAnApprover.into());
+    }
+}
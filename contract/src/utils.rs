@@ -0,0 +1,141 @@
+// Shared helpers for program-owned state accounts
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+    sysvar::rent::Rent,
+};
+
+use crate::validation::check_writable;
+use crate::AriaError;
+
+pub(crate) use aria_token_interface::{
+    account::VersionedAccount, apply_basis_points, MAX_BASIS_POINTS,
+};
+
+// Verify that the supplied account is the expected program derived address
+pub(crate) fn check_pda(account_info: &AccountInfo, expected: &Pubkey) -> ProgramResult {
+    if account_info.key != expected {
+        msg!("Invalid program address {}, expected {}", account_info.key, expected);
+        return Err(AriaError::InvalidProgramAddress.into());
+    }
+    Ok(())
+}
+
+// Verify the system program account
+pub(crate) fn check_system_program(account_info: &AccountInfo) -> ProgramResult {
+    if account_info.key != &system_program::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+// Create a program-owned PDA account, funded by `payer`.
+// Accounts that were pre-funded with lamports are topped up, allocated and assigned instead,
+// so nobody can block creation by sending lamports to the address first.
+pub(crate) fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    rent: &Rent,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    // Already allocated by this program. A smaller account was created with an older layout
+    // and has to be migrated first.
+    if new_account.owner == owner {
+        if new_account.data_len() < space {
+            msg!("Account {} has an outdated layout", new_account.key);
+            return Err(AriaError::AccountVersionMismatch.into());
+        }
+        return Ok(());
+    }
+
+    let required_lamports = rent.minimum_balance(space);
+
+    if new_account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                new_account.key,
+                required_lamports,
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), new_account.clone(), system_program_info.clone()],
+            &[signer_seeds],
+        )?;
+    } else {
+        let top_up = required_lamports.saturating_sub(new_account.lamports());
+        if top_up > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, new_account.key, top_up),
+                &[payer.clone(), new_account.clone(), system_program_info.clone()],
+            )?;
+        }
+        allocate_pda_account(new_account, system_program_info, space, owner, signer_seeds)?;
+    }
+    Ok(())
+}
+
+// Allocate and assign a PDA that already holds its lamports. The runtime rejects the
+// transaction if the account is left below the rent-exempt minimum.
+pub(crate) fn allocate_pda_account<'a>(
+    new_account: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &system_instruction::allocate(new_account.key, space as u64),
+        &[new_account.clone(), system_program_info.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(new_account.key, owner),
+        &[new_account.clone(), system_program_info.clone()],
+        &[signer_seeds],
+    )
+}
+
+// Close a program-owned account, returning its lamports to `destination`
+pub(crate) fn close_pda_account(account_info: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let lamports = account_info.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(AriaError::ArithmeticOverflow)?;
+    **account_info.try_borrow_mut_lamports()? = 0;
+    account_info.data.borrow_mut().fill(0);
+    Ok(())
+}
+
+// Deserialize program state from an account owned by this program
+pub(crate) fn load_state<T: VersionedAccount>(
+    account_info: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<T, ProgramError> {
+    if account_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let data = account_info.data.borrow();
+    T::unpack(&data).map_err(|err| {
+        if err == AriaError::AccountVersionMismatch.into() {
+            msg!("Account {} has an outdated layout", account_info.key);
+        }
+        err
+    })
+}
+
+// Serialize program state into an account, with the current account header
+pub(crate) fn store_state<T: VersionedAccount>(state: &T, account_info: &AccountInfo) -> ProgramResult {
+    check_writable(account_info)?;
+    state.pack_into(&mut account_info.data.borrow_mut())
+}
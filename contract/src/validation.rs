@@ -0,0 +1,192 @@
+// Account validation
+// Typed views of the accounts handlers receive. Each loader checks the account before its data
+// is trusted and fails with a precise AriaError: signatures and writability, sysvars by address,
+// mints and token accounts by owning token program and mint.
+
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use crate::token::{self, Account, Mint};
+use crate::AriaError;
+
+// Verify that the account signed the transaction
+pub(crate) fn check_signer(account_info: &AccountInfo) -> ProgramResult {
+    if !account_info.is_signer {
+        return Err(AriaError::InsufficientAuthority.into());
+    }
+    Ok(())
+}
+
+// Verify that the account is writable, before the handler changes it or CPIs into it
+pub(crate) fn check_writable(account_info: &AccountInfo) -> ProgramResult {
+    if !account_info.is_writable {
+        msg!("Account {} must be writable", account_info.key);
+        return Err(AriaError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+// Read a sysvar from its account after checking the sysvar address
+fn load_sysvar<S: Sysvar>(account_info: &AccountInfo) -> Result<S, ProgramError> {
+    if !S::check_id(account_info.key) {
+        msg!("Account {} is not the expected sysvar", account_info.key);
+        return Err(AriaError::InvalidSysvar.into());
+    }
+    S::from_account_info(account_info)
+}
+
+// Read the clock sysvar
+pub(crate) fn load_clock(clock_info: &AccountInfo) -> Result<Clock, ProgramError> {
+    load_sysvar(clock_info)
+}
+
+// Read the rent sysvar
+pub(crate) fn load_rent(rent_info: &AccountInfo) -> Result<Rent, ProgramError> {
+    load_sysvar(rent_info)
+}
+
+// Mint owned by the token program the handler CPIs into
+#[derive(Debug)]
+pub(crate) struct MintAccount {
+    pub key: Pubkey,
+    pub state: Mint,
+}
+
+impl MintAccount {
+    pub fn load(
+        mint_info: &AccountInfo,
+        token_program_info: &AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        token::check_mint_token_program(mint_info, token_program_info)?;
+        Ok(MintAccount {
+            key: *mint_info.key,
+            state: token::unpack_mint(mint_info)?,
+        })
+    }
+
+    // Verify that minting is controlled by `expected`
+    pub fn check_mint_authority(&self, expected: &Pubkey) -> ProgramResult {
+        match Option::<Pubkey>::from(self.state.mint_authority) {
+            None => Err(AriaError::MintAuthorityNotSet.into()),
+            Some(authority) if authority != *expected => {
+                msg!("Mint authority {} is not {}", authority, expected);
+                Err(AriaError::MintAuthorityMismatch.into())
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+// Token account holding tokens of `mint`
+#[derive(Debug)]
+pub(crate) struct TokenAccount {
+    pub state: Account,
+}
+
+impl TokenAccount {
+    pub fn load(account_info: &AccountInfo, mint: &Pubkey) -> Result<Self, ProgramError> {
+        let state = token::unpack_token_account(account_info)?;
+        if state.mint != *mint {
+            return Err(AriaError::TokenMintMismatch.into());
+        }
+        Ok(TokenAccount { state })
+    }
+
+    // Load a token account the handler credits or debits
+    pub fn load_writable(account_info: &AccountInfo, mint: &Pubkey) -> Result<Self, ProgramError> {
+        check_writable(account_info)?;
+        Self::load(account_info, mint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use solana_program::program_pack::Pack;
+
+    #[test]
+    fn test_account_flags_and_sysvars() {
+        let mut wallet = TestAccount::wallet();
+        assert_eq!(
+            check_signer(&wallet.info()).unwrap_err(),
+            AriaError::InsufficientAuthority.into()
+        );
+        assert_eq!(
+            check_writable(&wallet.info()).unwrap_err(),
+            AriaError::AccountNotWritable.into()
+        );
+        let mut wallet = wallet.signer();
+        assert!(check_signer(&wallet.info()).is_ok());
+        assert!(check_writable(&wallet.info()).is_ok());
+
+        let mut clock = clock_account(100);
+        let mut rent = rent_account();
+        assert_eq!(load_clock(&clock.info()).unwrap().unix_timestamp, 100);
+        assert!(load_rent(&rent.info()).is_ok());
+        assert_eq!(
+            load_clock(&rent.info()).unwrap_err(),
+            AriaError::InvalidSysvar.into()
+        );
+        assert_eq!(
+            load_rent(&clock.info()).unwrap_err(),
+            AriaError::InvalidSysvar.into()
+        );
+    }
+
+    #[test]
+    fn test_token_accounts() {
+        let mut token_program = token_program_account();
+        let authority = Pubkey::new_unique();
+        let mut mint = mint_account(Some(authority), 0);
+        let loaded = MintAccount::load(&mint.info(), &token_program.info()).unwrap();
+        assert!(loaded.check_mint_authority(&authority).is_ok());
+        assert_eq!(
+            loaded
+                .check_mint_authority(&Pubkey::new_unique())
+                .unwrap_err(),
+            AriaError::MintAuthorityMismatch.into()
+        );
+
+        let mut fixed_supply = mint_account(None, 0);
+        let loaded = MintAccount::load(&fixed_supply.info(), &token_program.info()).unwrap();
+        assert_eq!(
+            loaded.check_mint_authority(&authority).unwrap_err(),
+            AriaError::MintAuthorityNotSet.into()
+        );
+
+        let mut account = token_account(&mint.key, &authority, 5);
+        account.is_writable = false;
+        assert_eq!(
+            TokenAccount::load(&account.info(), &mint.key)
+                .unwrap()
+                .state
+                .amount,
+            5
+        );
+        assert_eq!(
+            TokenAccount::load(&account.info(), &fixed_supply.key).unwrap_err(),
+            AriaError::TokenMintMismatch.into()
+        );
+        assert_eq!(
+            TokenAccount::load_writable(&account.info(), &mint.key).unwrap_err(),
+            AriaError::AccountNotWritable.into()
+        );
+
+        // Token state in an account the token program does not own
+        let mut forged = TestAccount::wallet().writable();
+        forged.data = account.data.clone();
+        assert_eq!(Account::unpack(&forged.data).unwrap().amount, 5);
+        assert_eq!(
+            TokenAccount::load(&forged.info(), &mint.key).unwrap_err(),
+            AriaError::InvalidTokenAccountOwner.into()
+        );
+    }
+}
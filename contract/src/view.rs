@@ -0,0 +1,139 @@
+// View instructions
+// Read-only queries that write their borsh-encoded result as return data, so clients can answer
+// them by simulating a transaction. They change no state and are accepted while paused.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::set_return_data,
+    pubkey::Pubkey,
+};
+
+use aria_token_interface::roles::{find_minter_allowance_address, MinterAllowance};
+use aria_token_interface::view::{ConfigView, PendingAuthorityTransfer, ProgramVersion};
+
+use crate::roles::next_mint_time;
+use crate::token::unpack_mint;
+use crate::utils::{check_pda, load_state};
+use crate::validation::load_clock;
+use crate::{load_config, AriaError, AUTHORITY_TRANSFER_EXPIRY, TOTAL_SUPPLY};
+
+fn return_view<T: BorshSerialize>(view: &T) -> ProgramResult {
+    // Serializing into a Vec cannot fail
+    set_return_data(&view.try_to_vec().unwrap());
+    Ok(())
+}
+
+pub fn process_get_config(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let config_info = next_account_info(account_info_iter)?;
+
+    let config = load_config(program_id, config_info)?;
+    return_view(&ConfigView {
+        mint: config.mint,
+        authority: config.authority,
+        paused: config.paused,
+        last_mint_time: config.last_mint_time,
+    })
+}
+
+pub fn process_get_remaining_mintable(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    minter: Option<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+
+    let config = load_config(program_id, config_info)?;
+    if config.mint != *mint_info.key {
+        return Err(AriaError::TokenMintMismatch.into());
+    }
+    let mint = unpack_mint(mint_info)?;
+    let mut remaining = TOTAL_SUPPLY.saturating_sub(mint.supply);
+
+    // A minter without an allowance account has nothing left to mint
+    if let Some(minter) = minter {
+        let allowance_info = next_account_info(account_info_iter)?;
+        let (allowance_address, _) = find_minter_allowance_address(program_id, &minter);
+        check_pda(allowance_info, &allowance_address)?;
+        let allowance = if allowance_info.owner == program_id {
+            let allowance: MinterAllowance = load_state(allowance_info, program_id)?;
+            allowance.remaining
+        } else {
+            0
+        };
+        remaining = remaining.min(allowance);
+    }
+
+    return_view(&remaining)
+}
+
+pub fn process_get_next_mint_time(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    minter: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let config_info = next_account_info(account_info_iter)?;
+    let allowance_info = next_account_info(account_info_iter)?;
+
+    load_config(program_id, config_info)?;
+    let (allowance_address, _) = find_minter_allowance_address(program_id, &minter);
+    check_pda(allowance_info, &allowance_address)?;
+
+    // A minter without an allowance account, or that has not minted yet, has no cooldown
+    let next_mint_time = if allowance_info.owner == program_id {
+        let allowance: MinterAllowance = load_state(allowance_info, program_id)?;
+        next_mint_time(&allowance).unwrap_or(0)
+    } else {
+        0
+    };
+    return_view(&next_mint_time)
+}
+
+pub fn process_get_pending_authority_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    // Get required accounts
+    let config_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+
+    let config = load_config(program_id, config_info)?;
+    let clock = load_clock(clock_info)?;
+
+    // Same expiry rule as AcceptAuthorityTransferV2
+    let transfer = &config.authority_transfer;
+    let expires_at = transfer
+        .proposal_time
+        .saturating_add(AUTHORITY_TRANSFER_EXPIRY);
+    let pending = transfer
+        .proposed_authority
+        .filter(|_| clock.unix_timestamp <= expires_at)
+        .map(|proposed_authority| PendingAuthorityTransfer {
+            proposed_authority,
+            proposal_time: transfer.proposal_time,
+            expires_at,
+        });
+    return_view(&pending)
+}
+
+pub fn process_get_program_version() -> ProgramResult {
+    let part = |version: &str| version.parse().unwrap_or_default();
+    return_view(&ProgramVersion {
+        major: part(env!("CARGO_PKG_VERSION_MAJOR")),
+        minor: part(env!("CARGO_PKG_VERSION_MINOR")),
+        patch: part(env!("CARGO_PKG_VERSION_PATCH")),
+    })
+}
@@ -0,0 +1,159 @@
+// Metaplex stand-in
+// solana-program-test does not bundle the Metaplex token metadata program and its binary is not
+// vendored here, so the integration tests register this stand-in under its program ID. It handles
+// the two instructions the ARIA program sends for legacy SPL Token mints, with the same accounts,
+// signer checks and account layout as Metaplex, but without its validation of the metadata.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use mpl_token_metadata::accounts::Metadata;
+use mpl_token_metadata::instructions::{
+    CreateMetadataAccountV3InstructionArgs, UpdateMetadataAccountV2InstructionArgs,
+};
+use mpl_token_metadata::types::{DataV2, Key};
+use mpl_token_metadata::{MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use aria_token_interface::metadata::{
+    find_metaplex_metadata_address, METAPLEX_METADATA_SEED, METAPLEX_PROGRAM_ID,
+};
+
+// Size Metaplex allocates for a metadata account
+const METADATA_LEN: usize = 679;
+
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+
+pub fn add_metaplex_program(program_test: &mut ProgramTest) {
+    program_test.add_program(
+        "mpl_token_metadata",
+        METAPLEX_PROGRAM_ID,
+        processor!(process_instruction),
+    );
+}
+
+// Metaplex pads strings with NUL bytes to their maximum length
+fn pad(value: &str, len: usize) -> String {
+    format!("{value:\0<len$}")
+}
+
+fn write_data(metadata: &mut Metadata, data: DataV2) {
+    metadata.name = pad(&data.name, MAX_NAME_LENGTH);
+    metadata.symbol = pad(&data.symbol, MAX_SYMBOL_LENGTH);
+    metadata.uri = pad(&data.uri, MAX_URI_LENGTH);
+    metadata.seller_fee_basis_points = data.seller_fee_basis_points;
+    metadata.creators = data.creators;
+    metadata.collection = data.collection;
+    metadata.uses = data.uses;
+}
+
+fn store(metadata: &Metadata, metadata_info: &AccountInfo) -> ProgramResult {
+    let data = metadata.try_to_vec()?;
+    metadata_info.data.borrow_mut()[..data.len()].copy_from_slice(&data);
+    Ok(())
+}
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (&discriminator, mut args) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let account_info_iter = &mut accounts.iter();
+    match discriminator {
+        CREATE_METADATA_ACCOUNT_V3 => {
+            let args = CreateMetadataAccountV3InstructionArgs::deserialize(&mut args)?;
+            let metadata_info = next_account_info(account_info_iter)?;
+            let mint_info = next_account_info(account_info_iter)?;
+            let mint_authority_info = next_account_info(account_info_iter)?;
+            let payer_info = next_account_info(account_info_iter)?;
+            let update_authority_info = next_account_info(account_info_iter)?;
+            let system_program_info = next_account_info(account_info_iter)?;
+
+            let mint = spl_token::state::Mint::unpack(&mint_info.data.borrow())?;
+            if !mint_authority_info.is_signer
+                || mint.mint_authority != Some(*mint_authority_info.key).into()
+            {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let (metadata_address, bump) = find_metaplex_metadata_address(mint_info.key);
+            if *metadata_info.key != metadata_address {
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer_info.key,
+                    metadata_info.key,
+                    Rent::get()?.minimum_balance(METADATA_LEN),
+                    METADATA_LEN as u64,
+                    program_id,
+                ),
+                &[
+                    payer_info.clone(),
+                    metadata_info.clone(),
+                    system_program_info.clone(),
+                ],
+                &[&[
+                    METAPLEX_METADATA_SEED,
+                    program_id.as_ref(),
+                    mint_info.key.as_ref(),
+                    &[bump],
+                ]],
+            )?;
+
+            let mut metadata = Metadata {
+                key: Key::MetadataV1,
+                update_authority: *update_authority_info.key,
+                mint: *mint_info.key,
+                name: String::new(),
+                symbol: String::new(),
+                uri: String::new(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                primary_sale_happened: false,
+                is_mutable: args.is_mutable,
+                edition_nonce: None,
+                token_standard: None,
+                collection: None,
+                uses: None,
+                collection_details: args.collection_details,
+                programmable_config: None,
+            };
+            write_data(&mut metadata, args.data);
+            store(&metadata, metadata_info)
+        }
+        UPDATE_METADATA_ACCOUNT_V2 => {
+            let args = UpdateMetadataAccountV2InstructionArgs::deserialize(&mut args)?;
+            let metadata_info = next_account_info(account_info_iter)?;
+            let update_authority_info = next_account_info(account_info_iter)?;
+
+            let mut metadata = Metadata::try_from(metadata_info)?;
+            if !update_authority_info.is_signer
+                || metadata.update_authority != *update_authority_info.key
+            {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if let Some(data) = args.data {
+                write_data(&mut metadata, data);
+            }
+            if let Some(update_authority) = args.new_update_authority {
+                metadata.update_authority = update_authority;
+            }
+            store(&metadata, metadata_info)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
@@ -0,0 +1,886 @@
+// Compute-unit benchmark
+// Every instruction runs once through solana-program-test, and the compute units it consumed are
+// compared with the baseline checked in at `tests/compute_units/sbf.txt`. The test fails when an
+// instruction uses more than `TOLERANCE_PERCENT` over its baseline or more than `MAX_UNITS`, and
+// when the baseline is missing.
+//
+// Only `cargo test-sbf` runs the compiled program and meters what it costs on chain. Under
+// `cargo test` the program runs natively and only its CPIs and syscalls are metered, so the
+// units are not compared: the native run checks that every instruction succeeds and has an
+// entry in the baseline, and fails when there is none. Record the baseline with
+// `ARIA_UPDATE_COMPUTE_UNITS=1 cargo test-sbf --test compute_units`. Legacy mint metadata goes
+// to the Metaplex stand-in in `common`.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use aria_token::{find_program_data_address, TOTAL_SUPPLY};
+use aria_token_client::instruction as aria_instruction;
+use aria_token_interface::account::AccountType;
+use aria_token_interface::fees::TransferFeeParams;
+use aria_token_interface::governance::{find_governance_vault_address, ProposalInstruction, Vote};
+use aria_token_interface::instruction::InstructionDiscriminator;
+use aria_token_interface::metadata::MetadataField;
+use aria_token_interface::roles::{
+    find_minter_allowance_address, AllowanceChange, MinterAllowanceV1, Role,
+};
+use aria_token_interface::treasury::{find_treasury_vault_address, TreasuryAsset};
+use borsh::BorshSerialize;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use spl_token_2022::extension::ExtensionType;
+
+const SOL: u64 = 1_000_000_000;
+const TOKENS: u64 = 1_000_000_000;
+
+/// Allowed increase over the baseline before an instruction counts as a regression
+const TOLERANCE_PERCENT: u64 = 5;
+/// Half of the default per-instruction budget, leaving room for the rest of a transaction
+const MAX_UNITS: u64 = 100_000;
+/// Baseline recorded by `cargo test-sbf`, relative to the crate
+const BASELINE_PATH: &str = "tests/compute_units/sbf.txt";
+
+/// Instructions that fail with `InstructionDeprecated`
+const RETIRED: [InstructionDiscriminator; 4] = [
+    InstructionDiscriminator::ProposeAuthorityTransfer,
+    InstructionDiscriminator::AcceptAuthorityTransfer,
+    InstructionDiscriminator::CancelAuthorityTransfer,
+    InstructionDiscriminator::GetNextMintTime,
+];
+
+const MEMO_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+// Mint extensions the CLI creates for a Token-2022 mint with a transfer fee and hook
+const MINT_EXTENSIONS: [ExtensionType; 3] = [
+    ExtensionType::MetadataPointer,
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::TransferHook,
+];
+
+// Compute units consumed by each instruction, keyed by the instruction name
+type Measurements = BTreeMap<String, u64>;
+
+struct Bench {
+    context: ProgramTestContext,
+    // Upgrade authority of the program, which initializes it
+    authority: Keypair,
+    mint: Keypair,
+    token_program: Pubkey,
+}
+
+impl Bench {
+    // Start a validator with the ARIA program and an uninitialized mint of `token_program`
+    async fn start(program_test: ProgramTest, authority: Keypair, token_program: Pubkey) -> Self {
+        let context = program_test.start_with_context().await;
+        let mut bench = Bench {
+            context,
+            authority,
+            mint: Keypair::new(),
+            token_program,
+        };
+        let space = if token_program == spl_token_2022::id() {
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(
+                &MINT_EXTENSIONS,
+            )
+            .unwrap()
+        } else {
+            spl_token::state::Mint::LEN
+        };
+        let create_mint = system_instruction::create_account(
+            &bench.context.payer.pubkey(),
+            &bench.mint.pubkey(),
+            Rent::default().minimum_balance(space),
+            space as u64,
+            &token_program,
+        );
+        let mint = bench.mint.insecure_clone();
+        bench.process(&[create_mint], &[&mint]).await;
+        bench
+    }
+
+    // Run `instruction` alone in a transaction and record the units it consumed under its name
+    async fn measure(
+        &mut self,
+        units: &mut Measurements,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) {
+        let discriminator = InstructionDiscriminator::try_from(instruction.data[0]).unwrap();
+        let name = if self.token_program == spl_token_2022::id() {
+            format!("{:?} (Token-2022)", discriminator)
+        } else {
+            format!("{:?}", discriminator)
+        };
+
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.context.payer.pubkey()),
+            &[&[&self.context.payer], signers].concat(),
+            blockhash,
+        );
+        let outcome = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        let metadata = outcome.metadata.unwrap();
+        if let Err(error) = outcome.result {
+            panic!(
+                "{} failed: {:?}\n{}",
+                name,
+                error,
+                metadata.log_messages.join("\n")
+            );
+        }
+        let consumed = units.entry(name).or_default();
+        *consumed = (*consumed).max(metadata.compute_units_consumed);
+    }
+
+    // Run setup instructions that are not measured
+    async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &[&[&self.context.payer], signers].concat(),
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    async fn create_token_account(&mut self, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let space = if self.token_program == spl_token_2022::id() {
+            let extensions = ExtensionType::get_required_init_account_extensions(&MINT_EXTENSIONS);
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&extensions)
+                .unwrap()
+        } else {
+            spl_token::state::Account::LEN
+        };
+        let instructions = [
+            system_instruction::create_account(
+                &self.context.payer.pubkey(),
+                &account.pubkey(),
+                Rent::default().minimum_balance(space),
+                space as u64,
+                &self.token_program,
+            ),
+            spl_token_2022::instruction::initialize_account3(
+                &self.token_program,
+                &account.pubkey(),
+                &self.mint.pubkey(),
+                owner,
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &[&account]).await;
+        account.pubkey()
+    }
+
+    async fn transfer_tokens(&mut self, source: &Pubkey, destination: &Pubkey, amount: u64) {
+        let authority = self.authority.insecure_clone();
+        let transfer = spl_token_2022::instruction::transfer_checked(
+            &self.token_program,
+            source,
+            &self.mint.pubkey(),
+            destination,
+            &authority.pubkey(),
+            &[],
+            amount,
+            aria_token::TOKEN_DECIMALS,
+        )
+        .unwrap();
+        self.process(&[transfer], &[&authority]).await;
+    }
+
+    async fn fund(&mut self, address: &Pubkey) {
+        let transfer = system_instruction::transfer(&self.context.payer.pubkey(), address, SOL);
+        self.process(&[transfer], &[]).await;
+    }
+
+    // Move the cluster clock forward
+    async fn warp_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    async fn warp_slot(&mut self) {
+        let slot = self.context.banks_client.get_root_slot().await.unwrap();
+        self.context.warp_to_slot(slot + 2).unwrap();
+    }
+}
+
+// ARIA program whose program-data account names `authority` as its upgrade authority. The program
+// runs natively unless `cargo test-sbf` points solana-program-test at the compiled program.
+fn program_test() -> (ProgramTest, Pubkey, Keypair) {
+    let program_id = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let mut program_test = ProgramTest::new(
+        "aria_token",
+        program_id,
+        processor!(aria_token::process_instruction),
+    );
+    common::add_metaplex_program(&mut program_test);
+
+    let program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(authority.pubkey()),
+    })
+    .unwrap();
+    program_test.add_account(
+        find_program_data_address(&program_id).0,
+        Account {
+            lamports: Rent::default().minimum_balance(program_data.len()),
+            data: program_data,
+            owner: bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+    program_test.add_account(
+        authority.pubkey(),
+        Account {
+            lamports: 10 * SOL,
+            ..Account::default()
+        },
+    );
+    (program_test, program_id, authority)
+}
+
+// Core, referral, treasury, governance and admin instructions on an SPL Token mint
+async fn measure_spl_token(units: &mut Measurements) {
+    let (mut program_test, program_id, authority) = program_test();
+
+    // Minter allowance written before account headers were introduced, for MigrateAccount
+    let legacy_minter = Pubkey::new_unique();
+    let legacy = MinterAllowanceV1 {
+        is_initialized: true,
+        minter: legacy_minter,
+        remaining: 1_000,
+    }
+    .try_to_vec()
+    .unwrap();
+    assert_eq!(legacy.len(), 1 + 32 + 8);
+    let (legacy_allowance, _) = find_minter_allowance_address(&program_id, &legacy_minter);
+    program_test.add_account(
+        legacy_allowance,
+        Account {
+            lamports: Rent::default().minimum_balance(legacy.len()),
+            data: legacy,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    let mut bench = Bench::start(program_test, authority, spl_token::id()).await;
+    let authority = bench.authority.insecure_clone();
+    let admin = authority.pubkey();
+    let mint = bench.mint.pubkey();
+    let token_program = bench.token_program;
+    let user = Keypair::new();
+    bench.fund(&user.pubkey()).await;
+
+    bench
+        .measure(
+            units,
+            aria_instruction::initialize_mint(
+                &program_id,
+                &admin,
+                &mint,
+                &token_program,
+                None,
+                None,
+                String::new(),
+            ),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::update_minter_allowance(
+                &program_id,
+                &admin,
+                &admin,
+                AllowanceChange::Set(TOTAL_SUPPLY),
+            ),
+            &[&authority],
+        )
+        .await;
+    let wallet = bench.create_token_account(&admin).await;
+    bench
+        .measure(
+            units,
+            aria_instruction::mint_tokens(
+                &program_id,
+                &admin,
+                &mint,
+                &wallet,
+                &token_program,
+                1_000 * TOKENS,
+            ),
+            &[&authority],
+        )
+        .await;
+
+    // Views
+    bench
+        .measure(units, aria_instruction::get_config(&program_id), &[])
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::get_remaining_mintable(&program_id, &mint, Some(&admin)),
+            &[],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::get_next_mint_time(&program_id, &admin),
+            &[],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::get_program_version(&program_id),
+            &[],
+        )
+        .await;
+
+    // Referral
+    let user_wallet = bench.create_token_account(&user.pubkey()).await;
+    let recipient = bench.create_token_account(&Pubkey::new_unique()).await;
+    bench
+        .transfer_tokens(&wallet, &user_wallet, 100 * TOKENS)
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::set_referral_share(&program_id, &admin, &mint, 500),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::register_referrer(&program_id, &user.pubkey(), &mint, &admin),
+            &[&user],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::pay_with_referral(
+                &program_id,
+                &user.pubkey(),
+                &user_wallet,
+                &recipient,
+                &admin,
+                &wallet,
+                &mint,
+                &token_program,
+                10 * TOKENS,
+            ),
+            &[&user],
+        )
+        .await;
+
+    // Treasury
+    let approver = Keypair::new();
+    let (treasury_vault, _) = find_treasury_vault_address(&program_id, &mint);
+    let treasury_wallet = bench.create_token_account(&treasury_vault).await;
+    bench
+        .transfer_tokens(&wallet, &treasury_wallet, 100 * TOKENS)
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::configure_treasury(
+                &program_id,
+                &admin,
+                &mint,
+                vec![admin, approver.pubkey()],
+                2,
+            ),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::propose_spend(
+                &program_id,
+                &admin,
+                &mint,
+                0,
+                TreasuryAsset::Token,
+                &recipient,
+                10 * TOKENS,
+            ),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::approve_spend(&program_id, &approver.pubkey(), &mint, 0),
+            &[&approver],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::execute_spend(
+                &program_id,
+                &admin,
+                &mint,
+                0,
+                Some(&treasury_wallet),
+                &recipient,
+                &token_program,
+            ),
+            &[&authority],
+        )
+        .await;
+
+    // Governance
+    let voting_period = 60;
+    let (governance_vault, _) = find_governance_vault_address(&program_id, &mint);
+    let governance_wallet = bench.create_token_account(&governance_vault).await;
+    let memo = ProposalInstruction::from(&Instruction::new_with_bytes(
+        MEMO_PROGRAM_ID,
+        b"ARIA",
+        vec![],
+    ));
+    bench
+        .measure(
+            units,
+            aria_instruction::configure_governance(
+                &program_id,
+                &admin,
+                &mint,
+                voting_period,
+                1_000,
+                5_000,
+                1,
+            ),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::deposit_governing_tokens(
+                &program_id,
+                &admin,
+                &wallet,
+                &governance_wallet,
+                &mint,
+                &token_program,
+                500 * TOKENS,
+            ),
+            &[&authority],
+        )
+        .await;
+    // Voting power counts deposits made before the proposal's snapshot slot
+    bench.warp_slot().await;
+    bench
+        .measure(
+            units,
+            aria_instruction::create_proposal(
+                &program_id,
+                &admin,
+                &mint,
+                0,
+                "Benchmark proposal".to_string(),
+                vec![memo.clone()],
+            ),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::cast_vote(&program_id, &admin, &mint, 0, Vote::For, &[]),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::set_delegate(&program_id, &admin, &mint, &user.pubkey()),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::revoke_delegate(&program_id, &admin, &mint),
+            &[&authority],
+        )
+        .await;
+    bench.warp_clock(voting_period).await;
+    bench
+        .measure(
+            units,
+            aria_instruction::execute_proposal(&program_id, &mint, 0, &[memo]),
+            &[],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::withdraw_governing_tokens(
+                &program_id,
+                &admin,
+                &wallet,
+                &governance_wallet,
+                &mint,
+                &token_program,
+                100 * TOKENS,
+            ),
+            &[&authority],
+        )
+        .await;
+
+    // Metaplex metadata
+    bench
+        .measure(
+            units,
+            aria_instruction::update_metadata(
+                &program_id,
+                &admin,
+                &mint,
+                &token_program,
+                MetadataField::Uri,
+                "https://aria.example/ari.json".to_string(),
+            ),
+            &[&authority],
+        )
+        .await;
+
+    // Administration, ending with the authority transfer
+    bench
+        .measure(
+            units,
+            aria_instruction::pause(&program_id, &admin),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::unpause(&program_id, &admin),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::grant_role(&program_id, &admin, Role::Pauser, &user.pubkey()),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::revoke_role(&program_id, &admin, Role::Pauser, &user.pubkey()),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::migrate_account(
+                &program_id,
+                &admin,
+                &legacy_allowance,
+                AccountType::MinterAllowance,
+            ),
+            &[&authority],
+        )
+        .await;
+    let new_authority = Keypair::new();
+    let propose =
+        aria_instruction::propose_authority_transfer(&program_id, &admin, &new_authority.pubkey());
+    bench.measure(units, propose.clone(), &[&authority]).await;
+    bench
+        .measure(
+            units,
+            aria_instruction::cancel_authority_transfer(&program_id, &admin),
+            &[&authority],
+        )
+        .await;
+    bench.measure(units, propose, &[&authority]).await;
+    bench
+        .measure(
+            units,
+            aria_instruction::get_pending_authority_transfer(&program_id),
+            &[],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::accept_authority_transfer(&program_id, &new_authority.pubkey()),
+            &[&new_authority],
+        )
+        .await;
+}
+
+// Transfer fee, metadata and transfer hook instructions on a Token-2022 mint
+async fn measure_token_2022(units: &mut Measurements) {
+    let (program_test, program_id, authority) = program_test();
+    let mut bench = Bench::start(program_test, authority, spl_token_2022::id()).await;
+    let authority = bench.authority.insecure_clone();
+    let admin = authority.pubkey();
+    let mint = bench.mint.pubkey();
+    let token_program = bench.token_program;
+
+    bench
+        .measure(
+            units,
+            aria_instruction::initialize_mint(
+                &program_id,
+                &admin,
+                &mint,
+                &token_program,
+                Some(TransferFeeParams {
+                    transfer_fee_basis_points: 50,
+                    maximum_fee: 5 * TOKENS,
+                }),
+                Some(program_id),
+                "https://aria.example/ari.json".to_string(),
+            ),
+            &[&authority],
+        )
+        .await;
+    let set_allowance = aria_instruction::update_minter_allowance(
+        &program_id,
+        &admin,
+        &admin,
+        AllowanceChange::Set(TOTAL_SUPPLY),
+    );
+    bench.process(&[set_allowance], &[&authority]).await;
+    let wallet = bench.create_token_account(&admin).await;
+    bench
+        .measure(
+            units,
+            aria_instruction::mint_tokens(
+                &program_id,
+                &admin,
+                &mint,
+                &wallet,
+                &token_program,
+                1_000 * TOKENS,
+            ),
+            &[&authority],
+        )
+        .await;
+
+    // Transfer fee
+    let (treasury_vault, _) = find_treasury_vault_address(&program_id, &mint);
+    let treasury_wallet = bench.create_token_account(&treasury_vault).await;
+    bench
+        .measure(
+            units,
+            aria_instruction::update_transfer_fee(
+                &program_id,
+                &admin,
+                &mint,
+                TransferFeeParams {
+                    transfer_fee_basis_points: 100,
+                    maximum_fee: 10 * TOKENS,
+                },
+            ),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::harvest_withheld_fees(&program_id, &mint, &[wallet]),
+            &[],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::withdraw_withheld_fees(&program_id, &mint, &treasury_wallet),
+            &[],
+        )
+        .await;
+
+    // Metadata
+    bench
+        .measure(
+            units,
+            aria_instruction::update_metadata(
+                &program_id,
+                &admin,
+                &mint,
+                &token_program,
+                MetadataField::Key("website".to_string()),
+                "https://aria.example".to_string(),
+            ),
+            &[&authority],
+        )
+        .await;
+
+    // Transfer hook
+    let blocked = Pubkey::new_unique();
+    bench
+        .measure(
+            units,
+            aria_instruction::initialize_transfer_hook(&program_id, &admin, &mint, &admin),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::block_address(&program_id, &admin, &mint, &blocked),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::unblock_address(&program_id, &admin, &mint, &blocked),
+            &[&authority],
+        )
+        .await;
+    bench
+        .measure(
+            units,
+            aria_instruction::set_compliance_authority(
+                &program_id,
+                &admin,
+                &mint,
+                &Pubkey::new_unique(),
+            ),
+            &[&authority],
+        )
+        .await;
+}
+
+// Whether solana-program-test loads the compiled program instead of running it natively
+fn is_sbf() -> bool {
+    std::env::var_os("SBF_OUT_DIR").is_some() || std::env::var_os("BPF_OUT_DIR").is_some()
+}
+
+fn format_baseline(units: &Measurements) -> String {
+    let mut baseline = String::from(
+        "# Compute units consumed per instruction, recorded by tests/compute_units.rs\n",
+    );
+    for (name, consumed) in units {
+        baseline.push_str(&format!("{} {}\n", name, consumed));
+    }
+    baseline
+}
+
+fn parse_baseline(baseline: &str) -> Measurements {
+    baseline
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (name, consumed) = line.rsplit_once(' ').unwrap();
+            (name.to_string(), consumed.parse().unwrap())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_compute_units() {
+    let mut units = Measurements::new();
+    measure_spl_token(&mut units).await;
+    measure_token_2022(&mut units).await;
+
+    // Every instruction is covered, except the retired ones that only fail
+    for discriminator in InstructionDiscriminator::ALL {
+        if RETIRED.contains(&discriminator) {
+            continue;
+        }
+        let name = format!("{:?}", discriminator);
+        assert!(
+            units
+                .keys()
+                .any(|measured| measured.split(' ').next() == Some(name.as_str())),
+            "{} is not benchmarked",
+            name
+        );
+    }
+
+    let sbf = is_sbf();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(BASELINE_PATH);
+    if std::env::var_os("ARIA_UPDATE_COMPUTE_UNITS").is_some() {
+        assert!(
+            sbf,
+            "record the baseline with `cargo test-sbf`, native runs are not metered"
+        );
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, format_baseline(&units)).unwrap();
+    }
+    let update_hint =
+        "record it with `ARIA_UPDATE_COMPUTE_UNITS=1 cargo test-sbf --test compute_units`";
+    let baseline = match std::fs::read_to_string(&path) {
+        Ok(baseline) => parse_baseline(&baseline),
+        Err(_) => panic!("{} is missing; {}", path.display(), update_hint),
+    };
+
+    let mut failures = Vec::new();
+    for (name, &consumed) in units.iter() {
+        let expected = baseline.get(name).copied();
+        println!("{:<40} {:>8} (baseline {:?})", name, consumed, expected);
+        // Natively only CPIs and syscalls are metered, so only the coverage is checked
+        if !sbf {
+            if expected.is_none() {
+                failures.push(format!("{} has no baseline", name));
+            }
+            continue;
+        }
+        if consumed > MAX_UNITS {
+            failures.push(format!(
+                "{} consumed {} units, above {}",
+                name, consumed, MAX_UNITS
+            ));
+        }
+        match expected {
+            Some(expected) if consumed > expected + expected * TOLERANCE_PERCENT / 100 => failures
+                .push(format!(
+                    "{} consumed {} units, more than {}% over its baseline of {}",
+                    name, consumed, TOLERANCE_PERCENT, expected
+                )),
+            Some(_) => {}
+            None => failures.push(format!("{} has no baseline", name)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "compute units regressed against {}:\n{}\n{}",
+        path.display(),
+        failures.join("\n"),
+        update_hint
+    );
+}
@@ -0,0 +1,917 @@
+// Integration tests against the Solana runtime
+// The ARIA program runs natively inside solana-program-test next to the SPL Token programs bundled
+// with it, so instructions go through real SPL Token CPIs, the runtime's account checks and the
+// client crate's instruction builders. Legacy mint metadata goes to the Metaplex stand-in in
+// `common`.
+
+mod common;
+
+use aria_token::{
+    find_config_address, find_mint_authority_address, find_program_data_address, AriaError,
+    ProgramConfig, AUTHORITY_TRANSFER_EXPIRY, MINT_COOLDOWN, TOKEN_NAME, TOKEN_SYMBOL,
+    TOTAL_SUPPLY,
+};
+use aria_token_client::instruction as aria_instruction;
+use aria_token_interface::account::{AccountType, VersionedAccount};
+use aria_token_interface::metadata::{find_metaplex_metadata_address, MetadataField};
+use aria_token_interface::roles::{
+    find_minter_allowance_address, AllowanceChange, MinterAllowance, MinterAllowanceV1,
+};
+use aria_token_interface::transfer_hook::{
+    extra_account_metas, find_blocklist_address, find_extra_account_metas_address,
+};
+use aria_token_interface::view::{
+    decode_return_data, ConfigView, PendingAuthorityTransfer, ProgramVersion,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use mpl_token_metadata::accounts::Metadata;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_token_2022::extension::{transfer_hook, ExtensionType};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use spl_type_length_value::state::TlvStateBorrowed;
+
+const SOL: u64 = 1_000_000_000;
+
+struct TestEnv {
+    context: ProgramTestContext,
+    program_id: Pubkey,
+    // Upgrade authority of the program, which initializes it
+    authority: Keypair,
+    mint: Keypair,
+    destination: Pubkey,
+}
+
+impl TestEnv {
+    // Start a validator with the ARIA program and a funded upgrade authority
+    async fn start(program_test: ProgramTest, program_id: Pubkey, authority: Keypair) -> Self {
+        let mut context = program_test.start_with_context().await;
+        let mint = Keypair::new();
+        let rent = Rent::default();
+        let create_mint = system_instruction::create_account(
+            &context.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        );
+        let payer = context.payer.insecure_clone();
+        process(&mut context, &[create_mint], &[&payer, &mint])
+            .await
+            .unwrap();
+
+        TestEnv {
+            context,
+            program_id,
+            authority,
+            mint,
+            destination: Pubkey::default(),
+        }
+    }
+
+    async fn new() -> Self {
+        let (program_test, program_id, authority) = program_test();
+        Self::start(program_test, program_id, authority).await
+    }
+
+    // Initialized program with a destination token account and a minter allowance
+    async fn initialized() -> Self {
+        let mut env = Self::new().await;
+        env.initialize().await.unwrap();
+        env.destination = env.create_token_account(&env.authority.pubkey()).await;
+        env.set_minter_allowance(TOTAL_SUPPLY).await.unwrap();
+        env
+    }
+
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        process(&mut self.context, instructions, signers).await
+    }
+
+    async fn initialize(&mut self) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        self.initialize_as(&authority).await
+    }
+
+    async fn initialize_as(&mut self, authority: &Keypair) -> Result<(), BanksClientError> {
+        let instruction = aria_instruction::initialize_mint(
+            &self.program_id,
+            &authority.pubkey(),
+            &self.mint.pubkey(),
+            &spl_token::id(),
+            None,
+            None,
+            String::new(),
+        );
+        self.process(&[instruction], &[authority]).await
+    }
+
+    async fn create_token_account(&mut self, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let payer = self.context.payer.insecure_clone();
+        let instructions = [
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &account.pubkey(),
+                Rent::default().minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                &account.pubkey(),
+                &self.mint.pubkey(),
+                owner,
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &[&payer, &account])
+            .await
+            .unwrap();
+        account.pubkey()
+    }
+
+    async fn set_minter_allowance(&mut self, amount: u64) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = aria_instruction::update_minter_allowance(
+            &self.program_id,
+            &authority.pubkey(),
+            &authority.pubkey(),
+            AllowanceChange::Set(amount),
+        );
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    async fn mint_tokens(&mut self, amount: u64) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = aria_instruction::mint_tokens(
+            &self.program_id,
+            &authority.pubkey(),
+            &self.mint.pubkey(),
+            &self.destination,
+            &spl_token::id(),
+            amount,
+        );
+        self.process(&[instruction], &[&authority]).await
+    }
+
+    async fn propose(
+        &mut self,
+        signer: &Keypair,
+        new_authority: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = aria_instruction::propose_authority_transfer(
+            &self.program_id,
+            &signer.pubkey(),
+            new_authority,
+        );
+        self.process(&[instruction], &[signer]).await
+    }
+
+    async fn accept(&mut self, signer: &Keypair) -> Result<(), BanksClientError> {
+        let instruction =
+            aria_instruction::accept_authority_transfer(&self.program_id, &signer.pubkey());
+        self.process(&[instruction], &[signer]).await
+    }
+
+    async fn cancel(&mut self, signer: &Keypair) -> Result<(), BanksClientError> {
+        let instruction =
+            aria_instruction::cancel_authority_transfer(&self.program_id, &signer.pubkey());
+        self.process(&[instruction], &[signer]).await
+    }
+
+    async fn config(&mut self) -> ProgramConfig {
+        let (config_address, _) = find_config_address(&self.program_id);
+        let account = self.account(&config_address).await.unwrap();
+        ProgramConfig::unpack(&account.data).unwrap()
+    }
+
+    async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+    }
+
+    async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+        let account = self.account(address).await.unwrap();
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    // Metaplex metadata of the mint, without the padding of its strings
+    async fn metaplex_metadata(&mut self) -> (String, String, String) {
+        let (address, _) = find_metaplex_metadata_address(&self.mint.pubkey());
+        let account = self.account(&address).await.unwrap();
+        let metadata = Metadata::from_bytes(&account.data).unwrap();
+        let trim = |value: &str| value.trim_end_matches('\0').to_string();
+        (
+            trim(&metadata.name),
+            trim(&metadata.symbol),
+            trim(&metadata.uri),
+        )
+    }
+
+    async fn mint_state(&mut self) -> spl_token::state::Mint {
+        let mint = self.mint.pubkey();
+        let account = self.account(&mint).await.unwrap();
+        spl_token::state::Mint::unpack(&account.data).unwrap()
+    }
+
+    // Simulate a view instruction and decode its return data
+    async fn view<T: BorshDeserialize>(&mut self, instruction: Instruction) -> T {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer],
+            blockhash,
+        );
+        let simulation = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await
+            .unwrap();
+        assert_eq!(simulation.result, Some(Ok(())));
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        decode_return_data(&self.program_id, &return_data.program_id, &return_data.data).unwrap()
+    }
+
+    // Move the cluster clock forward
+    async fn warp_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+}
+
+// ARIA program whose program-data account names `authority` as its upgrade authority
+fn program_test() -> (ProgramTest, Pubkey, Keypair) {
+    let program_id = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let mut program_test = ProgramTest::new(
+        "aria_token",
+        program_id,
+        processor!(aria_token::process_instruction),
+    );
+    program_test.prefer_bpf(false);
+    common::add_metaplex_program(&mut program_test);
+
+    let program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(authority.pubkey()),
+    })
+    .unwrap();
+    program_test.add_account(
+        find_program_data_address(&program_id).0,
+        Account {
+            lamports: Rent::default().minimum_balance(program_data.len()),
+            data: program_data,
+            owner: bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+    program_test.add_account(
+        authority.pubkey(),
+        Account {
+            lamports: 10 * SOL,
+            ..Account::default()
+        },
+    );
+    (program_test, program_id, authority)
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    // A fresh blockhash keeps repeated identical transactions from being deduplicated
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &[&[&context.payer], signers].concat(),
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+#[track_caller]
+fn assert_aria_error(result: Result<(), BanksClientError>, expected: AriaError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, expected as u32, "expected {:?}", expected)
+        }
+        error => panic!("expected {:?}, got {:?}", expected, error),
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_mint() {
+    let mut env = TestEnv::new().await;
+    env.initialize().await.unwrap();
+
+    let config = env.config().await;
+    assert_eq!(config.mint, env.mint.pubkey());
+    assert_eq!(config.authority, env.authority.pubkey());
+    assert_eq!(config.last_mint_time, 0);
+
+    // The SPL mint is controlled by the program's mint authority PDA
+    let mint = env.mint_state().await;
+    let (mint_authority, _) = find_mint_authority_address(&env.program_id, &env.mint.pubkey());
+    assert_eq!(mint.mint_authority, Some(mint_authority).into());
+    assert_eq!(mint.freeze_authority, Some(env.authority.pubkey()).into());
+    assert_eq!(mint.supply, 0);
+
+    let result = env.initialize().await;
+    assert_aria_error(result, AriaError::AccountAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn test_legacy_mint_metadata() {
+    let mut env = TestEnv::initialized().await;
+    let (name, symbol, uri) = env.metaplex_metadata().await;
+    assert_eq!(name, TOKEN_NAME);
+    assert_eq!(symbol, TOKEN_SYMBOL);
+    assert_eq!(uri, "");
+
+    let authority = env.authority.insecure_clone();
+    let update = |field, value: &str| {
+        aria_instruction::update_metadata(
+            &env.program_id,
+            &authority.pubkey(),
+            &env.mint.pubkey(),
+            &spl_token::id(),
+            field,
+            value.to_string(),
+        )
+    };
+    let set_uri = update(MetadataField::Uri, "https://aria.example/ari.json");
+    let set_key = update(
+        MetadataField::Key("website".to_string()),
+        "https://aria.example",
+    );
+    env.process(&[set_uri], &[&authority]).await.unwrap();
+    let (name, _, uri) = env.metaplex_metadata().await;
+    assert_eq!(name, TOKEN_NAME);
+    assert_eq!(uri, "https://aria.example/ari.json");
+
+    // Metaplex metadata has no additional fields
+    let result = env.process(&[set_key], &[&authority]).await;
+    assert_aria_error(result, AriaError::MetadataFieldUnsupported);
+}
+
+#[tokio::test]
+async fn test_initialize_mint_requires_upgrade_authority() {
+    let mut env = TestEnv::new().await;
+    let intruder = env.context.payer.insecure_clone();
+    let result = env.initialize_as(&intruder).await;
+    assert_aria_error(result, AriaError::InsufficientAuthority);
+
+    env.initialize().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_mint_tokens() {
+    let mut env = TestEnv::initialized().await;
+    env.mint_tokens(5_000).await.unwrap();
+
+    let destination = env.destination;
+    assert_eq!(env.token_balance(&destination).await, 5_000);
+    assert_eq!(env.mint_state().await.supply, 5_000);
+    assert_ne!(env.config().await.last_mint_time, 0);
+
+    let (allowance_address, _) =
+        find_minter_allowance_address(&env.program_id, &env.authority.pubkey());
+    let allowance = env.account(&allowance_address).await.unwrap();
+    let allowance = MinterAllowance::unpack(&allowance.data).unwrap();
+    assert_eq!(allowance.remaining, TOTAL_SUPPLY - 5_000);
+}
+
+#[tokio::test]
+async fn test_mint_tokens_cooldown() {
+    let mut env = TestEnv::initialized().await;
+    env.mint_tokens(1_000).await.unwrap();
+
+    let result = env.mint_tokens(1_000).await;
+    assert_aria_error(result, AriaError::MintCooldownActive);
+
+    env.warp_clock(MINT_COOLDOWN - 10).await;
+    let result = env.mint_tokens(1_000).await;
+    assert_aria_error(result, AriaError::MintCooldownActive);
+
+    env.warp_clock(10).await;
+    env.mint_tokens(1_000).await.unwrap();
+    let destination = env.destination;
+    assert_eq!(env.token_balance(&destination).await, 2_000);
+}
+
+#[tokio::test]
+async fn test_mint_tokens_supply_cap() {
+    let mut env = TestEnv::initialized().await;
+    let result = env.mint_tokens(TOTAL_SUPPLY + 1).await;
+    assert_aria_error(result, AriaError::ExceedsSupplyCap);
+
+    env.mint_tokens(TOTAL_SUPPLY).await.unwrap();
+    assert_eq!(env.mint_state().await.supply, TOTAL_SUPPLY);
+
+    env.warp_clock(MINT_COOLDOWN).await;
+    env.set_minter_allowance(1).await.unwrap();
+    let result = env.mint_tokens(1).await;
+    assert_aria_error(result, AriaError::ExceedsSupplyCap);
+}
+
+#[tokio::test]
+async fn test_mint_tokens_allowance_and_roles() {
+    let mut env = TestEnv::initialized().await;
+    env.set_minter_allowance(100).await.unwrap();
+    let result = env.mint_tokens(101).await;
+    assert_aria_error(result, AriaError::MinterAllowanceExceeded);
+
+    // Only role holders can mint
+    let intruder = Keypair::new();
+    let instruction = aria_instruction::mint_tokens(
+        &env.program_id,
+        &intruder.pubkey(),
+        &env.mint.pubkey(),
+        &env.destination,
+        &spl_token::id(),
+        1,
+    );
+    let result = env.process(&[instruction], &[&intruder]).await;
+    assert_aria_error(result, AriaError::InsufficientAuthority);
+}
+
+#[tokio::test]
+async fn test_mint_tokens_paused() {
+    let mut env = TestEnv::initialized().await;
+    let authority = env.authority.insecure_clone();
+    let pause = aria_instruction::pause(&env.program_id, &authority.pubkey());
+    env.process(&[pause], &[&authority]).await.unwrap();
+
+    let result = env.mint_tokens(1_000).await;
+    assert_aria_error(result, AriaError::ProgramPaused);
+
+    let unpause = aria_instruction::unpause(&env.program_id, &authority.pubkey());
+    env.process(&[unpause], &[&authority]).await.unwrap();
+    env.mint_tokens(1_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_mint_tokens_account_validation() {
+    let mut env = TestEnv::initialized().await;
+    let authority = env.authority.pubkey();
+
+    // Destination of another mint
+    let other_mint = Keypair::new();
+    let payer = env.context.payer.insecure_clone();
+    let instructions = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &other_mint.pubkey(),
+            Rent::default().minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            &other_mint.pubkey(),
+            &authority,
+            None,
+            9,
+        )
+        .unwrap(),
+    ];
+    env.process(&instructions, &[&payer, &other_mint])
+        .await
+        .unwrap();
+    let mut instruction = aria_instruction::mint_tokens(
+        &env.program_id,
+        &authority,
+        &other_mint.pubkey(),
+        &env.destination,
+        &spl_token::id(),
+        1,
+    );
+    let signer = env.authority.insecure_clone();
+    let result = env.process(&[instruction.clone()], &[&signer]).await;
+    assert_aria_error(result, AriaError::TokenMintMismatch);
+
+    // Read-only destination
+    instruction = aria_instruction::mint_tokens(
+        &env.program_id,
+        &authority,
+        &env.mint.pubkey(),
+        &env.destination,
+        &spl_token::id(),
+        1,
+    );
+    instruction.accounts[2].is_writable = false;
+    let result = env.process(&[instruction.clone()], &[&signer]).await;
+    assert_aria_error(result, AriaError::AccountNotWritable);
+
+    // Rent sysvar in place of the clock
+    instruction.accounts[2].is_writable = true;
+    instruction.accounts[4].pubkey = solana_sdk::sysvar::rent::id();
+    let result = env.process(&[instruction], &[&signer]).await;
+    assert_aria_error(result, AriaError::InvalidSysvar);
+}
+
+#[tokio::test]
+async fn test_authority_transfer_lifecycle() {
+    let mut env = TestEnv::initialized().await;
+    let authority = env.authority.insecure_clone();
+    let new_authority = Keypair::new();
+
+    // Nothing to accept or cancel yet
+    let result = env.accept(&new_authority).await;
+    assert_aria_error(result, AriaError::AuthorityTransferNotFound);
+    let result = env.cancel(&authority).await;
+    assert_aria_error(result, AriaError::AuthorityTransferNotFound);
+
+    // Only the current authority can propose
+    let result = env.propose(&new_authority, &new_authority.pubkey()).await;
+    assert_aria_error(result, AriaError::InsufficientAuthority);
+
+    env.propose(&authority, &new_authority.pubkey())
+        .await
+        .unwrap();
+    let config = env.config().await;
+    assert_eq!(
+        config.authority_transfer.proposed_authority,
+        Some(new_authority.pubkey())
+    );
+
+    // Only the proposed authority can accept
+    let result = env.accept(&Keypair::new()).await;
+    assert_aria_error(result, AriaError::AuthorityTransferNotFound);
+
+    env.accept(&new_authority).await.unwrap();
+    let config = env.config().await;
+    assert_eq!(config.authority, new_authority.pubkey());
+    assert_eq!(config.authority_transfer.proposed_authority, None);
+
+    // The previous authority lost its rights
+    let result = env.propose(&authority, &authority.pubkey()).await;
+    assert_aria_error(result, AriaError::InsufficientAuthority);
+    env.propose(&new_authority, &authority.pubkey())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_authority_transfer_cancel() {
+    let mut env = TestEnv::initialized().await;
+    let authority = env.authority.insecure_clone();
+    let new_authority = Keypair::new();
+
+    env.propose(&authority, &new_authority.pubkey())
+        .await
+        .unwrap();
+    let result = env.cancel(&new_authority).await;
+    assert_aria_error(result, AriaError::InsufficientAuthority);
+    env.cancel(&authority).await.unwrap();
+
+    let result = env.accept(&new_authority).await;
+    assert_aria_error(result, AriaError::AuthorityTransferNotFound);
+    assert_eq!(env.config().await.authority, authority.pubkey());
+}
+
+#[tokio::test]
+async fn test_authority_transfer_expiry() {
+    let mut env = TestEnv::initialized().await;
+    let authority = env.authority.insecure_clone();
+    let new_authority = Keypair::new();
+
+    env.propose(&authority, &new_authority.pubkey())
+        .await
+        .unwrap();
+    env.warp_clock(AUTHORITY_TRANSFER_EXPIRY + 1).await;
+    let result = env.accept(&new_authority).await;
+    assert_aria_error(result, AriaError::AuthorityTransferExpired);
+
+    // A new proposal restarts the expiry window
+    env.propose(&authority, &new_authority.pubkey())
+        .await
+        .unwrap();
+    env.warp_clock(AUTHORITY_TRANSFER_EXPIRY).await;
+    env.accept(&new_authority).await.unwrap();
+    assert_eq!(env.config().await.authority, new_authority.pubkey());
+}
+
+#[tokio::test]
+async fn test_view_instructions() {
+    let mut env = TestEnv::initialized().await;
+    let program_id = env.program_id;
+    let mint = env.mint.pubkey();
+    let authority = env.authority.insecure_clone();
+
+    let version: ProgramVersion = env
+        .view(aria_instruction::get_program_version(&program_id))
+        .await;
+    assert_eq!(
+        version,
+        ProgramVersion {
+            major: env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+            minor: env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+            patch: env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+        }
+    );
+    let expected = ConfigView {
+        mint,
+        authority: authority.pubkey(),
+        paused: false,
+        last_mint_time: 0,
+    };
+    assert_eq!(
+        env.view::<ConfigView>(aria_instruction::get_config(&program_id))
+            .await,
+        expected
+    );
+    let next_mint_time: i64 = env
+        .view(aria_instruction::get_next_mint_time(
+            &program_id,
+            &authority.pubkey(),
+        ))
+        .await;
+    assert_eq!(next_mint_time, 0);
+
+    env.mint_tokens(5_000).await.unwrap();
+    env.set_minter_allowance(100).await.unwrap();
+    let last_mint_time = env.config().await.last_mint_time;
+    let next_mint_time: i64 = env
+        .view(aria_instruction::get_next_mint_time(
+            &program_id,
+            &authority.pubkey(),
+        ))
+        .await;
+    assert_eq!(next_mint_time, last_mint_time + MINT_COOLDOWN);
+
+    // The cooldown is per minter
+    let next_mint_time: i64 = env
+        .view(aria_instruction::get_next_mint_time(
+            &program_id,
+            &Pubkey::new_unique(),
+        ))
+        .await;
+    assert_eq!(next_mint_time, 0);
+
+    // Supply left under the cap, capped by the minter's allowance
+    let remaining: u64 = env
+        .view(aria_instruction::get_remaining_mintable(
+            &program_id,
+            &mint,
+            None,
+        ))
+        .await;
+    assert_eq!(remaining, TOTAL_SUPPLY - 5_000);
+    let remaining: u64 = env
+        .view(aria_instruction::get_remaining_mintable(
+            &program_id,
+            &mint,
+            Some(&authority.pubkey()),
+        ))
+        .await;
+    assert_eq!(remaining, 100);
+    let remaining: u64 = env
+        .view(aria_instruction::get_remaining_mintable(
+            &program_id,
+            &mint,
+            Some(&Pubkey::new_unique()),
+        ))
+        .await;
+    assert_eq!(remaining, 0);
+    let other_mint = Pubkey::new_unique();
+    let instruction = aria_instruction::get_remaining_mintable(&program_id, &other_mint, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_aria_error(result, AriaError::TokenMintMismatch);
+
+    // Views keep working while paused
+    let pause = aria_instruction::pause(&program_id, &authority.pubkey());
+    env.process(&[pause], &[&authority]).await.unwrap();
+    let config: ConfigView = env.view(aria_instruction::get_config(&program_id)).await;
+    assert_eq!(
+        config,
+        ConfigView {
+            paused: true,
+            last_mint_time,
+            ..expected
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_view_pending_authority_transfer() {
+    let mut env = TestEnv::initialized().await;
+    let authority = env.authority.insecure_clone();
+    let new_authority = Keypair::new();
+    let get_pending = aria_instruction::get_pending_authority_transfer(&env.program_id);
+
+    let pending: Option<PendingAuthorityTransfer> = env.view(get_pending.clone()).await;
+    assert_eq!(pending, None);
+
+    env.propose(&authority, &new_authority.pubkey())
+        .await
+        .unwrap();
+    let proposal_time = env.config().await.authority_transfer.proposal_time;
+    let pending: Option<PendingAuthorityTransfer> = env.view(get_pending.clone()).await;
+    assert_eq!(
+        pending,
+        Some(PendingAuthorityTransfer {
+            proposed_authority: new_authority.pubkey(),
+            proposal_time,
+            expires_at: proposal_time + AUTHORITY_TRANSFER_EXPIRY,
+        })
+    );
+
+    // An expired proposal can no longer be accepted
+    env.warp_clock(AUTHORITY_TRANSFER_EXPIRY + 1).await;
+    let pending: Option<PendingAuthorityTransfer> = env.view(get_pending).await;
+    assert_eq!(pending, None);
+}
+
+#[tokio::test]
+async fn test_unknown_instruction() {
+    let mut env = TestEnv::initialized().await;
+    let instruction = Instruction::new_with_bytes(env.program_id, &[u8::MAX], vec![]);
+    let result = env.process(&[instruction], &[]).await;
+    assert_aria_error(result, AriaError::UnknownInstruction);
+}
+
+#[tokio::test]
+async fn test_migrate_unversioned_account() {
+    let (mut program_test, program_id, authority) = program_test();
+
+    // Minter allowance written before account headers were introduced
+    let legacy = MinterAllowanceV1 {
+        is_initialized: true,
+        minter: authority.pubkey(),
+        remaining: 7_000,
+    }
+    .try_to_vec()
+    .unwrap();
+    assert_eq!(legacy.len(), 1 + 32 + 8);
+    let (allowance_address, _) = find_minter_allowance_address(&program_id, &authority.pubkey());
+    program_test.add_account(
+        allowance_address,
+        Account {
+            lamports: Rent::default().minimum_balance(legacy.len()),
+            data: legacy,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let mut env = TestEnv::start(program_test, program_id, authority).await;
+    env.initialize().await.unwrap();
+    let authority_key = env.authority.pubkey();
+    env.destination = env.create_token_account(&authority_key).await;
+
+    let result = env.mint_tokens(1_000).await;
+    assert_aria_error(result, AriaError::AccountVersionMismatch);
+
+    // Anyone can migrate the account and pays the extra rent
+    let payer = Keypair::new();
+    let fund = system_instruction::transfer(&env.context.payer.pubkey(), &payer.pubkey(), SOL);
+    env.process(&[fund], &[]).await.unwrap();
+    let migrate = aria_instruction::migrate_account(
+        &env.program_id,
+        &payer.pubkey(),
+        &allowance_address,
+        AccountType::MinterAllowance,
+    );
+    env.process(std::slice::from_ref(&migrate), &[&payer])
+        .await
+        .unwrap();
+
+    let account = env.account(&allowance_address).await.unwrap();
+    assert_eq!(account.data.len(), MinterAllowance::LEN);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(MinterAllowance::LEN)
+    );
+    assert_eq!(
+        MinterAllowance::unpack(&account.data).unwrap().remaining,
+        7_000
+    );
+
+    // Migrating again is a no-op, and the migrated allowance is usable
+    env.process(&[migrate], &[&payer]).await.unwrap();
+    env.mint_tokens(1_000).await.unwrap();
+    let destination = env.destination;
+    assert_eq!(env.token_balance(&destination).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_extra_account_meta_list() {
+    let (program_test, program_id, authority) = program_test();
+    let mut env = TestEnv::start(program_test, program_id, authority).await;
+    let authority = env.authority.insecure_clone();
+    let payer = env.context.payer.pubkey();
+
+    // Token-2022 mint naming the program as its transfer hook
+    let mint = Keypair::new();
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::TransferHook,
+    ])
+    .unwrap();
+    let create_mint = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            Rent::default().minimum_balance(space),
+            space as u64,
+            &spl_token_2022::id(),
+        ),
+        transfer_hook::instruction::initialize(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            None,
+            Some(program_id),
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            &authority.pubkey(),
+            Some(&authority.pubkey()),
+            9,
+        )
+        .unwrap(),
+    ];
+    env.process(&create_mint, &[&mint]).await.unwrap();
+
+    // The caller funds the extra-account-metas PDA, as the interface passes no payer
+    let mint = mint.pubkey();
+    let (extra_metas, _) = find_extra_account_metas_address(&program_id, &mint);
+    let rent = |len| Rent::default().minimum_balance(ExtraAccountMetaList::size_of(len).unwrap());
+    let initialize = aria_instruction::initialize_extra_account_meta_list(
+        &program_id,
+        &authority.pubkey(),
+        &mint,
+    );
+    let fund = system_instruction::transfer(&payer, &extra_metas, rent(1));
+    env.process(&[fund, initialize.clone()], &[&authority])
+        .await
+        .unwrap();
+    let result = env.process(&[initialize], &[&authority]).await;
+    assert_aria_error(result, AriaError::AccountAlreadyInitialized);
+
+    // InitializeTransferHook then only creates the blocklist
+    let initialize_hook = aria_instruction::initialize_transfer_hook(
+        &program_id,
+        &authority.pubkey(),
+        &mint,
+        &authority.pubkey(),
+    );
+    env.process(&[initialize_hook], &[&authority])
+        .await
+        .unwrap();
+    let (blocklist, _) = find_blocklist_address(&program_id, &mint);
+    assert!(env.account(&blocklist).await.is_some());
+
+    let mut metas = extra_account_metas().unwrap();
+    metas.push(ExtraAccountMeta::new_with_pubkey(&Pubkey::new_unique(), false, false).unwrap());
+    let intruder = Keypair::new();
+    let update = aria_instruction::update_extra_account_meta_list(
+        &program_id,
+        &intruder.pubkey(),
+        &mint,
+        &metas,
+    );
+    let result = env.process(&[update], &[&intruder]).await;
+    assert_aria_error(result, AriaError::InsufficientAuthority);
+
+    let fund = system_instruction::transfer(&payer, &extra_metas, rent(2) - rent(1));
+    let update = aria_instruction::update_extra_account_meta_list(
+        &program_id,
+        &authority.pubkey(),
+        &mint,
+        &metas,
+    );
+    env.process(&[fund, update], &[&authority]).await.unwrap();
+
+    let account = env.account(&extra_metas).await.unwrap();
+    assert_eq!(account.owner, program_id);
+    let state = TlvStateBorrowed::unpack(&account.data).unwrap();
+    let stored = ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&state).unwrap();
+    assert_eq!(stored.data(), metas.as_slice());
+}